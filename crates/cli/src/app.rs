@@ -1,9 +1,12 @@
 use crate::commands::{
     debug::DebugConfigArgs,
+    plugin::{OutdatedPluginsArgs, UpdatePluginsArgs},
+    shims::CheckShimsArgs,
     tool::{AddToolArgs, ListToolPluginsArgs, ListToolsArgs, RemoveToolArgs, ToolInfoArgs},
-    AliasArgs, BinArgs, CleanArgs, CompletionsArgs, InstallArgs, InstallGlobalArgs, ListArgs,
-    ListGlobalArgs, ListRemoteArgs, MigrateArgs, OutdatedArgs, PinArgs, RunArgs, SetupArgs,
-    UnaliasArgs, UninstallArgs, UninstallGlobalArgs,
+    ActivateArgs, AliasArgs, BinArgs, CleanArgs, CompletionsArgs, DedupeArgs, DiskUsageArgs,
+    DoctorArgs, EnvArgs, ExportArgs, InstallArgs, InstallGlobalArgs, ListArgs, ListGlobalArgs,
+    ListRemoteArgs, MigrateArgs, OutdatedArgs, PinArgs, ProjectsArgs, RegenArgs, RunArgs,
+    SetupArgs, ShellArgs, UnaliasArgs, UninstallArgs, UninstallGlobalArgs, WhichArgs,
 };
 use clap::builder::styling::{Color, Style, Styles};
 use clap::{Parser, Subcommand, ValueEnum};
@@ -76,12 +79,26 @@ pub struct App {
     )]
     pub log: Option<LogLevel>,
 
+    #[arg(
+        long,
+        global = true,
+        help = "Seconds a plugin function call can run before it's interrupted (0 disables)"
+    )]
+    pub plugin_timeout: Option<u64>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
 #[derive(Clone, Debug, Subcommand)]
 pub enum Commands {
+    #[command(
+        name = "activate",
+        about = "Activate proto for the current shell and directory.",
+        long_about = "Activate proto for the current shell by printing a hook that runs on every prompt. The hook\nprepends the bin directories of the configured (and installed) tools to PATH, bypassing shims.\n\nAdd `eval \"$(proto activate bash)\"` to your shell profile."
+    )]
+    Activate(ActivateArgs),
+
     #[command(
         alias = "a",
         name = "alias",
@@ -115,6 +132,41 @@ pub enum Commands {
         command: DebugCommands,
     },
 
+    #[command(
+        name = "dedupe",
+        about = "Deduplicate identical files across installed versions.",
+        long_about = "Deduplicate identical files across installed tool versions, by hard linking them\nto a shared store in ~/.proto/store. Files are only removed from the store once\nno longer used by any installed version."
+    )]
+    Dedupe(DedupeArgs),
+
+    #[command(
+        name = "doctor",
+        about = "Check the proto environment for common problems.",
+        long_about = "Check the proto environment for common problems, including PATH order, shims,\nmanifests, installed executables, plugins, and configuration. Exits with a non-zero code\nif any check fails."
+    )]
+    Doctor(DoctorArgs),
+
+    #[command(
+        name = "du",
+        about = "Report disk usage of tools, versions, and caches.",
+        long_about = "Report the disk usage of each installed tool, broken down by version and globals,\nand of the plugin and temporary caches in ~/.proto."
+    )]
+    Du(DiskUsageArgs),
+
+    #[command(
+        name = "env",
+        about = "Print environment exports for the provided tools.",
+        long_about = "Print shell-specific environment exports for the provided tools (tool@version),\nor the tools configured in .prototools if none provided. Missing tools will be installed,\nbut no versions will be pinned."
+    )]
+    Env(EnvArgs),
+
+    #[command(
+        name = "export",
+        about = "Export configured tools to another toolchain format.",
+        long_about = "Export configured tools to another toolchain format. Versions are resolved from the closest .prototools\nand written as an equivalent .tool-versions, devcontainer, Dockerfile, GitHub Actions, or Nix definition."
+    )]
+    Export(ExportArgs),
+
     #[command(
         alias = "i",
         name = "install",
@@ -175,6 +227,26 @@ pub enum Commands {
     )]
     Pin(PinArgs),
 
+    #[command(name = "plugin", about = "Operations for managing plugins.")]
+    Plugin {
+        #[command(subcommand)]
+        command: PluginCommands,
+    },
+
+    #[command(
+        name = "projects",
+        about = "List projects that have been recorded.",
+        long_about = "List projects whose .prototools have been loaded, and the versions they pin.\nVersions pinned by these projects will not be removed by `proto clean`, and\nprojects that no longer exist will be removed from the registry."
+    )]
+    Projects(ProjectsArgs),
+
+    #[command(
+        name = "regen",
+        about = "Regenerate all shims and binaries.",
+        long_about = "Regenerate all shims in ~/.proto/shims and binaries in ~/.proto/bin from scratch.\nOrphaned files that are not owned by an installed tool will be removed, and the shim registry rewritten."
+    )]
+    Regen(RegenArgs),
+
     #[command(
         alias = "r",
         name = "run",
@@ -186,6 +258,19 @@ pub enum Commands {
     #[command(name = "setup", about = "Setup proto for your current shell.")]
     Setup(SetupArgs),
 
+    #[command(
+        name = "shell",
+        about = "Start a subshell with the provided tools.",
+        long_about = "Start a subshell with the bin directories and environment variables of the provided tools (tool@version),\nor the tools configured in .prototools if none provided. Missing tools will be installed,\nbut no versions will be pinned."
+    )]
+    Shell(ShellArgs),
+
+    #[command(name = "shims", about = "Operations for managing shims.")]
+    Shims {
+        #[command(subcommand)]
+        command: ShimsCommands,
+    },
+
     #[command(name = "tool", about = "Operations for managing tools and plugins.")]
     Tool {
         #[command(subcommand)]
@@ -223,6 +308,14 @@ pub enum Commands {
         about = "Download and install all tools from the closest .prototools."
     )]
     Use,
+
+    #[command(
+        alias = "explain",
+        name = "which",
+        about = "Explain how a tool or shim is resolved to an executable.",
+        long_about = "Explain how a tool or shim is resolved to an executable, including the matching shim,\nwhere the version was detected from, how aliases were expanded, and the final executable."
+    )]
+    Which(WhichArgs),
 }
 
 #[derive(Clone, Debug, Subcommand)]
@@ -237,6 +330,35 @@ pub enum DebugCommands {
     Env,
 }
 
+#[derive(Clone, Debug, Subcommand)]
+pub enum PluginCommands {
+    #[command(
+        name = "outdated",
+        about = "Check for newer releases of plugins.",
+        long_about = "Check for newer releases of plugins that are pinned to a GitHub release tag,
+either with a github: locator, or a source: URL to a release asset."
+    )]
+    Outdated(OutdatedPluginsArgs),
+
+    #[command(
+        name = "update",
+        about = "Download plugins again, ignoring the cache.",
+        long_about = "Download plugins again, ignoring the cache, and report the previous and new plugin versions.
+If no IDs are provided, all configured plugins that are in use will be updated."
+    )]
+    Update(UpdatePluginsArgs),
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum ShimsCommands {
+    #[command(
+        name = "check",
+        about = "Check for shims and binaries that are shadowed in PATH.",
+        long_about = "Check for shims and binaries that are shadowed by an executable of the same name,\nin a directory that appears earlier in PATH, and the shell profile likely responsible."
+    )]
+    Check(CheckShimsArgs),
+}
+
 #[derive(Clone, Debug, Subcommand)]
 pub enum ToolCommands {
     #[command(
@@ -0,0 +1,294 @@
+use crate::error::ProtoCliError;
+use crate::helpers::ProtoResource;
+use crate::shell::ShellType;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{get_proto_version, ProtoEnvironment};
+use serde::{Deserialize, Serialize};
+use starbase::system;
+use starbase_utils::{fs, json};
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use tracing::debug;
+
+// Tracks what the previous activation changed, so that it can be reverted
+// when changing to a directory with different tools
+const ACTIVATED_PATHS: &str = "_PROTO_ACTIVATED_PATHS";
+const ACTIVATED_VARS: &str = "_PROTO_ACTIVATED_VARS";
+
+#[derive(Args, Clone, Debug)]
+pub struct ActivateArgs {
+    #[arg(required = true, help = "Shell to activate for")]
+    shell: ShellType,
+
+    #[arg(
+        long,
+        hide = true,
+        help = "Print exports for the current directory instead of the hook"
+    )]
+    export: bool,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct ActivateCache {
+    paths: Vec<PathBuf>,
+    vars: Vec<(String, String)>,
+}
+
+fn get_modified_time(path: &PathBuf) -> u128 {
+    fs::metadata(path)
+        .ok()
+        .and_then(|meta| meta.modified().ok())
+        .and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default()
+}
+
+// The cache is keyed by the loaded config files and their modified times,
+// and the tool inventories (which change when installing or uninstalling)
+fn create_cache_key(proto: &ProtoEnvironment) -> miette::Result<String> {
+    let manager = proto.load_config_manager()?;
+    let config = manager.get_merged_config()?;
+    let mut hasher = DefaultHasher::new();
+
+    get_proto_version().to_string().hash(&mut hasher);
+
+    for file in &manager.files {
+        if file.exists {
+            file.path.hash(&mut hasher);
+            get_modified_time(&file.path).hash(&mut hasher);
+        }
+    }
+
+    for id in config.versions.keys() {
+        let tool_dir = proto.tools_dir.join(id.as_str());
+
+        tool_dir.hash(&mut hasher);
+        get_modified_time(&tool_dir).hash(&mut hasher);
+    }
+
+    Ok(format!("{:x}", hasher.finish()))
+}
+
+async fn load_activate_cache(proto: &ProtoResource) -> miette::Result<ActivateCache> {
+    let cache_dir = proto.env.temp_dir.join("activate");
+    let cache_path = cache_dir.join(format!("{}.json", create_cache_key(&proto.env)?));
+
+    if cache_path.exists() {
+        if let Ok(cache) = json::read_file(&cache_path) {
+            debug!(cache = ?cache_path, "Loading activated environment from cache");
+
+            return Ok(cache);
+        }
+    }
+
+    let config = proto.env.load_config()?;
+    let mut cache = ActivateCache::default();
+
+    for (id, version) in &config.versions {
+        let mut tool = proto.load_tool(id).await?;
+
+        // Only activate installed tools, as installing
+        // on every directory change would be too slow
+        if !tool.is_setup(version).await? {
+            debug!(
+                tool = id.as_str(),
+                "Tool has not been installed, skipping activation"
+            );
+
+            continue;
+        }
+
+        for dir in tool.get_bin_dirs()? {
+            if !cache.paths.contains(&dir) {
+                cache.paths.push(dir);
+            }
+        }
+
+        cache.vars.push((
+            format!("{}_VERSION", tool.get_env_var_prefix()),
+            tool.get_resolved_version().to_string(),
+        ));
+    }
+
+    // Entries are never reused once a config changes, so remove old entries
+    // when creating a new one, but don't fail activation if they can't be removed
+    if cache_dir.exists() {
+        let _ = fs::remove_dir_stale_contents(&cache_dir, Duration::from_secs(86400));
+    }
+
+    json::write_file(&cache_path, &cache, false)?;
+
+    Ok(cache)
+}
+
+fn quote_value(shell: &ShellType, value: &str) -> String {
+    match shell {
+        ShellType::Fish => format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'")),
+        _ => format!("'{}'", value.replace('\'', "'\\''")),
+    }
+}
+
+fn format_export(shell: &ShellType, key: &str, value: &str) -> String {
+    match shell {
+        ShellType::Fish => {
+            if key == "PATH" {
+                let paths = env::split_paths(value)
+                    .map(|path| quote_value(shell, &path.to_string_lossy()))
+                    .collect::<Vec<_>>();
+
+                format!("set -gx PATH {};", paths.join(" "))
+            } else {
+                format!("set -gx {key} {};", quote_value(shell, value))
+            }
+        }
+        _ => format!("export {key}={};", quote_value(shell, value)),
+    }
+}
+
+fn format_unset(shell: &ShellType, key: &str) -> String {
+    match shell {
+        ShellType::Fish => format!("set -e {key};"),
+        _ => format!("unset {key};"),
+    }
+}
+
+fn export_env(shell: &ShellType, cache: ActivateCache) -> miette::Result<String> {
+    let mut lines = vec![];
+
+    // Remove paths and variables from the previous activation
+    let prev_paths = env::var_os(ACTIVATED_PATHS)
+        .map(|value| env::split_paths(&value).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let base_paths = env::var_os("PATH")
+        .map(|value| env::split_paths(&value).collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|path| !prev_paths.contains(path))
+        .collect::<Vec<_>>();
+
+    if let Ok(prev_vars) = env::var(ACTIVATED_VARS) {
+        for key in prev_vars.split(',') {
+            if !key.is_empty() && !cache.vars.iter().any(|(k, _)| k == key) {
+                lines.push(format_unset(shell, key));
+            }
+        }
+    }
+
+    // Then apply the current activation
+    let mut paths = cache.paths.clone();
+    paths.extend(base_paths);
+
+    lines.push(format_export(
+        shell,
+        "PATH",
+        &env::join_paths(paths).into_diagnostic()?.to_string_lossy(),
+    ));
+
+    for (key, value) in &cache.vars {
+        lines.push(format_export(shell, key, value));
+    }
+
+    if cache.paths.is_empty() {
+        lines.push(format_unset(shell, ACTIVATED_PATHS));
+    } else {
+        lines.push(format_export(
+            shell,
+            ACTIVATED_PATHS,
+            &env::join_paths(&cache.paths)
+                .into_diagnostic()?
+                .to_string_lossy(),
+        ));
+    }
+
+    if cache.vars.is_empty() {
+        lines.push(format_unset(shell, ACTIVATED_VARS));
+    } else {
+        lines.push(format_export(
+            shell,
+            ACTIVATED_VARS,
+            &cache
+                .vars
+                .iter()
+                .map(|(key, _)| key.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+        ));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+fn render_hook(shell: &ShellType) -> miette::Result<String> {
+    let hook = match shell {
+        ShellType::Bash => {
+            r#"
+_proto_activate_hook() {
+  local previous_exit_status=$?;
+  if [[ "${_PROTO_ACTIVATED_DIR:-}" != "$PWD" ]]; then
+    _PROTO_ACTIVATED_DIR="$PWD";
+    eval "$(proto activate bash --export)";
+  fi
+  return $previous_exit_status;
+};
+
+if [[ ";${PROMPT_COMMAND[*]:-};" != *";_proto_activate_hook;"* ]]; then
+  PROMPT_COMMAND="_proto_activate_hook${PROMPT_COMMAND:+;$PROMPT_COMMAND}";
+fi
+"#
+        }
+        ShellType::Zsh => {
+            r#"
+_proto_activate_hook() {
+  if [[ "${_PROTO_ACTIVATED_DIR:-}" != "$PWD" ]]; then
+    _PROTO_ACTIVATED_DIR="$PWD";
+    eval "$(proto activate zsh --export)";
+  fi
+};
+
+typeset -ag precmd_functions;
+if [[ -z "${precmd_functions[(r)_proto_activate_hook]+1}" ]]; then
+  precmd_functions=(_proto_activate_hook $precmd_functions);
+fi
+"#
+        }
+        ShellType::Fish => {
+            r#"
+function _proto_activate_hook --on-event fish_prompt;
+  if test "$_PROTO_ACTIVATED_DIR" != "$PWD";
+    set -g _PROTO_ACTIVATED_DIR "$PWD";
+    proto activate fish --export | source;
+  end;
+end;
+"#
+        }
+        _ => {
+            return Err(ProtoCliError::UnsupportedActivateShell {
+                shell: shell.to_string(),
+            }
+            .into());
+        }
+    };
+
+    Ok(hook.trim().to_owned())
+}
+
+#[system]
+pub async fn activate(args: ArgsRef<ActivateArgs>, proto: ResourceRef<ProtoResource>) {
+    if !args.export {
+        println!("{}", render_hook(&args.shell)?);
+
+        return Ok(());
+    }
+
+    // Validate the shell before doing any work
+    render_hook(&args.shell)?;
+
+    let cache = load_activate_cache(proto).await?;
+
+    println!("{}", export_env(&args.shell, cache)?);
+}
@@ -1,14 +1,21 @@
-use crate::helpers::ProtoResource;
+use crate::helpers::{format_bytes, get_dir_size, ProtoResource};
+use crate::printer::Printer;
 use clap::Args;
 use dialoguer::Confirm;
-use proto_core::{remove_bin_file, Id, ProtoError, Tool, VersionSpec};
+use proto_core::{
+    remove_bin_file, resolve_version, FileStore, Id, ProjectRegistry, ProtoConfig, ProtoError,
+    ShimCache, Tool, VersionSpec,
+};
+use serde::Serialize;
 use starbase::diagnostics::IntoDiagnostic;
 use starbase::{system, SystemResult};
 use starbase_styles::color;
-use starbase_utils::fs;
-use std::collections::HashSet;
+use starbase_utils::{fs, json};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 #[derive(Args, Clone, Debug, Default)]
 pub struct CleanArgs {
@@ -18,6 +25,15 @@ pub struct CleanArgs {
     )]
     pub days: Option<u8>,
 
+    #[arg(long, help = "Print what would be removed, without removing anything")]
+    pub dry_run: bool,
+
+    #[arg(long, help = "Print the cleaned files in JSON format")]
+    pub json: bool,
+
+    #[arg(long, help = "Always keep the newest number of versions per tool")]
+    pub keep: Option<usize>,
+
     #[arg(
         long,
         help = "Purge and delete the installed tool by ID",
@@ -37,17 +53,104 @@ pub struct CleanArgs {
     pub yes: bool,
 }
 
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum CleanReason {
+    // Version exists on the file system but not in the manifest
+    Untracked,
+    // Version has not been used, and is not pinned
+    Unused { days: u8 },
+    // File has not been accessed
+    Stale { days: u64 },
+}
+
+impl fmt::Display for CleanReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Untracked => write!(f, "not tracked in manifest"),
+            Self::Unused { days } => write!(f, "not used in over {days} days, and not pinned"),
+            Self::Stale { days } => write!(f, "not accessed in over {days} days"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct StaleVersion {
+    pub id: Id,
+    pub version: VersionSpec,
+    pub path: PathBuf,
+    pub size: u64,
+    pub reason: CleanReason,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct StaleFile {
+    pub path: PathBuf,
+    pub size: u64,
+    pub reason: CleanReason,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CleanReport {
+    pub dry_run: bool,
+    pub versions: Vec<StaleVersion>,
+    pub proto: Vec<StaleFile>,
+    pub plugins: Vec<StaleFile>,
+    pub temp: Vec<StaleFile>,
+    // Number of files that failed to be removed
+    pub failed: usize,
+}
+
+impl CleanReport {
+    pub fn get_total_size(&self) -> u64 {
+        self.versions.iter().map(|v| v.size).sum::<u64>()
+            + [&self.proto, &self.plugins, &self.temp]
+                .into_iter()
+                .flatten()
+                .map(|f| f.size)
+                .sum::<u64>()
+    }
+}
+
 fn is_older_than_days(now: u128, other: u128, days: u8) -> bool {
     (now - other) > ((days as u128) * 24 * 60 * 60 * 1000)
 }
 
-pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miette::Result<usize> {
+fn get_pinned_versions(
+    tool: &Tool,
+    config: &ProtoConfig,
+) -> miette::Result<BTreeMap<VersionSpec, Vec<PathBuf>>> {
+    // Versions pinned by other projects on the file system
+    let mut pinned = ProjectRegistry::find_pinned_versions(tool)?;
+
+    if let Some(spec) = config.versions.get(&tool.id) {
+        if let Some(version) = resolve_version(
+            spec,
+            &[],
+            &BTreeMap::default(),
+            Some(&tool.manifest),
+            config.tools.get(&tool.id),
+        ) {
+            pinned.entry(version).or_default();
+        }
+    }
+
+    Ok(pinned)
+}
+
+pub fn find_stale_versions(
+    tool: &mut Tool,
+    now: u128,
+    days: u8,
+    keep: Option<usize>,
+    pinned: &BTreeMap<VersionSpec, Vec<PathBuf>>,
+) -> miette::Result<Vec<StaleVersion>> {
     debug!("Checking {}", color::shell(tool.get_name()));
 
     if tool.metadata.inventory.override_dir.is_some() {
         debug!("Using an external inventory, skipping");
 
-        return Ok(0);
+        return Ok(vec![]);
     }
 
     let inventory_dir = tool.get_inventory_dir();
@@ -55,10 +158,10 @@ pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miett
     if !inventory_dir.exists() {
         debug!("Not being used, skipping");
 
-        return Ok(0);
+        return Ok(vec![]);
     }
 
-    let mut versions_to_clean = HashSet::<VersionSpec>::new();
+    let mut versions_to_clean = BTreeMap::<VersionSpec, CleanReason>::new();
 
     debug!("Scanning file system for stale and untracked versions");
 
@@ -88,15 +191,24 @@ pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miett
                     color::hash(version.to_string())
                 );
 
-                versions_to_clean.insert(version);
+                versions_to_clean.insert(version, CleanReason::Untracked);
             }
         }
     }
 
     debug!("Comparing last used timestamps from manifest");
 
+    // Versions are sorted, so the newest are last
+    let kept_versions = tool
+        .manifest
+        .versions
+        .keys()
+        .rev()
+        .take(keep.unwrap_or(0))
+        .collect::<HashSet<_>>();
+
     for (version, metadata) in &tool.manifest.versions {
-        if versions_to_clean.contains(version) {
+        if versions_to_clean.contains_key(version) {
             continue;
         }
 
@@ -109,6 +221,32 @@ pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miett
             continue;
         }
 
+        if kept_versions.contains(version) {
+            debug!(
+                "Version {} is one of the newest versions to keep, skipping",
+                color::hash(version.to_string())
+            );
+
+            continue;
+        }
+
+        if let Some(dirs) = pinned.get(version) {
+            if dirs.is_empty() {
+                debug!(
+                    "Version {} is pinned in configuration, skipping",
+                    color::hash(version.to_string())
+                );
+            } else {
+                debug!(
+                    "Version {} is pinned by projects {}, skipping",
+                    color::hash(version.to_string()),
+                    dirs.iter().map(color::path).collect::<Vec<_>>().join(", ")
+                );
+            }
+
+            continue;
+        }
+
         // None may mean a few things:
         // - It was recently installed but not used yet
         // - It was installed before we started tracking last used timestamps
@@ -121,18 +259,115 @@ pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miett
                     days
                 );
 
-                versions_to_clean.insert(version.to_owned());
+                versions_to_clean.insert(version.to_owned(), CleanReason::Unused { days });
             }
         }
     }
 
+    let mut stale = vec![];
+
+    for (version, reason) in versions_to_clean {
+        tool.set_version(version.clone());
+
+        let path = tool.get_tool_dir();
+
+        stale.push(StaleVersion {
+            id: tool.id.clone(),
+            size: get_dir_size(&path)?,
+            path,
+            reason,
+            version,
+        });
+    }
+
+    Ok(stale)
+}
+
+pub fn find_stale_files(dir: &Path, days: u64, recursive: bool) -> miette::Result<Vec<StaleFile>> {
+    let mut stale = vec![];
+
+    if !dir.exists() {
+        return Ok(stale);
+    }
+
+    let now = SystemTime::now();
+    let duration = Duration::from_secs(86400 * days);
+
+    let files = if recursive {
+        fs::read_dir_all(dir)?
+    } else {
+        fs::read_dir(dir)?
+    };
+
+    for file in files {
+        let path = file.path();
+
+        let Ok(meta) = fs::metadata(&path) else {
+            continue;
+        };
+
+        if !meta.is_file() {
+            continue;
+        }
+
+        // Match the logic in `fs::remove_file_if_older_than`
+        let last_used = meta
+            .accessed()
+            .or_else(|_| meta.modified())
+            .or_else(|_| meta.created())
+            .unwrap_or(now);
+
+        if last_used < (now - duration) {
+            stale.push(StaleFile {
+                path,
+                size: meta.len(),
+                reason: CleanReason::Stale { days },
+            });
+        }
+    }
+
+    stale.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(stale)
+}
+
+// Files may be in use or have restricted permissions, so a failed removal
+// is logged and counted, but does not abort the rest of the clean.
+// Return the files that were successfully removed.
+fn remove_stale_files(files: Vec<StaleFile>, failed: &mut usize) -> Vec<StaleFile> {
+    files
+        .into_iter()
+        .filter(|file| {
+            debug!("File {} {}, removing", color::path(&file.path), file.reason);
+
+            match fs::remove_file(&file.path) {
+                Ok(_) => true,
+                Err(error) => {
+                    warn!("Failed to remove {}: {}", color::path(&file.path), error);
+
+                    *failed += 1;
+                    false
+                }
+            }
+        })
+        .collect()
+}
+
+pub async fn clean_tool(
+    mut tool: Tool,
+    now: u128,
+    days: u8,
+    keep: Option<usize>,
+    pinned: &BTreeMap<VersionSpec, Vec<PathBuf>>,
+    yes: bool,
+) -> miette::Result<Vec<StaleVersion>> {
+    let versions_to_clean = find_stale_versions(&mut tool, now, days, keep, pinned)?;
     let count = versions_to_clean.len();
-    let mut clean_count = 0;
 
     if count == 0 {
         debug!("No versions to remove, continuing to next tool");
 
-        return Ok(0);
+        return Ok(vec![]);
     }
 
     if yes
@@ -142,80 +377,62 @@ pub async fn clean_tool(mut tool: Tool, now: u128, days: u8, yes: bool) -> miett
                 count,
                 versions_to_clean
                     .iter()
-                    .map(|v| color::hash(v.to_string()))
+                    .map(|v| color::hash(v.version.to_string()))
                     .collect::<Vec<_>>()
                     .join(", ")
             ))
             .interact()
             .into_diagnostic()?
     {
-        for version in versions_to_clean {
-            tool.set_version(version);
+        for stale in &versions_to_clean {
+            tool.set_version(stale.version.clone());
             tool.teardown().await?;
         }
 
-        clean_count += count;
-    } else {
-        debug!("Skipping remove, continuing to next tool");
+        return Ok(versions_to_clean);
     }
 
-    Ok(clean_count)
-}
-
-pub async fn clean_plugins(proto: &ProtoResource, days: u64) -> miette::Result<usize> {
-    let duration = Duration::from_secs(86400 * days);
-    let mut clean_count = 0;
-
-    for file in fs::read_dir(&proto.env.plugins_dir)? {
-        let path = file.path();
-
-        if path.is_file() {
-            let bytes = fs::remove_file_if_older_than(&path, duration)?;
-
-            if bytes > 0 {
-                debug!(
-                    "Plugin {} hasn't been used in over {} days, removing",
-                    color::path(&path),
-                    days
-                );
+    debug!("Skipping remove, continuing to next tool");
 
-                clean_count += 1;
-            }
-        }
-    }
-
-    Ok(clean_count)
+    Ok(vec![])
 }
 
-pub async fn clean_proto(proto: &ProtoResource, days: u64) -> miette::Result<usize> {
-    let duration = Duration::from_secs(86400 * days);
-    let mut clean_count = 0;
-
-    for file in fs::read_dir_all(proto.env.tools_dir.join("proto"))? {
-        let path = file.path();
-
-        if path.is_file() {
-            let bytes = fs::remove_file_if_older_than(&path, duration)?;
+pub async fn clean_plugins(
+    proto: &ProtoResource,
+    days: u64,
+    failed: &mut usize,
+) -> miette::Result<Vec<StaleFile>> {
+    let stale = find_stale_files(&proto.env.plugins_dir, days, false)?;
 
-            if bytes > 0 {
-                debug!(
-                    "proto version {} hasn't been used in over {} days, removing",
-                    color::path(&path),
-                    days
-                );
+    Ok(remove_stale_files(stale, failed))
+}
 
-                clean_count += 1;
-            }
-        }
-    }
+pub async fn clean_proto(
+    proto: &ProtoResource,
+    days: u64,
+    failed: &mut usize,
+) -> miette::Result<Vec<StaleFile>> {
+    let stale = find_stale_files(&proto.env.tools_dir.join("proto"), days, true)?;
 
-    Ok(clean_count)
+    Ok(remove_stale_files(stale, failed))
 }
 
 pub async fn purge_tool(proto: &ProtoResource, id: &Id, yes: bool) -> miette::Result<Tool> {
     let tool = proto.load_tool(id).await?;
     let inventory_dir = tool.get_inventory_dir();
 
+    // Other projects may still require these versions
+    ProjectRegistry::prune(&proto.env)?;
+
+    for (version, dirs) in ProjectRegistry::find_pinned_versions(&tool)? {
+        warn!(
+            "{} {} is pinned by other projects: {}",
+            tool.get_name(),
+            version,
+            dirs.iter().map(color::path).collect::<Vec<_>>().join(", ")
+        );
+    }
+
     if yes
         || Confirm::new()
             .with_prompt(format!(
@@ -228,6 +445,7 @@ pub async fn purge_tool(proto: &ProtoResource, id: &Id, yes: bool) -> miette::Re
     {
         // Delete inventory
         fs::remove_dir_all(inventory_dir)?;
+        FileStore::prune(&proto.env)?;
 
         // Delete binaries
         for bin in tool.get_bin_locations()? {
@@ -239,6 +457,8 @@ pub async fn purge_tool(proto: &ProtoResource, id: &Id, yes: bool) -> miette::Re
             fs::remove_file(shim.path)?;
         }
 
+        ShimCache::clear(&proto.env)?;
+
         info!("Purged {}", tool.get_name());
     }
 
@@ -260,51 +480,180 @@ pub async fn purge_plugins(proto: &ProtoResource, yes: bool) -> SystemResult {
         fs::remove_dir_all(plugins_dir)?;
         fs::create_dir_all(plugins_dir)?;
 
+        // Results cached from the purged plugins are no longer relevant
+        fs::remove_dir_all(proto.env.cache_dir.join("plugins"))?;
+
         info!("Purged all downloaded plugins");
     }
 
     Ok(())
 }
 
-pub async fn internal_clean(proto: &ProtoResource, args: &CleanArgs) -> SystemResult {
+pub async fn internal_clean(
+    proto: &ProtoResource,
+    args: &CleanArgs,
+) -> miette::Result<CleanReport> {
     let days = args.days.unwrap_or(30);
     let now = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
         .unwrap()
         .as_millis();
-    let mut clean_count = 0;
+    let config = proto.env.load_config()?;
+    let mut report = CleanReport {
+        dry_run: args.dry_run,
+        ..Default::default()
+    };
+
+    debug!("Pruning projects that no longer exist...");
+
+    ProjectRegistry::prune(&proto.env)?;
 
     debug!("Finding installed tools to clean up...");
 
-    for tool in proto.load_tools().await? {
-        clean_count += clean_tool(tool, now, days, args.yes).await?;
+    for mut tool in proto.load_tools().await? {
+        let pinned = get_pinned_versions(&tool, config)?;
+
+        if args.dry_run {
+            report.versions.extend(find_stale_versions(
+                &mut tool, now, days, args.keep, &pinned,
+            )?);
+        } else {
+            report
+                .versions
+                .extend(clean_tool(tool, now, days, args.keep, &pinned, args.yes).await?);
+        }
+    }
+
+    if !args.dry_run {
+        let pruned = FileStore::prune(&proto.env)?;
+
+        if pruned.files > 0 {
+            debug!("Removed {} unreferenced files from the store", pruned.files);
+        }
     }
 
-    clean_count += clean_proto(proto, days as u64).await?;
+    if args.dry_run {
+        report.proto = find_stale_files(&proto.env.tools_dir.join("proto"), days as u64, true)?;
+    } else {
+        report.proto = clean_proto(proto, days as u64, &mut report.failed).await?;
+    }
+
+    let clean_count = report.versions.len() + report.proto.len();
 
-    if clean_count > 0 {
+    if clean_count > 0 && !args.dry_run {
         info!("Successfully cleaned up {} versions", clean_count);
     }
 
     debug!("Finding installed plugins to clean up...");
 
-    clean_count = clean_plugins(proto, days as u64).await?;
+    if args.dry_run {
+        report.plugins = find_stale_files(&proto.env.plugins_dir, days as u64, false)?;
+    } else {
+        report.plugins = clean_plugins(proto, days as u64, &mut report.failed).await?;
+    }
+
+    if !report.plugins.is_empty() && !args.dry_run {
+        info!("Successfully cleaned up {} plugins", report.plugins.len());
+    }
+
+    if !args.dry_run {
+        debug!("Pruning expired shim resolutions...");
 
-    if clean_count > 0 {
-        info!("Successfully cleaned up {} plugins", clean_count);
+        let pruned = ShimCache::prune(&proto.env)?;
+
+        debug!("Pruned {} shim cache files", pruned.len());
     }
 
     debug!("Cleaning temporary directory...");
 
-    let results = fs::remove_dir_stale_contents(&proto.env.temp_dir, Duration::from_secs(86400))?;
+    report.temp = find_stale_files(&proto.env.temp_dir, 1, false)?;
+
+    if !args.dry_run {
+        report.temp = remove_stale_files(report.temp, &mut report.failed);
+
+        if !report.temp.is_empty() {
+            info!(
+                "Successfully cleaned {} temporary files ({} bytes)",
+                report.temp.len(),
+                report.temp.iter().map(|f| f.size).sum::<u64>()
+            );
+        }
+    }
 
-    if results.files_deleted > 0 {
-        info!(
-            "Successfully cleaned {} temporary files ({} bytes)",
-            results.files_deleted, results.bytes_saved
+    if report.failed > 0 {
+        warn!(
+            "Failed to remove {} files, they will be retried on the next clean",
+            report.failed
         );
     }
 
+    Ok(report)
+}
+
+fn print_report(report: &CleanReport) -> miette::Result<()> {
+    let mut printer = Printer::new();
+
+    let format_file = |file: &StaleFile| {
+        format!(
+            "{} {} {}",
+            color::path(&file.path),
+            color::muted_light(format_bytes(file.size)),
+            color::muted(format!("({})", file.reason))
+        )
+    };
+
+    printer.named_section("Versions", |p| {
+        p.entry_list(
+            "Remove",
+            report.versions.iter().map(|version| {
+                format!(
+                    "{} {} {} {}",
+                    color::id(&version.id),
+                    color::hash(version.version.to_string()),
+                    color::muted_light(format_bytes(version.size)),
+                    color::muted(format!("({})", version.reason))
+                )
+            }),
+            Some(color::muted_light("None")),
+        );
+
+        p.entry_list(
+            "Remove proto",
+            report.proto.iter().map(format_file),
+            Some(color::muted_light("None")),
+        );
+
+        Ok(())
+    })?;
+
+    printer.named_section("Plugins", |p| {
+        p.entry_list(
+            "Remove",
+            report.plugins.iter().map(format_file),
+            Some(color::muted_light("None")),
+        );
+
+        Ok(())
+    })?;
+
+    printer.named_section("Temporary files", |p| {
+        p.entry_list(
+            "Remove",
+            report.temp.iter().map(format_file),
+            Some(color::muted_light("None")),
+        );
+
+        Ok(())
+    })?;
+
+    printer.named_section("Total", |p| {
+        p.entry("Reclaimable", format_bytes(report.get_total_size()));
+
+        Ok(())
+    })?;
+
+    printer.flush();
+
     Ok(())
 }
 
@@ -320,5 +669,11 @@ pub async fn clean(args: ArgsRef<CleanArgs>, proto: ResourceRef<ProtoResource>)
         return Ok(());
     }
 
-    internal_clean(proto, args).await?;
+    let report = internal_clean(proto, args).await?;
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&report).into_diagnostic()?);
+    } else if args.dry_run {
+        print_report(&report)?;
+    }
 }
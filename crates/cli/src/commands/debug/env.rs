@@ -18,6 +18,7 @@ pub async fn env(proto: ResourceRef<ProtoResource>) {
         p.entry("Shims", color::path(&proto.env.shims_dir));
         p.entry("Plugins", color::path(&proto.env.plugins_dir));
         p.entry("Tools", color::path(&proto.env.tools_dir));
+        p.entry("Deduped files", color::path(&proto.env.store_dir));
         p.entry("Temp", color::path(&proto.env.temp_dir));
         p.entry_map(
             "Virtual",
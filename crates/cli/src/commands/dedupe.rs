@@ -0,0 +1,66 @@
+use crate::helpers::{format_bytes, ProtoResource};
+use clap::Args;
+use proto_core::{FileStore, Id};
+use starbase::system;
+use starbase_styles::color;
+use tracing::{debug, info};
+
+#[derive(Args, Clone, Debug)]
+pub struct DedupeArgs {
+    #[arg(help = "ID of tools to deduplicate")]
+    ids: Vec<Id>,
+}
+
+#[system]
+pub async fn dedupe(args: ArgsRef<DedupeArgs>, proto: ResourceRef<ProtoResource>) {
+    info!("Loading tools...");
+
+    let mut files = 0;
+    let mut bytes = 0;
+
+    for mut tool in proto.load_installed_tools(&args.ids).await? {
+        if tool.metadata.inventory.override_dir.is_some() {
+            debug!(
+                "{} is using an external inventory, skipping",
+                tool.get_name()
+            );
+
+            continue;
+        }
+
+        let mut versions = tool
+            .manifest
+            .installed_versions
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        versions.sort();
+
+        for version in versions {
+            tool.set_version(version);
+
+            info!(
+                "Deduplicating {} {}",
+                tool.get_name(),
+                color::hash(tool.get_resolved_version().to_string())
+            );
+
+            let result = FileStore::dedupe(&proto.env, &tool.get_tool_dir())?;
+
+            files += result.files;
+            bytes += result.bytes;
+        }
+    }
+
+    let pruned = FileStore::prune(&proto.env)?;
+
+    if pruned.files > 0 {
+        debug!("Removed {} unreferenced files from the store", pruned.files);
+    }
+
+    info!(
+        "Deduplicated {} files, saving {}",
+        files,
+        format_bytes(bytes)
+    );
+}
@@ -0,0 +1,490 @@
+use crate::helpers::ProtoResource;
+use crate::printer::Printer;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{
+    create_http_client_with_options, load_tool_from_locator, read_json_file_with_lock, ProtoConfig,
+    ShimRegistry, ToolManifest, MANIFEST_NAME, SCHEMA_PLUGIN_KEY, USER_CONFIG_NAME,
+};
+use proto_shim::{get_shim_file_name, locate_proto_exe, SHIM_VERSION};
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::{fs, json};
+use std::env;
+use std::process;
+
+#[derive(Args, Clone, Debug)]
+pub struct DoctorArgs {
+    #[arg(long, help = "Print the report in JSON format")]
+    json: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Serialize)]
+pub struct CheckResult {
+    category: &'static str,
+    name: String,
+    status: CheckStatus,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fix: Option<String>,
+}
+
+#[derive(Default, Serialize)]
+pub struct DoctorReport {
+    checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    fn add(
+        &mut self,
+        category: &'static str,
+        name: impl AsRef<str>,
+        status: CheckStatus,
+        message: impl AsRef<str>,
+        fix: Option<String>,
+    ) {
+        self.checks.push(CheckResult {
+            category,
+            name: name.as_ref().to_owned(),
+            status,
+            message: message.as_ref().to_owned(),
+            fix,
+        });
+    }
+
+    fn pass(&mut self, category: &'static str, name: impl AsRef<str>, message: impl AsRef<str>) {
+        self.add(category, name, CheckStatus::Pass, message, None);
+    }
+
+    fn warn(
+        &mut self,
+        category: &'static str,
+        name: impl AsRef<str>,
+        message: impl AsRef<str>,
+        fix: impl AsRef<str>,
+    ) {
+        let fix = fix.as_ref().to_owned();
+        self.add(category, name, CheckStatus::Warn, message, Some(fix));
+    }
+
+    fn fail(
+        &mut self,
+        category: &'static str,
+        name: impl AsRef<str>,
+        message: impl AsRef<str>,
+        fix: impl AsRef<str>,
+    ) {
+        let fix = fix.as_ref().to_owned();
+        self.add(category, name, CheckStatus::Fail, message, Some(fix));
+    }
+
+    fn has_failures(&self) -> bool {
+        self.checks
+            .iter()
+            .any(|check| check.status == CheckStatus::Fail)
+    }
+}
+
+fn check_environment(proto: &ProtoResource, report: &mut DoctorReport) {
+    let category = "Environment";
+
+    if env::var_os("PROTO_HOME").is_some() {
+        report.pass(
+            category,
+            "PROTO_HOME",
+            format!("Set to {}", proto.env.root.display()),
+        );
+    } else {
+        report.warn(
+            category,
+            "PROTO_HOME",
+            format!("Not set, defaulting to {}", proto.env.root.display()),
+            "Run `proto setup` to configure your shell",
+        );
+    }
+
+    let paths = env::var_os("PATH")
+        .map(|value| env::split_paths(&value).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let shims_index = paths.iter().position(|path| path == &proto.env.shims_dir);
+    let bin_index = paths.iter().position(|path| path == &proto.env.bin_dir);
+    let path_fix = format!(
+        "Add {} and then {} to the start of PATH",
+        proto.env.shims_dir.display(),
+        proto.env.bin_dir.display()
+    );
+
+    match (shims_index, bin_index) {
+        (None, _) => {
+            report.fail(
+                category,
+                "PATH",
+                format!("{} is not on PATH", proto.env.shims_dir.display()),
+                path_fix,
+            );
+        }
+        (Some(_), None) => {
+            report.warn(
+                category,
+                "PATH",
+                format!("{} is not on PATH", proto.env.bin_dir.display()),
+                path_fix,
+            );
+        }
+        (Some(shims), Some(bin)) if bin < shims => {
+            report.warn(
+                category,
+                "PATH",
+                "Bin directory is before the shims directory, so version detection will be bypassed",
+                path_fix,
+            );
+        }
+        _ => {
+            report.pass(category, "PATH", "Shims and bin directories are on PATH");
+        }
+    };
+}
+
+fn check_shims(proto: &ProtoResource, report: &mut DoctorReport) -> miette::Result<()> {
+    let category = "Shims";
+
+    let Some(shim_binary_path) = locate_proto_exe("proto-shim") else {
+        report.fail(
+            category,
+            "proto-shim",
+            "Unable to find the proto-shim binary",
+            "Reinstall proto, as proto-shim should be installed alongside it",
+        );
+
+        return Ok(());
+    };
+
+    // Older binaries do not support `--version`, so treat them as mismatched
+    let shim_version = process::Command::new(&shim_binary_path)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .trim()
+                .parse::<u8>()
+                .ok()
+        });
+
+    match shim_version {
+        Some(version) if version == SHIM_VERSION => {
+            report.pass(
+                category,
+                "proto-shim",
+                format!(
+                    "Found at {} (version {})",
+                    shim_binary_path.display(),
+                    version
+                ),
+            );
+        }
+        version => {
+            report.fail(
+                category,
+                "proto-shim",
+                format!(
+                    "Found at {} (version {}), but proto expects version {}",
+                    shim_binary_path.display(),
+                    version.map_or_else(|| "unknown".into(), |version| version.to_string()),
+                    SHIM_VERSION
+                ),
+                "Reinstall proto, as proto-shim should be installed alongside it",
+            );
+        }
+    };
+
+    // Shims are copies of the shim binary, so compare their contents
+    let shim_binary = fs::read_file_bytes(&shim_binary_path)?;
+    let mut outdated = vec![];
+    let mut missing = vec![];
+
+    for name in ShimRegistry::load(&proto.env)?.into_keys() {
+        let shim_path = proto.env.shims_dir.join(get_shim_file_name(&name));
+
+        if !shim_path.exists() {
+            missing.push(name);
+        } else if fs::read_file_bytes(&shim_path)? != shim_binary {
+            outdated.push(name);
+        }
+    }
+
+    if !missing.is_empty() {
+        report.warn(
+            category,
+            "Registry",
+            format!("Missing shims for {}", missing.join(", ")),
+            "Run `proto regen` to regenerate shims",
+        );
+    }
+
+    if outdated.is_empty() {
+        report.pass(
+            category,
+            "Shim version",
+            "All shims match the current proto-shim binary",
+        );
+    } else {
+        report.warn(
+            category,
+            "Shim version",
+            format!(
+                "Shims for {} were created by a different proto-shim version",
+                outdated.join(", ")
+            ),
+            "Run `proto regen` to regenerate shims",
+        );
+    }
+
+    Ok(())
+}
+
+fn check_manifests(proto: &ProtoResource, report: &mut DoctorReport) -> miette::Result<()> {
+    let category = "Manifests";
+
+    if !proto.env.tools_dir.exists() {
+        return Ok(());
+    }
+
+    for dir in fs::read_dir(&proto.env.tools_dir)? {
+        let manifest_path = dir.path().join(MANIFEST_NAME);
+
+        if !manifest_path.exists() {
+            continue;
+        }
+
+        let id = fs::file_name(dir.path());
+
+        let manifest: ToolManifest = match read_json_file_with_lock(&manifest_path) {
+            Ok(manifest) => manifest,
+            Err(error) => {
+                report.fail(
+                    category,
+                    &id,
+                    format!("Unable to parse {}: {}", manifest_path.display(), error),
+                    format!("Run `proto clean --purge {id}` and reinstall"),
+                );
+
+                continue;
+            }
+        };
+
+        #[allow(deprecated)]
+        if !manifest.aliases.is_empty() || manifest.default_version.is_some() {
+            report.warn(
+                category,
+                &id,
+                "Contains legacy aliases or a global version",
+                "Run `proto migrate v0.24` to migrate them",
+            );
+        } else {
+            report.pass(category, &id, "Manifest is valid");
+        }
+    }
+
+    Ok(())
+}
+
+async fn check_plugins(proto: &ProtoResource, report: &mut DoctorReport) -> miette::Result<()> {
+    let category = "Plugins";
+    let config = proto.env.load_config()?;
+    let builtin_plugins = ProtoConfig::builtin_plugins();
+
+    for (id, locator) in &config.plugins {
+        if id == SCHEMA_PLUGIN_KEY {
+            continue;
+        }
+
+        // Avoid downloading built-in plugins that are not in use
+        if builtin_plugins.contains_key(id) && !proto.env.tools_dir.join(id.as_str()).exists() {
+            continue;
+        }
+
+        let mut tool = match load_tool_from_locator(id, &proto.env, locator).await {
+            Ok(tool) => tool,
+            Err(error) => {
+                report.fail(
+                    category,
+                    id,
+                    format!("Unable to load plugin from {locator}: {error}"),
+                    format!("Check the `{id}` plugin locator in your .prototools"),
+                );
+
+                continue;
+            }
+        };
+
+        report.pass(category, id, format!("Loaded plugin from {locator}"));
+
+        // Check that installed versions have an executable
+        let mut versions = tool
+            .manifest
+            .installed_versions
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        versions.sort();
+
+        for version in versions {
+            let name = format!("{} {}", tool.id, version);
+
+            tool.set_version(version.clone());
+
+            if !tool.get_tool_dir().exists() {
+                report.fail(
+                    "Tools",
+                    &name,
+                    format!(
+                        "Installed directory {} does not exist",
+                        tool.get_tool_dir().display()
+                    ),
+                    format!("Run `proto install {} {}` to reinstall", tool.id, version),
+                );
+
+                continue;
+            }
+
+            match tool.get_exe_location() {
+                Ok(Some(location)) if !location.path.exists() => {
+                    report.fail(
+                        "Tools",
+                        &name,
+                        format!("Missing executable {}", location.path.display()),
+                        format!(
+                            "Run `proto uninstall {0} {1}` and `proto install {0} {1}` to reinstall",
+                            tool.id, version
+                        ),
+                    );
+                }
+                Ok(_) => {
+                    report.pass("Tools", &name, "Executable exists");
+                }
+                Err(error) => {
+                    report.fail(
+                        "Tools",
+                        &name,
+                        format!("Unable to locate executable: {error}"),
+                        "Update the plugin to the latest version",
+                    );
+                }
+            };
+        }
+    }
+
+    Ok(())
+}
+
+fn check_config(proto: &ProtoResource, report: &mut DoctorReport) -> miette::Result<()> {
+    let category = "Config";
+    let config = proto.env.load_config()?;
+
+    match create_http_client_with_options(&config.settings.http) {
+        Ok(_) => {
+            report.pass(category, "HTTP client", "Created from settings");
+        }
+        Err(error) => {
+            report.fail(
+                category,
+                "HTTP client",
+                format!("Unable to create: {error}"),
+                "Check the `[settings.http]` section in your .prototools",
+            );
+        }
+    };
+
+    let user_config = proto.env.root.join(USER_CONFIG_NAME);
+
+    if user_config.exists() {
+        report.warn(
+            category,
+            USER_CONFIG_NAME,
+            format!("Found deprecated {}", user_config.display()),
+            "Move settings to ~/.proto/.prototools and delete the file",
+        );
+    }
+
+    Ok(())
+}
+
+fn format_status(status: CheckStatus) -> String {
+    match status {
+        CheckStatus::Pass => color::success("pass"),
+        CheckStatus::Warn => color::caution("warn"),
+        CheckStatus::Fail => color::failure("fail"),
+    }
+}
+
+fn print_report(report: &DoctorReport) -> miette::Result<()> {
+    let mut printer = Printer::new();
+    let mut categories = vec![];
+
+    for check in &report.checks {
+        if !categories.contains(&check.category) {
+            categories.push(check.category);
+        }
+    }
+
+    for category in categories {
+        printer.named_section(category, |p| {
+            for check in report
+                .checks
+                .iter()
+                .filter(|check| check.category == category)
+            {
+                p.entry(
+                    format!("{} {}", format_status(check.status), check.name),
+                    &check.message,
+                );
+
+                if let Some(fix) = &check.fix {
+                    p.section(|p| {
+                        p.entry("Fix", color::muted_light(fix));
+
+                        Ok(())
+                    })?;
+                }
+            }
+
+            Ok(())
+        })?;
+    }
+
+    printer.flush();
+
+    Ok(())
+}
+
+#[system]
+pub async fn doctor(args: ArgsRef<DoctorArgs>, proto: ResourceRef<ProtoResource>) {
+    let mut report = DoctorReport::default();
+
+    check_environment(proto, &mut report);
+    check_shims(proto, &mut report)?;
+    check_manifests(proto, &mut report)?;
+    check_plugins(proto, &mut report).await?;
+    check_config(proto, &mut report)?;
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&report).into_diagnostic()?);
+    } else {
+        print_report(&report)?;
+    }
+
+    if report.has_failures() {
+        process::exit(1);
+    }
+}
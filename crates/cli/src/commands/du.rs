@@ -0,0 +1,279 @@
+use crate::helpers::{
+    create_datetime, format_bytes, get_dir_size, get_unique_dir_size, ProtoResource,
+};
+use crate::printer::Printer;
+use clap::{Args, ValueEnum};
+use miette::IntoDiagnostic;
+use proto_core::{Id, VersionSpec};
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::json;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(ValueEnum, Clone, Debug, Default)]
+pub enum DiskUsageSort {
+    #[default]
+    Name,
+    Size,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct DiskUsageArgs {
+    #[arg(help = "ID of tools to report")]
+    ids: Vec<Id>,
+
+    #[arg(long, help = "Print the report in JSON format")]
+    json: bool,
+
+    #[arg(value_enum, long, default_value_t, help = "Sort tools and versions by")]
+    sort: DiskUsageSort,
+}
+
+#[derive(Serialize)]
+pub struct VersionUsage {
+    version: VersionSpec,
+    dir: PathBuf,
+    size: u64,
+    installed_at: Option<u128>,
+    last_used_at: Option<u128>,
+}
+
+#[derive(Serialize)]
+pub struct DirUsage {
+    dir: PathBuf,
+    size: u64,
+}
+
+#[derive(Serialize)]
+pub struct ToolUsage {
+    id: Id,
+    name: String,
+    inventory: DirUsage,
+    versions: Vec<VersionUsage>,
+    globals: Option<DirUsage>,
+    size: u64,
+}
+
+#[derive(Serialize)]
+pub struct DiskUsageReport {
+    tools: Vec<ToolUsage>,
+    plugins: DirUsage,
+    store: DirUsage,
+    temp: DirUsage,
+    size: u64,
+}
+
+fn get_dir_usage(dir: PathBuf) -> miette::Result<DirUsage> {
+    Ok(DirUsage {
+        size: get_dir_size(&dir)?,
+        dir,
+    })
+}
+
+#[system]
+pub async fn du(args: ArgsRef<DiskUsageArgs>, proto: ResourceRef<ProtoResource>) {
+    if !args.json {
+        info!("Loading tools...");
+    }
+
+    let mut tools = proto.load_installed_tools(&args.ids).await?;
+
+    let mut usages = vec![];
+
+    for tool in &mut tools {
+        let inventory = get_dir_usage(tool.get_inventory_dir())?;
+        let mut versions = vec![];
+
+        let mut installed_versions = tool
+            .manifest
+            .installed_versions
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        installed_versions.sort();
+
+        for version in &installed_versions {
+            let meta = tool.manifest.versions.get(version).cloned();
+
+            tool.set_version(version.to_owned());
+
+            let usage = get_dir_usage(tool.get_tool_dir())?;
+
+            versions.push(VersionUsage {
+                version: version.to_owned(),
+                dir: usage.dir,
+                size: usage.size,
+                installed_at: meta.as_ref().map(|m| m.installed_at),
+                last_used_at: meta.and_then(|m| m.last_used_at),
+            });
+        }
+
+        // Globals may be located outside of the inventory (~/.cargo/bin, etc)
+        let mut globals = None;
+
+        // Locate using the last (highest) version that was set above
+        if !installed_versions.is_empty() {
+            tool.locate_globals_dir().await?;
+
+            if let Some(globals_dir) = tool.get_globals_bin_dir() {
+                globals = Some(get_dir_usage(globals_dir.to_path_buf())?);
+            }
+        }
+
+        // Versions may share files through the file store,
+        // so only count each file once for the tool
+        let mut seen = HashSet::new();
+        let mut size = get_unique_dir_size(&inventory.dir, &mut seen)?;
+
+        if let Some(globals) = &globals {
+            size += get_unique_dir_size(&globals.dir, &mut seen)?;
+        }
+
+        if matches!(args.sort, DiskUsageSort::Size) {
+            versions.sort_by(|a, d| d.size.cmp(&a.size));
+        }
+
+        usages.push(ToolUsage {
+            id: tool.id.clone(),
+            name: tool.get_name().to_owned(),
+            inventory,
+            versions,
+            globals,
+            size,
+        });
+    }
+
+    match args.sort {
+        DiskUsageSort::Name => usages.sort_by(|a, d| a.id.cmp(&d.id)),
+        DiskUsageSort::Size => usages.sort_by(|a, d| d.size.cmp(&a.size)),
+    };
+
+    let plugins = get_dir_usage(proto.env.plugins_dir.clone())?;
+    let store = get_dir_usage(proto.env.store_dir.clone())?;
+    let temp = get_dir_usage(proto.env.temp_dir.clone())?;
+
+    // Stored files are hard linked into installed versions,
+    // so only count each file once for the total
+    let mut seen = HashSet::new();
+    let mut size = 0;
+
+    for dir in usages
+        .iter()
+        .flat_map(|usage| [Some(&usage.inventory), usage.globals.as_ref()])
+        .flatten()
+        .chain([&plugins, &store, &temp])
+        .map(|usage| &usage.dir)
+    {
+        size += get_unique_dir_size(dir, &mut seen)?;
+    }
+
+    let report = DiskUsageReport {
+        size,
+        tools: usages,
+        plugins,
+        store,
+        temp,
+    };
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&report).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    let mut printer = Printer::new();
+
+    for usage in &report.tools {
+        printer.line();
+        printer.header(&usage.id, &usage.name);
+
+        printer.section(|p| {
+            p.entry(
+                "Store",
+                format!(
+                    "{} {}",
+                    color::path(&usage.inventory.dir),
+                    color::muted_light(format_bytes(usage.inventory.size))
+                ),
+            );
+
+            if let Some(globals) = &usage.globals {
+                p.entry(
+                    "Globals",
+                    format!(
+                        "{} {}",
+                        color::path(&globals.dir),
+                        color::muted_light(format_bytes(globals.size))
+                    ),
+                );
+            }
+
+            p.entry_map(
+                "Versions",
+                usage.versions.iter().map(|version| {
+                    let mut comments = vec![format_bytes(version.size)];
+
+                    if let Some(at) = version.installed_at.and_then(create_datetime) {
+                        comments.push(format!("installed {}", at.format("%x")));
+                    }
+
+                    if let Some(at) = version.last_used_at.and_then(create_datetime) {
+                        comments.push(format!("last used {}", at.format("%x")));
+                    }
+
+                    (
+                        color::hash(version.version.to_string()),
+                        color::muted_light(comments.join(", ")),
+                    )
+                }),
+                Some(color::muted_light("None")),
+            );
+
+            p.entry("Total", format_bytes(usage.size));
+
+            Ok(())
+        })?;
+    }
+
+    printer.named_section("Cache", |p| {
+        p.entry(
+            "Plugins",
+            format!(
+                "{} {}",
+                color::path(&report.plugins.dir),
+                color::muted_light(format_bytes(report.plugins.size))
+            ),
+        );
+
+        p.entry(
+            "File store",
+            format!(
+                "{} {}",
+                color::path(&report.store.dir),
+                color::muted_light(format_bytes(report.store.size))
+            ),
+        );
+
+        p.entry(
+            "Temporary",
+            format!(
+                "{} {}",
+                color::path(&report.temp.dir),
+                color::muted_light(format_bytes(report.temp.size))
+            ),
+        );
+
+        Ok(())
+    })?;
+
+    printer.named_section("Total", |p| {
+        p.entry("Size", format_bytes(report.size));
+
+        Ok(())
+    })?;
+
+    printer.flush();
+}
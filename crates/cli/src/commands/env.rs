@@ -0,0 +1,162 @@
+use crate::error::ProtoCliError;
+use crate::helpers::{create_progress_bar, ProtoResource};
+use crate::shell::{detect_shell, format_env_vars, ShellType};
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{Id, UnresolvedVersionSpec};
+use starbase::system;
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+use tracing::debug;
+
+/// A tool ID with an optional version, in the format of `tool@version`.
+#[derive(Clone, Debug)]
+pub struct ToolSpecArg {
+    pub id: Id,
+    pub spec: Option<UnresolvedVersionSpec>,
+}
+
+impl FromStr for ToolSpecArg {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (id, spec) = match value.split_once('@') {
+            Some((id, spec)) => (id, Some(spec)),
+            None => (value, None),
+        };
+
+        Ok(Self {
+            id: Id::new(id).map_err(|error| error.to_string())?,
+            spec: spec
+                .map(UnresolvedVersionSpec::parse)
+                .transpose()
+                .map_err(|error| error.to_string())?,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct ToolsEnvironment {
+    pub paths: Vec<PathBuf>,
+    pub vars: Vec<(String, String)>,
+}
+
+impl ToolsEnvironment {
+    /// Return the bin directories joined with the current `PATH`.
+    pub fn join_path(&self) -> miette::Result<String> {
+        let mut paths = self.paths.clone();
+
+        if let Some(path) = env::var_os("PATH") {
+            paths.extend(env::split_paths(&path));
+        }
+
+        Ok(env::join_paths(paths)
+            .into_diagnostic()?
+            .to_string_lossy()
+            .to_string())
+    }
+}
+
+/// Load the provided tools (or the configured tools if none provided), install
+/// them if necessary, and return their bin directories and environment variables.
+/// Does not modify any config files, or pin versions.
+pub async fn load_tools_environment(
+    proto: &ProtoResource,
+    tools: &[ToolSpecArg],
+) -> miette::Result<ToolsEnvironment> {
+    let config = proto.env.load_config()?;
+    let mut specs = vec![];
+
+    if tools.is_empty() {
+        for (id, version) in &config.versions {
+            specs.push((id.to_owned(), version.to_owned()));
+        }
+    } else {
+        for tool in tools {
+            let version = match &tool.spec {
+                Some(spec) => spec.to_owned(),
+                None => config.versions.get(&tool.id).cloned().unwrap_or_default(),
+            };
+
+            specs.push((tool.id.clone(), version));
+        }
+    }
+
+    if specs.is_empty() {
+        return Err(ProtoCliError::NoConfiguredTools.into());
+    }
+
+    let mut environment = ToolsEnvironment::default();
+
+    for (id, version) in specs {
+        let mut tool = proto.load_tool(&id).await?;
+
+        if !tool.is_setup(&version).await? {
+            debug!(
+                "{} {} has not been installed, installing",
+                tool.get_name(),
+                version
+            );
+
+            let pb = create_progress_bar(format!(
+                "Installing {} {}",
+                tool.get_name(),
+                tool.get_resolved_version()
+            ));
+
+            tool.setup(&version, false).await?;
+
+            pb.finish_and_clear();
+        }
+
+        for dir in tool.get_bin_dirs()? {
+            if !environment.paths.contains(&dir) {
+                environment.paths.push(dir);
+            }
+        }
+
+        let prefix = tool.get_env_var_prefix();
+
+        environment.vars.push((
+            format!("{prefix}_VERSION"),
+            tool.get_resolved_version().to_string(),
+        ));
+
+        environment.vars.push((
+            format!("{prefix}_BIN"),
+            tool.get_exe_path()?.to_string_lossy().to_string(),
+        ));
+    }
+
+    Ok(environment)
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct EnvArgs {
+    #[arg(help = "Tools to include, in the format of tool@version")]
+    tools: Vec<ToolSpecArg>,
+
+    #[arg(long, help = "Shell to print exports for")]
+    shell: Option<ShellType>,
+}
+
+#[system]
+pub async fn env(args: ArgsRef<EnvArgs>, proto: ResourceRef<ProtoResource>) {
+    let shell = detect_shell(args.shell);
+    let environment = load_tools_environment(proto, &args.tools).await?;
+
+    let mut vars = environment.vars.clone();
+
+    if !environment.paths.is_empty() {
+        vars.push((
+            "PATH".into(),
+            env::join_paths(&environment.paths)
+                .into_diagnostic()?
+                .to_string_lossy()
+                .to_string(),
+        ));
+    }
+
+    println!("{}", format_env_vars(&shell, "proto", vars).trim());
+}
@@ -0,0 +1,42 @@
+use super::ExportedTool;
+use miette::IntoDiagnostic;
+use starbase_styles::color;
+use starbase_utils::json::{self, JsonMap, JsonValue};
+use tracing::warn;
+
+fn map_feature(id: &str) -> Option<&'static str> {
+    match id {
+        "bun" => Some("ghcr.io/shyim/devcontainers-features/bun:0"),
+        "deno" => Some("ghcr.io/devcontainers-community/features/deno:1"),
+        "go" => Some("ghcr.io/devcontainers/features/go:1"),
+        "node" => Some("ghcr.io/devcontainers/features/node:1"),
+        "python" => Some("ghcr.io/devcontainers/features/python:1"),
+        "rust" => Some("ghcr.io/devcontainers/features/rust:1"),
+        _ => None,
+    }
+}
+
+pub fn export(tools: &[ExportedTool]) -> miette::Result<String> {
+    let mut features = JsonMap::new();
+
+    for tool in tools {
+        let Some(feature) = map_feature(&tool.id) else {
+            warn!(
+                "No devcontainer feature available for {}, skipping",
+                color::id(&tool.id)
+            );
+
+            continue;
+        };
+
+        features.insert(
+            feature.to_owned(),
+            json::json!({ "version": tool.version.to_string() }),
+        );
+    }
+
+    let mut root = JsonMap::new();
+    root.insert("features".into(), JsonValue::Object(features));
+
+    json::to_string_pretty(&root).into_diagnostic()
+}
@@ -0,0 +1,55 @@
+use super::ExportedTool;
+use miette::IntoDiagnostic;
+use proto_core::PartialProtoConfig;
+use starbase_utils::toml;
+use std::collections::BTreeMap;
+
+pub fn export(tools: &[ExportedTool]) -> miette::Result<String> {
+    let mut versions = BTreeMap::new();
+    let mut plugins = BTreeMap::new();
+
+    for tool in tools {
+        versions.insert(tool.id.clone(), tool.version.to_unresolved_spec());
+
+        if let Some(locator) = &tool.locator {
+            plugins.insert(tool.id.clone(), locator.to_owned());
+        }
+    }
+
+    let config = PartialProtoConfig {
+        versions: Some(versions),
+        plugins: if plugins.is_empty() {
+            None
+        } else {
+            Some(plugins)
+        },
+        ..Default::default()
+    };
+
+    let prototools = toml::to_string_pretty(&config).into_diagnostic()?;
+
+    Ok(format!(
+        r#"# syntax=docker/dockerfile:1.4
+FROM debian:bookworm-slim AS proto
+
+RUN apt-get update \
+    && apt-get install -y --no-install-recommends bash ca-certificates curl git gzip unzip xz-utils \
+    && rm -rf /var/lib/apt/lists/*
+
+ENV PROTO_HOME="/root/.proto"
+ENV PATH="$PROTO_HOME/shims:$PROTO_HOME/bin:$PATH"
+
+RUN curl -fsSL https://moonrepo.dev/install/proto.sh | bash -s -- {}
+
+WORKDIR /proto
+
+COPY <<EOF .prototools
+{}
+EOF
+
+RUN proto use
+"#,
+        env!("CARGO_PKG_VERSION"),
+        prototools.trim()
+    ))
+}
@@ -0,0 +1,52 @@
+use super::ExportedTool;
+use starbase_styles::color;
+use tracing::warn;
+
+// (action, version input)
+fn map_action(id: &str) -> Option<(&'static str, &'static str)> {
+    match id {
+        "bun" => Some(("oven-sh/setup-bun@v1", "bun-version")),
+        "deno" => Some(("denoland/setup-deno@v1", "deno-version")),
+        "go" => Some(("actions/setup-go@v5", "go-version")),
+        "node" => Some(("actions/setup-node@v4", "node-version")),
+        "pnpm" => Some(("pnpm/action-setup@v2", "version")),
+        "python" => Some(("actions/setup-python@v5", "python-version")),
+        "rust" => Some(("dtolnay/rust-toolchain@master", "toolchain")),
+        _ => None,
+    }
+}
+
+pub fn export(tools: &[ExportedTool]) -> miette::Result<String> {
+    let mut lines = vec!["steps:".to_owned()];
+
+    for tool in tools {
+        let version = tool.version.to_string();
+
+        if let Some((action, input)) = map_action(&tool.id) {
+            lines.push(format!("  - uses: {action}"));
+            lines.push("    with:".into());
+            lines.push(format!("      {input}: '{version}'"));
+            continue;
+        }
+
+        // These are installed through node, so must come after it
+        match tool.id.as_str() {
+            "npm" => {
+                lines.push(format!("  - run: npm install -g npm@{version}"));
+            }
+            "yarn" => {
+                lines.push(format!(
+                    "  - run: corepack enable && corepack prepare yarn@{version} --activate"
+                ));
+            }
+            _ => {
+                warn!(
+                    "No GitHub action available for {}, skipping",
+                    color::id(&tool.id)
+                );
+            }
+        };
+    }
+
+    Ok(lines.join("\n"))
+}
@@ -0,0 +1,112 @@
+mod devcontainer;
+mod dockerfile;
+mod github_actions;
+mod nix;
+mod tool_versions;
+
+use crate::error::ProtoCliError;
+use crate::helpers::ProtoResource;
+use clap::{Args, ValueEnum};
+use proto_core::{Id, PluginLocator, ProtoConfig, VersionSpec};
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::fs;
+use std::path::PathBuf;
+use tracing::{debug, info};
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum ExportFormat {
+    Devcontainer,
+    Dockerfile,
+    GithubActions,
+    Nix,
+    ToolVersions,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct ExportArgs {
+    #[arg(value_enum, long, required = true, help = "Format to export to")]
+    format: ExportFormat,
+
+    #[arg(long, help = "Include versions in global .prototools")]
+    include_global: bool,
+
+    #[arg(long, help = "Write the export to a file instead of stdout")]
+    to: Option<PathBuf>,
+}
+
+pub struct ExportedTool {
+    pub id: Id,
+    pub version: VersionSpec,
+
+    // Only set for non built-in plugins
+    pub locator: Option<PluginLocator>,
+}
+
+impl ExportedTool {
+    /// Return the major and minor parts of the resolved version,
+    /// or `None` if resolved to an alias (like canary).
+    pub fn get_major_minor(&self) -> Option<(u64, u64)> {
+        match &self.version {
+            VersionSpec::Version(version) => Some((version.major, version.minor)),
+            _ => None,
+        }
+    }
+}
+
+#[system]
+pub async fn export(args: ArgsRef<ExportArgs>, proto: ResourceRef<ProtoResource>) {
+    let manager = proto.env.load_config_manager()?;
+
+    let config = if args.include_global {
+        manager.get_merged_config()?
+    } else {
+        manager.get_merged_config_without_global()?
+    };
+
+    if config.versions.is_empty() {
+        return Err(ProtoCliError::NoConfiguredTools.into());
+    }
+
+    let builtin_plugins = ProtoConfig::builtin_plugins();
+    let mut tools = vec![];
+
+    for (tool_id, config_version) in &config.versions {
+        let mut tool = proto.load_tool(tool_id).await?;
+
+        debug!(
+            "Resolving {} {} for export",
+            tool.get_name(),
+            config_version
+        );
+
+        tool.resolve_version(config_version, false).await?;
+
+        tools.push(ExportedTool {
+            version: tool.get_resolved_version(),
+            locator: tool
+                .locator
+                .take()
+                .filter(|locator| builtin_plugins.get(tool_id) != Some(locator)),
+            id: tool.id,
+        });
+    }
+
+    let output = match args.format {
+        ExportFormat::Devcontainer => devcontainer::export(&tools)?,
+        ExportFormat::Dockerfile => dockerfile::export(&tools)?,
+        ExportFormat::GithubActions => github_actions::export(&tools)?,
+        ExportFormat::Nix => nix::export(&tools)?,
+        ExportFormat::ToolVersions => tool_versions::export(&tools)?,
+    };
+
+    if let Some(file) = &args.to {
+        let file = proto.env.cwd.join(file);
+
+        fs::write_file(&file, format!("{}\n", output.trim_end()))?;
+
+        info!("Exported {} tools to {}", tools.len(), color::path(file));
+    } else {
+        println!("{}", output.trim_end());
+    }
+}
@@ -0,0 +1,58 @@
+use super::ExportedTool;
+use starbase_styles::color;
+use tracing::warn;
+
+// nixpkgs only provides attributes per major (or minor) release,
+// so the exact version is included as a comment instead
+fn map_packages(tool: &ExportedTool) -> Vec<String> {
+    let major_minor = tool.get_major_minor();
+
+    match (tool.id.as_str(), major_minor) {
+        ("bun", _) => vec!["bun".into()],
+        ("deno", _) => vec!["deno".into()],
+        ("go", Some((major, minor))) => vec![format!("go_{major}_{minor}")],
+        ("node", Some((major, _))) => vec![format!("nodejs_{major}")],
+        ("pnpm", _) => vec!["nodePackages.pnpm".into()],
+        ("python", Some((major, minor))) => vec![format!("python{major}{minor}")],
+        ("rust", _) => vec!["rustc".into(), "cargo".into()],
+        ("yarn", _) => vec!["yarn".into()],
+        _ => vec![],
+    }
+}
+
+pub fn export(tools: &[ExportedTool]) -> miette::Result<String> {
+    let mut packages = vec![];
+
+    for tool in tools {
+        // Bundled with node
+        if tool.id == "npm" {
+            continue;
+        }
+
+        let names = map_packages(tool);
+
+        if names.is_empty() {
+            warn!(
+                "No nixpkgs package available for {}, skipping",
+                color::id(&tool.id)
+            );
+
+            continue;
+        }
+
+        for name in names {
+            packages.push(format!("    pkgs.{name} # {} {}", tool.id, tool.version));
+        }
+    }
+
+    Ok(format!(
+        r#"{{ pkgs ? import <nixpkgs> {{}} }}:
+
+pkgs.mkShell {{
+  packages = [
+{}
+  ];
+}}"#,
+        packages.join("\n")
+    ))
+}
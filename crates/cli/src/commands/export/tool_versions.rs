@@ -0,0 +1,20 @@
+use super::ExportedTool;
+
+// asdf uses different names for some plugins
+fn map_tool_name(id: &str) -> &str {
+    match id {
+        "go" => "golang",
+        "node" => "nodejs",
+        other => other,
+    }
+}
+
+pub fn export(tools: &[ExportedTool]) -> miette::Result<String> {
+    let mut lines = vec![];
+
+    for tool in tools {
+        lines.push(format!("{} {}", map_tool_name(&tool.id), tool.version));
+    }
+
+    Ok(lines.join("\n"))
+}
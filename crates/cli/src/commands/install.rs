@@ -5,11 +5,16 @@ use crate::shell;
 use crate::telemetry::{track_usage, Metric};
 use clap::Args;
 use miette::IntoDiagnostic;
-use proto_core::{Id, PinType, Tool, UnresolvedVersionSpec};
+use proto_core::{
+    detect_version, is_version_satisfied, Id, PinType, ProjectRegistry, ProtoError, Tool,
+    UnresolvedVersionSpec,
+};
 use proto_pdk_api::{InstallHook, SyncShellProfileInput, SyncShellProfileOutput};
 use starbase::system;
 use starbase_styles::color;
 use std::env;
+use std::future::Future;
+use std::pin::Pin;
 use tracing::{debug, info};
 
 #[derive(Args, Clone, Debug)]
@@ -76,6 +81,112 @@ async fn pin_version(
     Ok(pin)
 }
 
+/// Ensure the tools required by the provided tool are installed, preferring the
+/// version pinned by the project, and make their executables available to the tool.
+/// If installing is not allowed, an error is returned for missing requirements.
+pub fn install_requirements<'a>(
+    proto: &'a ProtoResource,
+    tool: &'a mut Tool,
+    required_by: &'a [Id],
+    can_install: bool,
+) -> Pin<Box<dyn Future<Output = miette::Result<()>> + Send + 'a>> {
+    Box::pin(async move {
+        if tool.metadata.requires.is_empty() {
+            return Ok(());
+        }
+
+        let mut chain = required_by.to_vec();
+        chain.push(tool.id.clone());
+
+        for requirement in tool.metadata.requires.clone() {
+            let id = Id::new(&requirement.id)?;
+
+            if chain.contains(&id) {
+                return Err(ProtoError::RequirementCycle {
+                    tool: tool.get_name().to_owned(),
+                    requirement: id,
+                }
+                .into());
+            }
+
+            let mut dep = proto.load_tool(&id).await?;
+
+            install_requirements(proto, &mut dep, &chain, can_install).await?;
+
+            // Prefer the version pinned by the project
+            let version = match detect_version(&dep, None).await {
+                Ok(version) => version,
+                Err(error)
+                    if matches!(
+                        error.downcast_ref::<ProtoError>(),
+                        Some(ProtoError::VersionDetectFailed { .. })
+                    ) =>
+                {
+                    requirement.version.clone().unwrap_or_default()
+                }
+                Err(error) => return Err(error),
+            };
+
+            let installed = dep.is_setup(&version).await?;
+            let resolved_version = dep.get_resolved_version();
+
+            if let Some(required) = &requirement.version {
+                if !is_version_satisfied(required, &resolved_version) {
+                    return Err(ProtoError::RequirementNotSatisfied {
+                        tool: tool.get_name().to_owned(),
+                        requirement: id,
+                        required: required.to_string(),
+                        version: resolved_version.to_string(),
+                    }
+                    .into());
+                }
+            }
+
+            if !installed {
+                if !can_install {
+                    return Err(ProtoError::MissingToolForRun {
+                        tool: dep.get_name().to_owned(),
+                        version: resolved_version.to_string(),
+                        command: format!("proto install {id} {resolved_version}"),
+                    }
+                    .into());
+                }
+
+                info!(
+                    "{} requires {} {}, installing",
+                    tool.get_name(),
+                    dep.get_name(),
+                    resolved_version,
+                );
+
+                dep = internal_install(
+                    proto,
+                    InstallArgs {
+                        canary: false,
+                        id: id.clone(),
+                        pin: false,
+                        passthrough: vec![],
+                        spec: Some(resolved_version.to_unresolved_spec()),
+                    },
+                    Some(dep),
+                )
+                .await?;
+            }
+
+            debug!(
+                tool = tool.id.as_str(),
+                requirement = id.as_str(),
+                version = resolved_version.to_string(),
+                "Required tool is installed"
+            );
+
+            tool.set_dependency_bin(id, dep.get_exe_path()?.to_path_buf());
+        }
+
+        Ok(())
+    })
+}
+
 pub async fn internal_install(
     proto: &ProtoResource,
     args: InstallArgs,
@@ -192,13 +303,13 @@ pub async fn internal_install(
     // Clean plugins
     debug!("Auto-cleaning plugins");
 
-    clean_plugins(proto, 7).await?;
+    clean_plugins(proto, 7, &mut 0).await?;
 
     Ok(tool)
 }
 
 fn update_shell(tool: &Tool, passthrough_args: Vec<String>) -> miette::Result<()> {
-    if !tool.plugin.has_func("sync_shell_profile") {
+    if !tool.plugin.has_func("sync_shell_profile")? {
         return Ok(());
     }
 
@@ -233,16 +344,16 @@ fn update_shell(tool: &Tool, passthrough_args: Vec<String>) -> miette::Result<()
 
     debug!(shell = ?shell_type, env_vars = ?env_vars, "Updating shell profile");
 
-    if let Some(content) = shell::format_env_vars(&shell_type, tool.id.as_str(), env_vars) {
-        if let Some(updated_profile) =
-            shell::write_profile_if_not_setup(&shell_type, content, &output.check_var)?
-        {
-            info!(
-                "Added {} to shell profile {}",
-                output.check_var,
-                color::path(updated_profile)
-            );
-        }
+    let content = shell::format_env_vars(&shell_type, tool.id.as_str(), env_vars);
+
+    if let Some(updated_profile) =
+        shell::write_profile_if_not_setup(&shell_type, content, &output.check_var)?
+    {
+        info!(
+            "Added {} to shell profile {}",
+            output.check_var,
+            color::path(updated_profile)
+        );
     }
 
     Ok(())
@@ -250,5 +361,12 @@ fn update_shell(tool: &Tool, passthrough_args: Vec<String>) -> miette::Result<()
 
 #[system]
 pub async fn install(args: ArgsRef<InstallArgs>, proto: ResourceRef<ProtoResource>) {
-    internal_install(proto, args.to_owned(), None).await?;
+    let mut tool = proto.load_tool(&args.id).await?;
+
+    install_requirements(proto, &mut tool, &[], true).await?;
+
+    internal_install(proto, args.to_owned(), Some(tool)).await?;
+
+    // Record the current projects, so that their pinned versions are retained
+    ProjectRegistry::track_loaded(&proto.env)?;
 }
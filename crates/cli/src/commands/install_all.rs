@@ -3,9 +3,10 @@ use crate::helpers::{
 };
 use crate::{
     commands::clean::{internal_clean, CleanArgs},
-    commands::install::{internal_install, InstallArgs},
+    commands::install::{install_requirements, internal_install, InstallArgs},
 };
 use miette::IntoDiagnostic;
+use proto_core::{ProjectRegistry, ToolManifest};
 use starbase::system;
 use starbase_styles::color;
 use std::process;
@@ -54,30 +55,50 @@ pub async fn install_all(proto: ResourceRef<ProtoResource>) {
 
     disable_progress_bars();
 
-    // Then install each tool in parallel!
-    let mut futures = vec![];
+    // Install requirements one tool at a time, as tools in this batch may
+    // require the same tool, or each other, and would race to install it
+    let mut tools_to_install = vec![];
+    let mut has_requirements = false;
 
-    for tool in tools {
+    for mut tool in tools {
         if let Some(version) = versions.remove(&tool.id) {
-            let proto_clone = proto.clone();
-
-            futures.push(tokio::spawn(async move {
-                internal_install(
-                    &proto_clone,
-                    InstallArgs {
-                        canary: false,
-                        id: tool.id.clone(),
-                        pin: false,
-                        passthrough: vec![],
-                        spec: Some(version),
-                    },
-                    Some(tool),
-                )
-                .await
-            }));
+            has_requirements |= !tool.metadata.requires.is_empty();
+
+            install_requirements(proto, &mut tool, &[], true).await?;
+
+            tools_to_install.push((tool, version));
+        }
+    }
+
+    // Requirements may be tools in this batch, so reload what has been installed
+    if has_requirements {
+        for (tool, _) in &mut tools_to_install {
+            tool.manifest = ToolManifest::load_from(tool.get_inventory_dir())?;
         }
     }
 
+    // Then install each tool in parallel!
+    let mut futures = vec![];
+
+    for (tool, version) in tools_to_install {
+        let proto_clone = proto.clone();
+
+        futures.push(tokio::spawn(async move {
+            internal_install(
+                &proto_clone,
+                InstallArgs {
+                    canary: false,
+                    id: tool.id.clone(),
+                    pin: false,
+                    passthrough: vec![],
+                    spec: Some(version),
+                },
+                Some(tool),
+            )
+            .await
+        }));
+    }
+
     for future in futures {
         future.await.into_diagnostic()??;
     }
@@ -88,6 +109,9 @@ pub async fn install_all(proto: ResourceRef<ProtoResource>) {
 
     info!("Successfully installed tools");
 
+    // Record the current projects, so that their pinned versions are retained
+    ProjectRegistry::track_loaded(&proto.env)?;
+
     if config.settings.auto_clean {
         info!("Auto-clean enabled, starting clean");
 
@@ -25,10 +25,10 @@ pub async fn install_global(args: ArgsRef<InstallGlobalArgs>, proto: ResourceRef
     tool.resolve_version(&version, true).await?;
     tool.locate_globals_dir().await?;
 
-    let globals_dir = tool.get_globals_bin_dir();
+    let globals_dir = tool.get_globals_bin_dir().map(|dir| dir.to_path_buf());
     let mut log_list = vec![];
 
-    if !tool.plugin.has_func("install_global") || globals_dir.is_none() {
+    if !tool.plugin.has_func("install_global")? || globals_dir.is_none() {
         return Err(ProtoCliError::GlobalsNotSupported {
             tool: tool.get_name().to_owned(),
         }
@@ -55,6 +55,9 @@ pub async fn install_global(args: ArgsRef<InstallGlobalArgs>, proto: ResourceRef
         pb.finish_and_clear();
     }
 
+    // Create shims for the new globals, if they have not been already
+    tool.generate_global_shims(false).await?;
+
     info!(
         "Installed {} to {}!",
         log_list.join(", "),
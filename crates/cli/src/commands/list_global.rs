@@ -2,10 +2,8 @@ use crate::error::ProtoCliError;
 use crate::helpers::ProtoResource;
 use clap::Args;
 use proto_core::{detect_version, Id};
-use starbase::diagnostics::IntoDiagnostic;
 use starbase::system;
 use starbase_styles::color;
-use starbase_utils::fs;
 use std::process;
 use tracing::debug;
 
@@ -33,40 +31,22 @@ pub async fn list_global(args: ArgsRef<ListGlobalArgs>, proto: ResourceRef<Proto
 
     debug!(globals_dir = ?globals_dir, "Finding global packages");
 
-    let mut bins = vec![];
-    let globals_prefix = tool.get_globals_prefix();
-
-    if globals_dir.exists() {
-        for file in fs::read_dir(globals_dir)? {
-            if file.file_type().into_diagnostic()?.is_dir() {
-                continue;
-            }
-
-            let file_path = file.path();
-            let mut file_name = fs::file_name(&file_path);
-
-            if let Some(prefix) = globals_prefix {
-                if let Some(prefixless) = file_name.strip_prefix(prefix) {
-                    file_name = prefixless.to_owned();
-                } else {
-                    continue;
-                }
-            }
-
-            bins.push(format!(
+    let bins = tool
+        .get_globals_locations()?
+        .into_iter()
+        .map(|location| {
+            format!(
                 "{} - {}",
-                file_name,
-                color::path(file_path.canonicalize().unwrap_or(file_path))
-            ));
-        }
-    }
+                location.name,
+                color::path(location.path.canonicalize().unwrap_or(location.path))
+            )
+        })
+        .collect::<Vec<_>>();
 
     if bins.is_empty() {
         eprintln!("No global packages installed");
         process::exit(1);
     }
 
-    bins.sort();
-
     println!("{}", bins.join("\n"));
 }
@@ -78,16 +78,16 @@ fn update_shell() -> SystemResult {
     let shell = shell::detect_shell(None);
     let substitutions = vec![
         (
-            format_env_var(&shell, "PROTO_ROOT", "$HOME/.proto").unwrap(),
-            format_env_var(&shell, "PROTO_HOME", "$HOME/.proto").unwrap(),
+            format_env_var(&shell, "PROTO_ROOT", "$HOME/.proto"),
+            format_env_var(&shell, "PROTO_HOME", "$HOME/.proto"),
         ),
         (
-            format_env_var(&shell, "PATH", "$PROTO_ROOT/bin").unwrap(),
-            format_env_var(&shell, "PATH", "$PROTO_HOME/shims:$PROTO_HOME/bin").unwrap(),
+            format_env_var(&shell, "PATH", "$PROTO_ROOT/bin"),
+            format_env_var(&shell, "PATH", "$PROTO_HOME/shims:$PROTO_HOME/bin"),
         ),
         (
-            format_env_var(&shell, "PATH", "$PROTO_HOME/bin").unwrap(),
-            format_env_var(&shell, "PATH", "$PROTO_HOME/shims:$PROTO_HOME/bin").unwrap(),
+            format_env_var(&shell, "PATH", "$PROTO_HOME/bin"),
+            format_env_var(&shell, "PATH", "$PROTO_HOME/shims:$PROTO_HOME/bin"),
         ),
     ];
 
@@ -1,8 +1,14 @@
+mod activate;
 mod alias;
 mod bin;
 mod clean;
 mod completions;
 pub mod debug;
+mod dedupe;
+mod doctor;
+mod du;
+mod env;
+mod export;
 mod install;
 mod install_all;
 mod install_global;
@@ -12,18 +18,30 @@ mod list_remote;
 mod migrate;
 mod outdated;
 mod pin;
+pub mod plugin;
+mod projects;
+mod regen;
 mod run;
 mod setup;
+mod shell;
+pub mod shims;
 pub mod tool;
 mod unalias;
 mod uninstall;
 mod uninstall_global;
 mod upgrade;
+mod which;
 
+pub use activate::*;
 pub use alias::*;
 pub use bin::*;
 pub use clean::*;
 pub use completions::*;
+pub use dedupe::*;
+pub use doctor::*;
+pub use du::*;
+pub use env::*;
+pub use export::*;
 pub use install::*;
 pub use install_all::*;
 pub use install_global::*;
@@ -33,9 +51,13 @@ pub use list_remote::*;
 pub use migrate::*;
 pub use outdated::*;
 pub use pin::*;
+pub use projects::*;
+pub use regen::*;
 pub use run::*;
 pub use setup::*;
+pub use shell::*;
 pub use unalias::*;
 pub use uninstall::*;
 pub use uninstall_global::*;
 pub use upgrade::*;
+pub use which::*;
@@ -1,6 +1,6 @@
 use crate::helpers::ProtoResource;
 use clap::Args;
-use proto_core::{Id, ProtoConfig, Tool, UnresolvedVersionSpec};
+use proto_core::{Id, ProjectRegistry, ProtoConfig, Tool, UnresolvedVersionSpec};
 use starbase::{system, SystemResult};
 use starbase_styles::color;
 use std::collections::BTreeMap;
@@ -40,6 +40,13 @@ pub async fn internal_pin(tool: &mut Tool, args: &PinArgs, link: bool) -> System
         "Pinned the version",
     );
 
+    // Retain the pinned version when cleaning or uninstalling
+    if !args.global {
+        if let Some(dir) = path.parent() {
+            ProjectRegistry::track(&tool.proto, vec![dir.to_path_buf()])?;
+        }
+    }
+
     Ok(())
 }
 
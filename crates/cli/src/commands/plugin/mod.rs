@@ -0,0 +1,5 @@
+mod outdated;
+mod update;
+
+pub use outdated::*;
+pub use update::*;
@@ -0,0 +1,137 @@
+use crate::helpers::ProtoResource;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{Id, PluginLocator};
+use semver::Version;
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color::{self, OwoStyle};
+use starbase_utils::json;
+use tracing::{debug, info};
+
+#[derive(Args, Clone, Debug)]
+pub struct OutdatedPluginsArgs {
+    #[arg(help = "ID of plugins to check")]
+    ids: Vec<Id>,
+
+    #[arg(long, help = "Print the list in JSON format")]
+    json: bool,
+}
+
+#[derive(Serialize)]
+pub struct PluginOutdatedItem {
+    id: Id,
+    locator: PluginLocator,
+    repo_slug: String,
+    current_tag: String,
+    latest_tag: String,
+    is_outdated: bool,
+}
+
+// Extract the repository and release tag from GitHub locators,
+// and source URLs that point to a GitHub release asset
+fn extract_github_release(locator: &PluginLocator) -> Option<(String, String)> {
+    match locator {
+        PluginLocator::GitHub(github) => github
+            .tag
+            .as_ref()
+            .map(|tag| (github.repo_slug.clone(), tag.clone())),
+        PluginLocator::SourceUrl { url, .. } => {
+            let path = url.strip_prefix("https://github.com/")?;
+            let mut parts = path.split('/');
+
+            let owner = parts.next()?;
+            let repo = parts.next()?;
+
+            if parts.next()? != "releases" || parts.next()? != "download" {
+                return None;
+            }
+
+            let tag = parts.next()?;
+
+            Some((format!("{owner}/{repo}"), tag.to_owned()))
+        }
+        _ => None,
+    }
+}
+
+// Compare tags as versions when possible, so that a pinned prerelease
+// that is newer than the latest release isn't considered outdated
+fn is_tag_outdated(current_tag: &str, latest_tag: &str) -> bool {
+    let parse = |tag: &str| Version::parse(tag.strip_prefix('v').unwrap_or(tag)).ok();
+
+    match (parse(current_tag), parse(latest_tag)) {
+        (Some(current), Some(latest)) => current < latest,
+        _ => current_tag != latest_tag,
+    }
+}
+
+#[system]
+pub async fn outdated(args: ArgsRef<OutdatedPluginsArgs>, proto: ResourceRef<ProtoResource>) {
+    let plugins = proto.load_active_plugins(&args.ids)?;
+
+    if !args.json {
+        info!("Checking for newer plugin releases...");
+    }
+
+    let loader = proto.env.get_plugin_loader()?;
+    let mut items = vec![];
+
+    for (id, locator) in plugins {
+        let Some((repo_slug, current_tag)) = extract_github_release(&locator) else {
+            debug!(
+                plugin = id.as_str(),
+                "Plugin {} is not pinned to a GitHub release, skipping", locator
+            );
+
+            continue;
+        };
+
+        let latest_tag = loader.fetch_latest_github_tag(&repo_slug).await?;
+
+        items.push(PluginOutdatedItem {
+            is_outdated: is_tag_outdated(&current_tag, &latest_tag),
+            id,
+            locator,
+            repo_slug,
+            current_tag,
+            latest_tag,
+        });
+    }
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&items).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    if items.is_empty() {
+        info!("No plugins are pinned to a GitHub release");
+
+        return Ok(());
+    }
+
+    for item in items {
+        let mut comments = vec![format!(
+            "current release {}",
+            color::symbol(&item.current_tag)
+        )];
+
+        if item.is_outdated {
+            comments.push(format!(
+                "latest release {}",
+                color::symbol(&item.latest_tag)
+            ));
+            comments.push(color::success("update available!"));
+        } else {
+            comments.push("on the latest release".into());
+        }
+
+        println!(
+            "{} {} {}",
+            OwoStyle::new().bold().style(color::id(&item.id)),
+            color::muted("-"),
+            comments.join(&color::muted_light(", "))
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use crate::helpers::ProtoResource;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{load_tool_from_locator, load_tool_from_path, Id, PluginLocator};
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color::{self, OwoStyle};
+use starbase_utils::json;
+use tracing::{debug, info};
+
+#[derive(Args, Clone, Debug)]
+pub struct UpdatePluginsArgs {
+    #[arg(help = "ID of plugins to update")]
+    ids: Vec<Id>,
+
+    #[arg(long, help = "Print the updates in JSON format")]
+    json: bool,
+}
+
+#[derive(Serialize)]
+pub struct PluginUpdateItem {
+    id: Id,
+    locator: PluginLocator,
+    previous_version: Option<String>,
+    version: Option<String>,
+}
+
+fn format_version(version: Option<&String>) -> String {
+    version
+        .map(|v| color::symbol(format!("v{v}")))
+        .unwrap_or_else(|| color::muted_light("unknown"))
+}
+
+#[system]
+pub async fn update(args: ArgsRef<UpdatePluginsArgs>, proto: ResourceRef<ProtoResource>) {
+    let plugins = proto.load_active_plugins(&args.ids)?;
+
+    if plugins.is_empty() {
+        if !args.json {
+            info!("No plugins to update");
+        }
+
+        return Ok(());
+    }
+
+    let loader = proto.env.get_plugin_loader()?;
+    let mut items = vec![];
+
+    for (id, locator) in plugins {
+        if !args.json {
+            info!("Updating {} plugin...", color::id(&id));
+        }
+
+        // Only load a previously downloaded plugin, as loading through the
+        // locator would download it if missing or stale. It may also be broken.
+        let previous_tool = match loader.get_cache_path(&id, &locator) {
+            Some(path) if !path.exists() => {
+                debug!(plugin = id.as_str(), "Plugin has not been downloaded yet");
+
+                None
+            }
+            Some(path) => Some(load_tool_from_path(&id, &proto.env, &locator, path).await),
+            None => Some(load_tool_from_locator(&id, &proto.env, &locator).await),
+        };
+
+        let previous_version = match previous_tool {
+            Some(Ok(tool)) => tool.metadata.plugin_version,
+            Some(Err(error)) => {
+                debug!(
+                    plugin = id.as_str(),
+                    "Failed to load current plugin: {}", error
+                );
+
+                None
+            }
+            None => None,
+        };
+
+        loader.refresh_plugin(&id, &locator).await?;
+
+        let version = load_tool_from_locator(&id, &proto.env, &locator)
+            .await?
+            .metadata
+            .plugin_version;
+
+        items.push(PluginUpdateItem {
+            id,
+            locator,
+            previous_version,
+            version,
+        });
+    }
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&items).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    for item in items {
+        let comment = if item.previous_version == item.version {
+            format!(
+                "{} {}",
+                format_version(item.version.as_ref()),
+                color::muted_light("(unchanged)")
+            )
+        } else {
+            format!(
+                "{} {} {}",
+                format_version(item.previous_version.as_ref()),
+                color::muted("→"),
+                format_version(item.version.as_ref())
+            )
+        };
+
+        println!(
+            "{} {} {}",
+            OwoStyle::new().bold().style(color::id(&item.id)),
+            color::muted("-"),
+            comment
+        );
+    }
+}
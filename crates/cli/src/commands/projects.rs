@@ -0,0 +1,88 @@
+use crate::helpers::{create_datetime, ProtoResource};
+use crate::printer::Printer;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{Id, ProjectRegistry, ProtoConfig, ProtoPlatform, UnresolvedVersionSpec};
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::json;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(Serialize)]
+pub struct ProjectItem {
+    dir: PathBuf,
+    last_loaded_at: u128,
+    versions: BTreeMap<Id, UnresolvedVersionSpec>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct ProjectsArgs {
+    #[arg(long, help = "Print the projects in JSON format")]
+    json: bool,
+}
+
+#[system]
+pub async fn projects(args: ArgsRef<ProjectsArgs>, proto: ResourceRef<ProtoResource>) {
+    let pruned = ProjectRegistry::prune(&proto.env)?;
+
+    if !pruned.is_empty() && !args.json {
+        info!(
+            "Removed {} projects that no longer exist from the registry",
+            pruned.len()
+        );
+    }
+
+    let platform = ProtoPlatform::from_env();
+    let mut items = vec![];
+
+    for (dir, project) in ProjectRegistry::load(&proto.env)? {
+        let config = ProtoConfig::load_for_platform(&dir, false, &platform)?;
+
+        items.push(ProjectItem {
+            dir,
+            last_loaded_at: project.last_loaded_at,
+            versions: config.versions.unwrap_or_default(),
+        });
+    }
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&items).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    if items.is_empty() {
+        info!("No projects have been recorded");
+
+        return Ok(());
+    }
+
+    let mut printer = Printer::new();
+
+    for item in items {
+        printer.named_section(item.dir.to_string_lossy(), |p| {
+            if let Some(at) = create_datetime(item.last_loaded_at) {
+                p.entry(
+                    "Last loaded",
+                    color::muted_light(at.format("%x").to_string()),
+                );
+            }
+
+            p.entry_map(
+                "Versions",
+                item.versions
+                    .iter()
+                    .map(|(id, spec)| (color::id(id), color::hash(spec.to_string())))
+                    .collect::<Vec<_>>(),
+                Some(color::muted_light("None")),
+            );
+
+            Ok(())
+        })?;
+    }
+
+    printer.flush();
+}
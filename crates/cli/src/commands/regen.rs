@@ -0,0 +1,257 @@
+use crate::helpers::ProtoResource;
+use crate::printer::Printer;
+use clap::Args;
+use dialoguer::Confirm;
+use miette::IntoDiagnostic;
+use proto_core::{remove_bin_file, ShimRegistry, Tool, VersionSpec};
+use proto_shim::get_shim_file_name;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::fs;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+#[derive(Args, Clone, Debug)]
+pub struct RegenArgs {
+    #[arg(
+        long,
+        help = "Print the changes that would be made, without making them"
+    )]
+    dry_run: bool,
+
+    #[arg(long, help = "Avoid and force confirm prompts")]
+    yes: bool,
+}
+
+// Files in `~/.proto/bin` that are not owned by a tool
+const PROTO_BINS: [&str; 4] = ["proto", "proto.exe", "proto-shim", "proto-shim.exe"];
+
+#[derive(Default)]
+struct RegenPlan {
+    bins: BTreeSet<PathBuf>,
+    shims: BTreeSet<PathBuf>,
+    registry: BTreeSet<String>,
+}
+
+fn find_orphaned_files(dir: &Path, expected: &BTreeSet<PathBuf>) -> miette::Result<Vec<PathBuf>> {
+    let mut orphans = vec![];
+
+    if !dir.exists() {
+        return Ok(orphans);
+    }
+
+    for file in fs::read_dir(dir)? {
+        let path = file.path();
+        let name = fs::file_name(&path);
+
+        // Skip the registry and shim cache, and proto's own binaries
+        if path.is_dir() || name == "registry.json" || PROTO_BINS.contains(&name.as_str()) {
+            continue;
+        }
+
+        if !expected.contains(&path) {
+            orphans.push(path);
+        }
+    }
+
+    orphans.sort();
+
+    Ok(orphans)
+}
+
+async fn create_plan(tools: &mut [Tool]) -> miette::Result<RegenPlan> {
+    let mut plan = RegenPlan::default();
+
+    for tool in tools {
+        for shim in tool.get_shim_locations()? {
+            plan.shims.insert(shim.path);
+            plan.registry.insert(shim.name);
+        }
+
+        tool.locate_globals_dir().await?;
+
+        for global in tool.get_globals_locations()? {
+            if plan.registry.insert(global.name.clone()) {
+                plan.shims
+                    .insert(tool.proto.shims_dir.join(get_shim_file_name(&global.name)));
+            }
+        }
+
+        for bin in tool.get_bin_locations()? {
+            plan.bins.insert(bin.path);
+        }
+    }
+
+    Ok(plan)
+}
+
+#[system]
+pub async fn regen(args: ArgsRef<RegenArgs>, proto: ResourceRef<ProtoResource>) {
+    info!("Loading tools...");
+
+    // Skips tools/plugins that are not in use
+    let mut tools = proto
+        .load_tools()
+        .await?
+        .into_iter()
+        .filter(|tool| !tool.manifest.installed_versions.is_empty())
+        .collect::<Vec<_>>();
+
+    // Link the globally pinned version to `~/.proto/bin`, otherwise
+    // the latest installed version, as installing would have linked it
+    let manager = proto.env.load_config_manager()?;
+    let global_versions = manager
+        .files
+        .iter()
+        .filter(|file| file.global)
+        .find_map(|file| file.config.versions.clone())
+        .unwrap_or_default();
+
+    for tool in &mut tools {
+        if let Some(spec) = global_versions.get(&tool.id) {
+            tool.resolve_version(spec, false).await?;
+
+            if tool.is_installed() {
+                continue;
+            }
+        }
+
+        let installed = &tool.manifest.installed_versions;
+        let latest = installed
+            .iter()
+            .filter(|version| matches!(version, VersionSpec::Version(_)))
+            .max()
+            .or_else(|| installed.iter().max())
+            .cloned();
+
+        if let Some(version) = latest {
+            tool.set_version(version);
+        }
+    }
+
+    let plan = create_plan(&mut tools).await?;
+    let orphaned_shims = find_orphaned_files(&proto.env.shims_dir, &plan.shims)?;
+    let orphaned_bins = find_orphaned_files(&proto.env.bin_dir, &plan.bins)?;
+    let orphaned_entries = ShimRegistry::load(&proto.env)?
+        .into_keys()
+        .filter(|name| !plan.registry.contains(name))
+        .collect::<Vec<_>>();
+
+    if args.dry_run {
+        let mut printer = Printer::new();
+
+        let format_removed = |value: String| format!("{} {}", color::failure("-"), value);
+        let format_created = |value: String| format!("{} {}", color::success("+"), value);
+
+        printer.named_section("Shims", |p| {
+            p.entry_list(
+                "Remove",
+                orphaned_shims
+                    .iter()
+                    .map(|path| format_removed(color::path(path))),
+                Some(color::muted_light("None")),
+            );
+
+            p.entry_list(
+                "Regenerate",
+                plan.shims
+                    .iter()
+                    .map(|path| format_created(color::path(path))),
+                Some(color::muted_light("None")),
+            );
+
+            Ok(())
+        })?;
+
+        printer.named_section("Binaries", |p| {
+            p.entry_list(
+                "Remove",
+                orphaned_bins
+                    .iter()
+                    .map(|path| format_removed(color::path(path))),
+                Some(color::muted_light("None")),
+            );
+
+            p.entry_list(
+                "Relink",
+                plan.bins
+                    .iter()
+                    .map(|path| format_created(color::path(path))),
+                Some(color::muted_light("None")),
+            );
+
+            Ok(())
+        })?;
+
+        printer.named_section("Registry", |p| {
+            p.entry_list(
+                "Remove",
+                orphaned_entries
+                    .iter()
+                    .map(|name| format_removed(color::id(name))),
+                Some(color::muted_light("None")),
+            );
+
+            Ok(())
+        })?;
+
+        printer.flush();
+
+        return Ok(());
+    }
+
+    let mut orphan_count = orphaned_shims.len() + orphaned_bins.len();
+
+    // Declining only skips the removal, the rest is still regenerated
+    if orphan_count > 0
+        && !args.yes
+        && !Confirm::new()
+            .with_prompt(format!(
+                "Found {} orphaned files not owned by any tool, remove them?",
+                orphan_count
+            ))
+            .interact()
+            .into_diagnostic()?
+    {
+        orphan_count = 0;
+    } else {
+        info!("Removing orphaned shims and binaries...");
+
+        for path in &orphaned_shims {
+            debug!(shim = ?path, "Removing orphaned shim");
+
+            fs::remove_file(path)?;
+        }
+
+        for path in &orphaned_bins {
+            debug!(bin = ?path, "Removing orphaned binary");
+
+            remove_bin_file(path)?;
+        }
+    }
+
+    // Rewrite the registry from scratch, and clear cached
+    // resolutions as they may point to removed executables
+    fs::remove_file(proto.env.shims_dir.join("registry.json"))?;
+    fs::remove_dir_all(proto.env.shims_dir.join("cache"))?;
+
+    info!("Regenerating shims...");
+
+    for tool in &mut tools {
+        tool.generate_shims(true).await?;
+    }
+
+    info!("Relinking binaries...");
+
+    for tool in &mut tools {
+        tool.symlink_bins(true).await?;
+    }
+
+    info!(
+        "Regenerated {} shims and {} binaries, and removed {} orphaned files",
+        plan.shims.len(),
+        plan.bins.len(),
+        orphan_count
+    );
+}
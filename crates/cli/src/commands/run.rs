@@ -1,16 +1,19 @@
-use crate::commands::install::{internal_install, InstallArgs};
+use crate::commands::install::{install_requirements, internal_install, InstallArgs};
 use crate::error::ProtoCliError;
 use crate::helpers::ProtoResource;
 use clap::Args;
 use miette::IntoDiagnostic;
-use proto_core::{detect_version, Id, ProtoError, Tool, UnresolvedVersionSpec};
-use proto_pdk_api::{ExecutableConfig, RunHook};
+use proto_core::{
+    detect_version, Id, ProtoError, ShimCache, ShimCacheEntry, ShimRegistry, Tool,
+    UnresolvedVersionSpec,
+};
+use proto_pdk_api::{DetectVersionOutput, ExecutableConfig, RunHook};
 use proto_shim::exec_command_and_replace;
 use starbase::system;
 use std::env;
 use std::ffi::OsStr;
 use std::process::Command;
-use system_env::create_process_command;
+use system_env::{create_process_command, find_command_on_path};
 use tracing::debug;
 
 #[derive(Args, Clone, Debug)]
@@ -50,13 +53,13 @@ fn is_trying_to_self_upgrade(tool: &Tool, args: &[String]) -> bool {
     false
 }
 
-fn get_executable(tool: &Tool, args: &RunArgs) -> miette::Result<ExecutableConfig> {
+pub fn get_executable(tool: &Tool, alt: Option<&str>) -> miette::Result<ExecutableConfig> {
     let tool_dir = tool.get_tool_dir();
 
     // Run an alternate executable (via shim)
-    if let Some(alt_name) = &args.alt {
+    if let Some(alt_name) = alt {
         for location in tool.get_shim_locations()? {
-            if location.name == *alt_name {
+            if location.name == alt_name {
                 let Some(exe_path) = &location.config.exe_path else {
                     continue;
                 };
@@ -78,6 +81,35 @@ fn get_executable(tool: &Tool, args: &RunArgs) -> miette::Result<ExecutableConfi
             }
         }
 
+        // Globals are not tool shims, but still run through the tool
+        if !tool
+            .get_shim_locations()?
+            .iter()
+            .any(|location| location.name == alt_name)
+        {
+            if let Some(globals_dir) = tool.get_globals_bin_dir() {
+                for location in tool.get_globals_locations()? {
+                    if location.name == alt_name {
+                        debug!(
+                            bin = alt_name,
+                            path = ?location.path,
+                            "Received a global binary to run with",
+                        );
+
+                        return Ok(location.config);
+                    }
+                }
+
+                return Err(ProtoCliError::MissingRunGlobalBin {
+                    bin: alt_name.to_owned(),
+                    tool: tool.get_name().to_owned(),
+                    version: tool.get_resolved_version().to_string(),
+                    path: globals_dir.to_path_buf(),
+                }
+                .into());
+            }
+        }
+
         return Err(ProtoCliError::MissingRunAltBin {
             bin: alt_name.to_owned(),
             path: tool_dir,
@@ -133,6 +165,114 @@ fn create_command<I: IntoIterator<Item = A>, A: AsRef<OsStr>>(
     Ok(command)
 }
 
+// The shim name is inherited by all descendant processes, so only trust it
+// when the shim would have executed this exact `proto run` invocation
+fn is_run_by_shim(tool: &Tool, args: &RunArgs, shim_name: &str) -> miette::Result<bool> {
+    let registry = ShimRegistry::load(&tool.proto)?;
+
+    let (id, alt) = match registry.get(shim_name) {
+        Some(shim) => match &shim.parent {
+            Some(parent) => (
+                parent.as_str(),
+                shim.alt_bin.is_some_and(|alt| alt).then_some(shim_name),
+            ),
+            None => (shim_name, None),
+        },
+        None => (shim_name, None),
+    };
+
+    Ok(args.id.as_str() == id && args.alt.as_deref() == alt)
+}
+
+// When ran through a shim, cache the resolved command so that subsequent
+// shim executions in the same directory can bypass `proto run` entirely
+fn cache_shim_resolution(
+    tool: &Tool,
+    args: &RunArgs,
+    exe_config: &ExecutableConfig,
+    version_var: &str,
+) -> miette::Result<()> {
+    let Ok(shim_name) = env::var("PROTO_SHIM_NAME") else {
+        return Ok(());
+    };
+
+    if !is_run_by_shim(tool, args, &shim_name)? {
+        debug!(
+            shim = &shim_name,
+            tool = tool.id.as_str(),
+            "Shim does not belong to the tool being ran, not caching resolution"
+        );
+
+        return Ok(());
+    }
+
+    // Detection was customized, or a hook may modify the environment,
+    // so we can't cache
+    if env::var_os("PROTO_DETECT_STRATEGY").is_some() || tool.plugin.has_func("pre_run")? {
+        return Ok(());
+    }
+
+    let exe_path = exe_config.exe_path.as_ref().unwrap();
+    let mut entry = ShimCacheEntry::default();
+
+    if let Some(parent_exe_name) = &exe_config.parent_exe_name {
+        let Some(parent_exe_path) = find_command_on_path(parent_exe_name) else {
+            return Ok(());
+        };
+
+        entry.bin = parent_exe_path;
+        entry.args.push(exe_path.to_string_lossy().to_string());
+    } else {
+        entry.bin = exe_path.to_owned();
+    }
+
+    // Windows scripts must be wrapped in a shell
+    let ext = entry
+        .bin
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if ext == "ps1" || ext == "cmd" || ext == "bat" {
+        return Ok(());
+    }
+
+    // Track all files that were checked while detecting the version
+    let version_files = if tool.plugin.has_func("detect_version_files")? {
+        tool.plugin
+            .cache_func::<DetectVersionOutput>("detect_version_files")?
+            .files
+    } else {
+        vec![]
+    };
+
+    for file in &tool.proto.load_config_manager()?.files {
+        entry.track_file(file.path.clone());
+
+        if let Some(dir) = file.path.parent() {
+            for version_file in &version_files {
+                entry.track_file(dir.join(version_file));
+            }
+        }
+    }
+
+    entry.env_vars.insert(
+        version_var.to_owned(),
+        tool.get_resolved_version().to_string(),
+    );
+
+    entry.env_vars.insert(
+        format!("{}_BIN", tool.get_env_var_prefix()),
+        exe_path.to_string_lossy().to_string(),
+    );
+
+    entry.self_upgrade_commands = tool.metadata.self_upgrade_commands.clone();
+
+    debug!(shim = &shim_name, bin = ?entry.bin, "Caching shim resolution");
+
+    ShimCache::update(&tool.proto, &tool.proto.cwd, &shim_name, entry)
+}
+
 #[system]
 pub async fn run(args: ArgsRef<RunArgs>, proto: ResourceRef<ProtoResource>) -> SystemResult {
     let mut tool = proto.load_tool(&args.id).await?;
@@ -146,13 +286,19 @@ pub async fn run(args: ArgsRef<RunArgs>, proto: ResourceRef<ProtoResource>) -> S
         .into());
     }
 
+    let auto_install = tool.proto.load_config()?.settings.auto_install;
+
+    // Ensure required tools are installed before this tool
+    install_requirements(proto, &mut tool, &[], auto_install).await?;
+
+    // Only cache versions that were detected from files
+    let version_var = format!("{}_VERSION", tool.get_env_var_prefix());
+    let cacheable = args.spec.is_none() && env::var_os(&version_var).is_none();
     let version = detect_version(&tool, args.spec.clone()).await?;
 
     // Check if installed or install
     if !tool.is_setup(&version).await? {
-        let config = tool.proto.load_config()?;
-
-        if !config.settings.auto_install {
+        if !auto_install {
             return Err(ProtoError::MissingToolForRun {
                 tool: tool.get_name().to_owned(),
                 version: version.to_string(),
@@ -179,7 +325,11 @@ pub async fn run(args: ArgsRef<RunArgs>, proto: ResourceRef<ProtoResource>) -> S
     }
 
     // Determine the binary path to execute
-    let exe_config = get_executable(&tool, args)?;
+    if args.alt.is_some() {
+        tool.locate_globals_dir().await?;
+    }
+
+    let exe_config = get_executable(&tool, args.alt.as_deref())?;
     let exe_path = exe_config.exe_path.as_ref().unwrap();
 
     // Run before hook
@@ -191,7 +341,9 @@ pub async fn run(args: ArgsRef<RunArgs>, proto: ResourceRef<ProtoResource>) -> S
     // Create and run the command
     let mut command = create_command(&tool, &exe_config, &args.passthrough)?;
 
+    // Don't leak the shim to child processes, as they may run other tools
     command
+        .env_remove("PROTO_SHIM_NAME")
         .env(
             format!("{}_VERSION", tool.get_env_var_prefix()),
             tool.get_resolved_version().to_string(),
@@ -209,6 +361,11 @@ pub async fn run(args: ArgsRef<RunArgs>, proto: ResourceRef<ProtoResource>) -> S
         let _ = tool.manifest.save();
     }
 
+    if cacheable {
+        // Ignore failures to not disrupt the user
+        let _ = cache_shim_resolution(&tool, args, &exe_config, &version_var);
+    }
+
     // Must be the last line!
     exec_command_and_replace(command).into_diagnostic()?;
 }
@@ -1,7 +1,6 @@
 use crate::helpers::ProtoResource;
-use crate::shell::detect_shell;
+use crate::shell::{detect_shell, ShellType};
 use clap::Args;
-use clap_complete::Shell;
 use starbase::system;
 use std::env;
 use std::path::PathBuf;
@@ -10,7 +9,7 @@ use tracing::debug;
 #[derive(Args, Clone, Debug)]
 pub struct SetupArgs {
     #[arg(long, help = "Shell to setup for")]
-    shell: Option<Shell>,
+    shell: Option<ShellType>,
 
     #[arg(long, help = "Return the profile path if setup")]
     profile: bool,
@@ -38,7 +37,7 @@ pub async fn setup(args: ArgsRef<SetupArgs>, proto: ResourceRef<ProtoResource>)
 
 // For other shells, write environment variable(s) to an applicable profile!
 #[cfg(not(windows))]
-pub fn do_setup(shell: Shell, _dirs: Vec<PathBuf>, print_profile: bool) -> miette::Result<()> {
+pub fn do_setup(shell: ShellType, _dirs: Vec<PathBuf>, print_profile: bool) -> miette::Result<()> {
     use crate::shell::{format_env_vars, write_profile_if_not_setup};
 
     debug!("Updating PATH in {} shell", shell);
@@ -51,11 +50,11 @@ pub fn do_setup(shell: Shell, _dirs: Vec<PathBuf>, print_profile: bool) -> miett
         ),
     ];
 
-    if let Some(content) = format_env_vars(&shell, "proto", env_vars) {
-        if let Some(updated_profile) = write_profile_if_not_setup(&shell, content, "PROTO_HOME")? {
-            if print_profile {
-                println!("{}", updated_profile.to_string_lossy());
-            }
+    let content = format_env_vars(&shell, "proto", env_vars);
+
+    if let Some(updated_profile) = write_profile_if_not_setup(&shell, content, "PROTO_HOME")? {
+        if print_profile {
+            println!("{}", updated_profile.to_string_lossy());
         }
     }
 
@@ -65,7 +64,11 @@ pub fn do_setup(shell: Shell, _dirs: Vec<PathBuf>, print_profile: bool) -> miett
 // Windows does not support setting environment variables from a shell,
 // so we're going to execute the `setx` command instead!
 #[cfg(windows)]
-pub fn do_setup(shell: Shell, mut dirs: Vec<PathBuf>, print_profile: bool) -> miette::Result<()> {
+pub fn do_setup(
+    shell: ShellType,
+    mut dirs: Vec<PathBuf>,
+    print_profile: bool,
+) -> miette::Result<()> {
     use miette::IntoDiagnostic;
     use std::process::Command;
     use tracing::warn;
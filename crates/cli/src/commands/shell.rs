@@ -0,0 +1,59 @@
+use super::env::{load_tools_environment, ToolSpecArg};
+use crate::helpers::ProtoResource;
+use crate::shell::{detect_shell, ShellType};
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_shim::exec_command_and_replace;
+use starbase::system;
+use starbase_styles::color;
+use std::env;
+use std::process::Command;
+use tracing::{debug, info};
+
+#[derive(Args, Clone, Debug)]
+pub struct ShellArgs {
+    #[arg(help = "Tools to include, in the format of tool@version")]
+    tools: Vec<ToolSpecArg>,
+
+    #[arg(long, help = "Shell to spawn")]
+    shell: Option<ShellType>,
+}
+
+fn get_shell_bin(shell: Option<ShellType>) -> String {
+    if let Some(shell) = shell {
+        return shell.get_bin_name().to_owned();
+    }
+
+    if let Ok(bin) = env::var("SHELL") {
+        if !bin.is_empty() {
+            return bin;
+        }
+    }
+
+    detect_shell(None).get_bin_name().to_owned()
+}
+
+#[system]
+pub async fn shell(args: ArgsRef<ShellArgs>, proto: ResourceRef<ProtoResource>) {
+    let environment = load_tools_environment(proto, &args.tools).await?;
+    let shell_bin = get_shell_bin(args.shell);
+
+    let mut command = Command::new(&shell_bin);
+    command.env("PATH", environment.join_path()?);
+    command.envs(environment.vars.iter().map(|(k, v)| (k, v)));
+
+    info!(
+        "Starting a new {} shell, type {} to return",
+        color::shell(&shell_bin),
+        color::shell("exit")
+    );
+
+    debug!(
+        shell = &shell_bin,
+        paths = ?environment.paths,
+        vars = ?environment.vars,
+        "Spawning subshell"
+    );
+
+    exec_command_and_replace(command).into_diagnostic()?;
+}
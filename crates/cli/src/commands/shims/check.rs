@@ -0,0 +1,155 @@
+use crate::helpers::ProtoResource;
+use crate::printer::Printer;
+use crate::shell::{detect_shell, find_profiles, ShellType};
+use clap::Args;
+use proto_core::{find_shadowing_executable, get_home_dir, ShimRegistry};
+use proto_shim::get_shim_file_name;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use tracing::debug;
+
+#[derive(Args, Clone, Debug)]
+pub struct CheckShimsArgs {
+    #[arg(long, help = "Shell to find profiles for")]
+    shell: Option<ShellType>,
+}
+
+struct Conflict {
+    exe_path: PathBuf,
+    name: String,
+    profile: Option<PathBuf>,
+    shadowed_by: PathBuf,
+}
+
+// Return true if the line contains the needle as a complete path,
+// and not as a prefix of a longer path (`/usr/bin` vs `/usr/bin2`)
+fn contains_path(line: &str, needle: &str) -> bool {
+    line.match_indices(needle).any(|(index, _)| {
+        line[index + needle.len()..]
+            .chars()
+            .next()
+            .map_or(true, |next| {
+                matches!(next, '/' | '\\' | ':' | ';' | '"' | '\'' | ')' | ']' | ',')
+                    || next.is_whitespace()
+            })
+    })
+}
+
+// Find the shell profile that adds the directory to `PATH`. Only lines that
+// modify `PATH` and reference the directory itself are considered, so
+// directories added indirectly (by sourcing a version manager, or by
+// the system) are not attributed to a profile.
+fn find_responsible_profile(shell: &ShellType, dir: &Path) -> miette::Result<Option<PathBuf>> {
+    let mut needles = vec![dir.to_string_lossy().to_string()];
+
+    if let Ok(suffix) = dir.strip_prefix(get_home_dir()?) {
+        let suffix = suffix.to_string_lossy();
+
+        needles.push(format!("$HOME/{suffix}"));
+        needles.push(format!("${{HOME}}/{suffix}"));
+        needles.push(format!("~/{suffix}"));
+    }
+
+    for profile in find_profiles(shell)? {
+        if !profile.exists() {
+            continue;
+        }
+
+        let content = fs::read_file(&profile)?;
+
+        let adds_to_path = content.lines().map(|line| line.trim()).any(|line| {
+            !line.starts_with('#')
+                && (line.contains("PATH") || line.contains("fish_add_path"))
+                && needles.iter().any(|needle| contains_path(line, needle))
+        });
+
+        if adds_to_path {
+            debug!(profile = ?profile, dir = ?dir, "Found profile that adds directory to PATH");
+
+            return Ok(Some(profile));
+        }
+    }
+
+    Ok(None)
+}
+
+#[system]
+pub async fn check(args: ArgsRef<CheckShimsArgs>, proto: ResourceRef<ProtoResource>) {
+    let shell = detect_shell(args.shell);
+    let mut exes = vec![];
+
+    for name in ShimRegistry::load(&proto.env)?.into_keys() {
+        let shim_path = proto.env.shims_dir.join(get_shim_file_name(&name));
+
+        if shim_path.exists() {
+            exes.push((name, shim_path));
+        }
+    }
+
+    if proto.env.bin_dir.exists() {
+        for file in fs::read_dir(&proto.env.bin_dir)? {
+            let path = file.path();
+
+            if !path.is_dir() {
+                exes.push((fs::file_name(&path), path));
+            }
+        }
+    }
+
+    let mut conflicts = vec![];
+
+    for (name, exe_path) in exes {
+        debug!(exe = ?exe_path, "Checking if executable is shadowed");
+
+        let Some(shadowed_by) =
+            find_shadowing_executable(&exe_path, &[&proto.env.shims_dir, &proto.env.bin_dir])
+        else {
+            continue;
+        };
+
+        conflicts.push(Conflict {
+            profile: find_responsible_profile(&shell, shadowed_by.parent().unwrap())?,
+            exe_path,
+            name,
+            shadowed_by,
+        });
+    }
+
+    if !conflicts.is_empty() {
+        let mut printer = Printer::new();
+
+        for conflict in conflicts {
+            printer.named_section(&conflict.name, |p| {
+                p.entry("Executable", color::path(&conflict.exe_path));
+                p.entry("Shadowed by", color::path(&conflict.shadowed_by));
+                p.entry(
+                    "Added by",
+                    match &conflict.profile {
+                        Some(profile) => color::path(profile),
+                        None => color::muted_light("Unknown"),
+                    },
+                );
+
+                Ok(())
+            })?;
+        }
+
+        printer.line();
+        printer.entry(
+            "Fix",
+            format!(
+                "Move {} and {} before other directories in PATH",
+                color::path(&proto.env.shims_dir),
+                color::path(&proto.env.bin_dir),
+            ),
+        );
+        printer.flush();
+
+        process::exit(1);
+    }
+
+    println!("No conflicts found, all shims and binaries take precedence in PATH");
+}
@@ -139,7 +139,7 @@ pub async fn info(args: ArgsRef<ToolInfoArgs>, proto: ResourceRef<ProtoResource>
 
     if !tool_config.config.is_empty() {
         printer.named_section("Configuration", |p| {
-            for (key, value) in tool_config.config {
+            for (key, value) in &tool_config.config {
                 p.entry(key, value.to_string());
             }
 
@@ -159,5 +159,35 @@ pub async fn info(args: ArgsRef<ToolInfoArgs>, proto: ResourceRef<ProtoResource>
         Ok(())
     })?;
 
+    // PERMISSIONS
+
+    printer.named_section("Permissions", |p| {
+        let Some(permissions) = &tool_config.permissions else {
+            p.entry("Access", color::muted_light("Unrestricted"));
+
+            return Ok(());
+        };
+
+        p.entry_list(
+            "Commands",
+            permissions.commands.iter().map(color::label),
+            Some(color::muted_light("None")),
+        );
+
+        p.entry_list(
+            "Environment variables",
+            permissions.env_vars.iter().map(color::property),
+            Some(color::muted_light("None")),
+        );
+
+        p.entry_list(
+            "HTTP hosts",
+            permissions.hosts.iter().map(color::url),
+            Some(color::muted_light("None")),
+        );
+
+        Ok(())
+    })?;
+
     printer.flush();
 }
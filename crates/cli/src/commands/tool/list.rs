@@ -1,7 +1,6 @@
 use crate::error::ProtoCliError;
-use crate::helpers::ProtoResource;
+use crate::helpers::{create_datetime, ProtoResource};
 use crate::printer::Printer;
-use chrono::{DateTime, NaiveDateTime};
 use clap::Args;
 use miette::IntoDiagnostic;
 use proto_core::{Id, ProtoToolConfig, ToolManifest, UnresolvedVersionSpec};
@@ -150,8 +149,3 @@ pub async fn list(args: ArgsRef<ListToolsArgs>, proto: ResourceRef<ProtoResource
 
     printer.lock().await.flush();
 }
-
-fn create_datetime(millis: u128) -> Option<NaiveDateTime> {
-    DateTime::from_timestamp((millis / 1000) as i64, ((millis % 1000) * 1_000_000) as u32)
-        .map(|dt| dt.naive_local())
-}
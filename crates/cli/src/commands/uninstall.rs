@@ -2,9 +2,12 @@ use crate::commands::clean::purge_tool;
 use crate::helpers::{create_progress_bar, disable_progress_bars, ProtoResource};
 use crate::telemetry::{track_usage, Metric};
 use clap::Args;
-use proto_core::{Id, Tool, UnresolvedVersionSpec};
+use dialoguer::Confirm;
+use miette::IntoDiagnostic;
+use proto_core::{Id, ProjectRegistry, Tool, UnresolvedVersionSpec};
 use starbase::system;
-use tracing::{debug, info};
+use starbase_styles::color;
+use tracing::{debug, info, warn};
 
 #[derive(Args, Clone, Debug)]
 pub struct UninstallArgs {
@@ -43,6 +46,29 @@ pub async fn uninstall(args: ArgsRef<UninstallArgs>, proto: ResourceRef<ProtoRes
         return Ok(());
     }
 
+    // Other projects may still require this version
+    ProjectRegistry::prune(&proto.env)?;
+
+    if let Some(dirs) =
+        ProjectRegistry::find_pinned_versions(&tool)?.get(&tool.get_resolved_version())
+    {
+        warn!(
+            "{} {} is pinned by other projects: {}",
+            tool.get_name(),
+            tool.get_resolved_version(),
+            dirs.iter().map(color::path).collect::<Vec<_>>().join(", ")
+        );
+
+        if !args.yes
+            && !Confirm::new()
+                .with_prompt("Uninstall anyway?")
+                .interact()
+                .into_diagnostic()?
+        {
+            return Ok(());
+        }
+    }
+
     debug!("Uninstalling {} with version {}", tool.get_name(), spec);
 
     if tool.disable_progress_bars() {
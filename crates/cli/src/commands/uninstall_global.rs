@@ -30,7 +30,7 @@ pub async fn uninstall_global(
     let globals_dir = tool.get_globals_bin_dir();
     let mut log_list = vec![];
 
-    if !tool.plugin.has_func("uninstall_global") || globals_dir.is_none() {
+    if !tool.plugin.has_func("uninstall_global")? || globals_dir.is_none() {
         return Err(ProtoCliError::GlobalsNotSupported {
             tool: tool.get_name().to_owned(),
         }
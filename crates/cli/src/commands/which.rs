@@ -0,0 +1,255 @@
+use crate::commands::run::get_executable;
+use crate::helpers::ProtoResource;
+use crate::printer::Printer;
+use clap::Args;
+use miette::IntoDiagnostic;
+use proto_core::{
+    detect_version_with_source, DetectStrategy, DetectedFrom, Id, Shim, ShimRegistry,
+    UnresolvedVersionSpec, VersionSpec,
+};
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::json;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use system_env::find_command_on_path;
+
+#[derive(Serialize)]
+pub struct WhichResolution {
+    aliases: Vec<UnresolvedVersionSpec>,
+    candidate: UnresolvedVersionSpec,
+    detect_strategy: Option<DetectStrategy>,
+    detected_from: DetectedFrom,
+    env_vars: BTreeMap<String, String>,
+    exe_path: Option<PathBuf>,
+    id: Id,
+    installed: bool,
+    name: String,
+    parent_exe_name: Option<String>,
+    parent_exe_path: Option<PathBuf>,
+    shim: Option<Shim>,
+    version: VersionSpec,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct WhichArgs {
+    #[arg(required = true, help = "ID of tool, or name of shim")]
+    name: Id,
+
+    #[arg(help = "Version or alias of tool")]
+    spec: Option<UnresolvedVersionSpec>,
+
+    #[arg(long, help = "Print the resolution in JSON format")]
+    json: bool,
+}
+
+#[system]
+pub async fn which(args: ArgsRef<WhichArgs>, proto: ResourceRef<ProtoResource>) {
+    // Secondary shims (npx, etc) run through their parent tool
+    let shim = ShimRegistry::load(&proto.env)?.remove(args.name.as_str());
+
+    let (id, alt) = match shim.as_ref().and_then(|shim| shim.parent.as_ref()) {
+        Some(parent) => (
+            Id::raw(parent),
+            shim.as_ref()
+                .is_some_and(|shim| shim.alt_bin.is_some_and(|alt| alt))
+                .then(|| args.name.to_string()),
+        ),
+        None => (args.name.clone(), None),
+    };
+
+    let mut tool = proto.load_tool(&id).await?;
+
+    // Detect and resolve the same way that `proto run` does
+    let (candidate, detected_from) = detect_version_with_source(&tool, args.spec.clone()).await?;
+
+    let detect_strategy = matches!(
+        detected_from,
+        DetectedFrom::ProtoConfig { .. } | DetectedFrom::VersionFile { .. }
+    )
+    .then(|| {
+        proto
+            .env
+            .load_config()
+            .map(|config| config.settings.detect_strategy.clone())
+    })
+    .transpose()?;
+
+    let aliases = if matches!(candidate, UnresolvedVersionSpec::Alias(_)) {
+        tool.load_version_resolver(&candidate)
+            .await?
+            .expand_aliases(&candidate)
+    } else {
+        vec![candidate.clone()]
+    };
+
+    let installed = tool.is_setup(&candidate).await?;
+    let version = tool.get_resolved_version();
+    let prefix = tool.get_env_var_prefix();
+
+    let mut env_vars = BTreeMap::default();
+    let mut exe_path = None;
+    let mut parent_exe_name = None;
+    let mut parent_exe_path = None;
+
+    if let Some(shim) = &shim {
+        env_vars.extend(shim.env_vars.clone());
+    }
+
+    env_vars.insert(format!("{prefix}_VERSION"), version.to_string());
+
+    if installed {
+        if alt.is_some() {
+            tool.locate_globals_dir().await?;
+        }
+
+        let exe_config = get_executable(&tool, alt.as_deref())?;
+        let path = exe_config.exe_path.unwrap();
+
+        env_vars.insert(format!("{prefix}_BIN"), path.to_string_lossy().to_string());
+
+        if let Some(parent) = exe_config.parent_exe_name {
+            parent_exe_path = find_command_on_path(&parent);
+            parent_exe_name = Some(parent);
+        }
+
+        exe_path = Some(path);
+    }
+
+    let resolution = WhichResolution {
+        aliases,
+        candidate,
+        detect_strategy,
+        detected_from,
+        env_vars,
+        exe_path,
+        id: tool.id.clone(),
+        installed,
+        name: args.name.to_string(),
+        parent_exe_name,
+        parent_exe_path,
+        shim,
+        version,
+    };
+
+    if args.json {
+        println!("{}", json::to_string_pretty(&resolution).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    let mut printer = Printer::new();
+
+    printer.header(&tool.id, &tool.metadata.name);
+
+    // SHIM
+
+    printer.named_section("Shim", |p| {
+        let Some(shim) = &resolution.shim else {
+            p.entry("Registry entry", color::failure("None"));
+
+            return Ok(());
+        };
+
+        p.entry("Registry entry", color::id(&resolution.name));
+
+        if let Some(parent) = &shim.parent {
+            p.entry("Parent tool", color::id(parent));
+        }
+
+        if let Some(alt) = &alt {
+            p.entry("Alternate binary", color::id(alt));
+        }
+
+        if !shim.before_args.is_empty() {
+            p.entry("Before args", color::shell(shim.before_args.join(" ")));
+        }
+
+        if !shim.after_args.is_empty() {
+            p.entry("After args", color::shell(shim.after_args.join(" ")));
+        }
+
+        Ok(())
+    })?;
+
+    // VERSION
+
+    printer.named_section("Version", |p| {
+        match &resolution.detected_from {
+            DetectedFrom::CommandLine => {
+                p.entry("Detected from", "command line");
+            }
+            DetectedFrom::EnvVar { name } => {
+                p.entry("Detected from", color::property(name));
+            }
+            DetectedFrom::ProtoConfig { path } => {
+                p.entry("Detected from", color::path(path));
+            }
+            DetectedFrom::VersionFile { path } => {
+                p.entry(
+                    "Detected from",
+                    format!(
+                        "{} {}",
+                        color::path(path),
+                        color::muted_light("(ecosystem)")
+                    ),
+                );
+            }
+        };
+
+        if let Some(strategy) = &resolution.detect_strategy {
+            p.entry("Detect strategy", color::symbol(strategy.to_string()));
+        }
+
+        p.entry(
+            "Candidate",
+            resolution
+                .aliases
+                .iter()
+                .map(|spec| color::hash(spec.to_string()))
+                .collect::<Vec<_>>()
+                .join(&color::muted(" → ")),
+        );
+
+        p.entry("Resolved", color::hash(resolution.version.to_string()));
+
+        Ok(())
+    })?;
+
+    // EXECUTABLE
+
+    printer.named_section("Executable", |p| {
+        let Some(exe_path) = &resolution.exe_path else {
+            p.entry("Installed", color::failure("No"));
+
+            return Ok(());
+        };
+
+        p.entry("Installed", color::success("Yes"));
+        p.entry("Executable", color::path(exe_path));
+
+        if let Some(parent) = &resolution.parent_exe_name {
+            p.entry(
+                "Parent executable",
+                match &resolution.parent_exe_path {
+                    Some(parent_path) => color::path(parent_path),
+                    None => color::failure(format!("{parent} (not found on PATH)")),
+                },
+            );
+        }
+
+        p.entry_map(
+            "Environment variables",
+            resolution
+                .env_vars
+                .iter()
+                .map(|(key, value)| (color::property(key), value.to_owned())),
+            None,
+        );
+
+        Ok(())
+    })?;
+
+    printer.flush();
+}
@@ -30,6 +30,24 @@ pub enum ProtoCliError {
 		)]
     MissingRunAltBin { bin: String, path: PathBuf },
 
+    #[diagnostic(
+        code(proto::cli::missing_global_binary),
+        help = "Globals are installed per tool version. Reinstall the global package for this version."
+    )]
+    #[error(
+			"Unable to run, global binary {} has not been installed for {} {}. Attempted to find in {}.",
+			.bin.style(Style::File),
+			.tool,
+			.version.style(Style::Hash),
+			.path.style(Style::Path),
+		)]
+    MissingRunGlobalBin {
+        bin: String,
+        tool: String,
+        version: String,
+        path: PathBuf,
+    },
+
     #[diagnostic(code(proto::cli::no_configured_tools))]
     #[error("No tools have been configured in {}.", PROTO_CONFIG_NAME.style(Style::File),)]
     NoConfiguredTools,
@@ -61,4 +79,11 @@ pub enum ProtoCliError {
     #[diagnostic(code(proto::cli::unknown_migration))]
     #[error("Unknown migration operation {}.", .op.style(Style::Symbol))]
     UnknownMigration { op: String },
+
+    #[diagnostic(code(proto::cli::unsupported_activate_shell))]
+    #[error(
+        "Shell {} is not supported for activation. Supported shells are bash, fish, and zsh.",
+        .shell.style(Style::Shell)
+    )]
+    UnsupportedActivateShell { shell: String },
 }
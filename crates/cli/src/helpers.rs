@@ -1,12 +1,15 @@
+use chrono::{DateTime, NaiveDateTime};
 use indicatif::{ProgressBar, ProgressStyle};
 use miette::IntoDiagnostic;
 use proto_core::{
-    load_schema_plugin_with_proto, load_tool_from_locator, load_tool_with_proto, Id,
-    ProtoEnvironment, Tool, SCHEMA_PLUGIN_KEY,
+    load_schema_plugin_with_proto, load_tool_from_locator, load_tool_with_proto, Id, PluginLocator,
+    ProtoConfig, ProtoEnvironment, ProtoError, Tool, SCHEMA_PLUGIN_KEY,
 };
 use starbase::Resource;
-use std::collections::HashSet;
+use starbase_utils::fs;
+use std::collections::{BTreeMap, HashSet};
 use std::env;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -46,6 +49,74 @@ pub fn create_progress_bar<S: AsRef<str>>(start: S) -> ProgressBar {
     pb
 }
 
+pub fn create_datetime(millis: u128) -> Option<NaiveDateTime> {
+    DateTime::from_timestamp((millis / 1000) as i64, ((millis % 1000) * 1_000_000) as u32)
+        .map(|dt| dt.naive_local())
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+pub type FileId = (u64, u64);
+
+// Hard links share the same device and inode, so are only counted once
+#[cfg(unix)]
+fn get_file_id(meta: &std::fs::Metadata) -> Option<FileId> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn get_file_id(_meta: &std::fs::Metadata) -> Option<FileId> {
+    None
+}
+
+pub fn get_dir_size(dir: &Path) -> miette::Result<u64> {
+    get_unique_dir_size(dir, &mut HashSet::new())
+}
+
+/// Return the size of all files in the directory, excluding files
+/// that have already been counted (are in `seen`), for example,
+/// files that are hard linked to the file store.
+pub fn get_unique_dir_size(dir: &Path, seen: &mut HashSet<FileId>) -> miette::Result<u64> {
+    let mut size = 0;
+
+    if !dir.exists() {
+        return Ok(size);
+    }
+
+    for entry in fs::read_dir_all(dir)? {
+        // Don't follow symlinks, as they may point outside the directory
+        if let Ok(meta) = entry.path().symlink_metadata() {
+            if !meta.is_file() {
+                continue;
+            }
+
+            if get_file_id(&meta).map_or(true, |id| seen.insert(id)) {
+                size += meta.len();
+            }
+        }
+    }
+
+    Ok(size)
+}
+
 #[derive(Clone, Resource)]
 pub struct ProtoResource {
     pub env: Arc<ProtoEnvironment>,
@@ -58,6 +129,43 @@ impl ProtoResource {
         })
     }
 
+    /// Return the configured plugins that are in use, or the provided plugins.
+    /// Built-in plugins are only in use if their tool has been installed.
+    pub fn load_active_plugins(
+        &self,
+        filter: &[Id],
+    ) -> miette::Result<BTreeMap<Id, PluginLocator>> {
+        let config = self.env.load_config()?;
+        let mut plugins = BTreeMap::new();
+
+        if !filter.is_empty() {
+            for id in filter {
+                let Some(locator) = config.plugins.get(id) else {
+                    return Err(ProtoError::UnknownTool { id: id.to_owned() }.into());
+                };
+
+                plugins.insert(id.to_owned(), locator.to_owned());
+            }
+
+            return Ok(plugins);
+        }
+
+        let builtin_plugins = ProtoConfig::builtin_plugins();
+
+        for (id, locator) in &config.plugins {
+            if id == SCHEMA_PLUGIN_KEY
+                || builtin_plugins.contains_key(id)
+                    && !self.env.tools_dir.join(id.as_str()).exists()
+            {
+                continue;
+            }
+
+            plugins.insert(id.to_owned(), locator.to_owned());
+        }
+
+        Ok(plugins)
+    }
+
     pub async fn load_tool(&self, id: &Id) -> miette::Result<Tool> {
         load_tool_with_proto(id, &self.env).await
     }
@@ -66,6 +174,28 @@ impl ProtoResource {
         self.load_tools_with_filters(HashSet::new()).await
     }
 
+    /// Load tools that have an inventory in `~/.proto/tools`, avoiding
+    /// plugins for tools that have never been installed.
+    pub async fn load_installed_tools(&self, filter: &[Id]) -> miette::Result<Vec<Tool>> {
+        let mut ids = HashSet::new();
+
+        if self.env.tools_dir.exists() {
+            for dir in fs::read_dir(&self.env.tools_dir)? {
+                let id = Id::raw(fs::file_name(dir.path()));
+
+                if filter.is_empty() || filter.contains(&id) {
+                    ids.insert(id);
+                }
+            }
+        }
+
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        self.load_tools_with_filters(ids.iter().collect()).await
+    }
+
     pub async fn load_tools_with_filters(&self, filter: HashSet<&Id>) -> miette::Result<Vec<Tool>> {
         let config = self.env.load_config()?;
 
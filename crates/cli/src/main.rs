@@ -7,7 +7,7 @@ mod shell;
 mod systems;
 mod telemetry;
 
-use app::{App as CLI, Commands, DebugCommands, ToolCommands};
+use app::{App as CLI, Commands, DebugCommands, PluginCommands, ShimsCommands, ToolCommands};
 use clap::Parser;
 use starbase::{tracing::TracingOptions, App, MainResult};
 use starbase_utils::string_vec;
@@ -26,8 +26,16 @@ async fn main() -> MainResult {
         env::set_var("STARBASE_LOG", level);
     }
 
+    // Applied through the `plugin-timeout` setting
+    if let Some(timeout) = cli.plugin_timeout {
+        env::set_var("PROTO_PLUGIN_TIMEOUT", timeout.to_string());
+    }
+
     App::setup_tracing_with_options(TracingOptions {
-        default_level: if matches!(cli.command, Commands::Bin { .. } | Commands::Run { .. }) {
+        default_level: if matches!(
+            cli.command,
+            Commands::Activate { .. } | Commands::Bin { .. } | Commands::Run { .. }
+        ) {
             LevelFilter::WARN
         } else if matches!(cli.command, Commands::Completions { .. }) {
             LevelFilter::OFF
@@ -61,6 +69,7 @@ async fn main() -> MainResult {
     app.analyze(systems::remove_old_bins);
 
     match cli.command {
+        Commands::Activate(args) => app.execute_with_args(commands::activate, args),
         Commands::Alias(args) => app.execute_with_args(commands::alias, args),
         Commands::Bin(args) => app.execute_with_args(commands::bin, args),
         Commands::Clean(args) => app.execute_with_args(commands::clean, args),
@@ -69,6 +78,11 @@ async fn main() -> MainResult {
             DebugCommands::Config(args) => app.execute_with_args(commands::debug::config, args),
             DebugCommands::Env => app.execute(commands::debug::env),
         },
+        Commands::Dedupe(args) => app.execute_with_args(commands::dedupe, args),
+        Commands::Doctor(args) => app.execute_with_args(commands::doctor, args),
+        Commands::Du(args) => app.execute_with_args(commands::du, args),
+        Commands::Env(args) => app.execute_with_args(commands::env, args),
+        Commands::Export(args) => app.execute_with_args(commands::export, args),
         Commands::Install(args) => app.execute_with_args(commands::install, args),
         Commands::InstallGlobal(args) => app.execute_with_args(commands::install_global, args),
         Commands::List(args) => app.execute_with_args(commands::list, args),
@@ -77,8 +91,20 @@ async fn main() -> MainResult {
         Commands::Migrate(args) => app.execute_with_args(commands::migrate, args),
         Commands::Outdated(args) => app.execute_with_args(commands::outdated, args),
         Commands::Pin(args) => app.execute_with_args(commands::pin, args),
+        Commands::Plugin { command } => match command {
+            PluginCommands::Outdated(args) => {
+                app.execute_with_args(commands::plugin::outdated, args)
+            }
+            PluginCommands::Update(args) => app.execute_with_args(commands::plugin::update, args),
+        },
+        Commands::Projects(args) => app.execute_with_args(commands::projects, args),
+        Commands::Regen(args) => app.execute_with_args(commands::regen, args),
         Commands::Run(args) => app.execute_with_args(commands::run, args),
         Commands::Setup(args) => app.execute_with_args(commands::setup, args),
+        Commands::Shell(args) => app.execute_with_args(commands::shell, args),
+        Commands::Shims { command } => match command {
+            ShimsCommands::Check(args) => app.execute_with_args(commands::shims::check, args),
+        },
         Commands::Tool { command } => match command {
             ToolCommands::Add(args) => app.execute_with_args(commands::tool::add, args),
             ToolCommands::Info(args) => app.execute_with_args(commands::tool::info, args),
@@ -93,6 +119,7 @@ async fn main() -> MainResult {
         Commands::UninstallGlobal(args) => app.execute_with_args(commands::uninstall_global, args),
         Commands::Upgrade => app.execute(commands::upgrade),
         Commands::Use => app.execute(commands::install_all),
+        Commands::Which(args) => app.execute_with_args(commands::which, args),
     };
 
     app.run().await?;
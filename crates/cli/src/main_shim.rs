@@ -2,12 +2,13 @@
 // so these imports primarily use std, and avoid fat crates.
 
 use anyhow::{anyhow, Result};
-use proto_shim::{exec_command_and_replace, locate_proto_exe};
+use proto_shim::{exec_command_and_replace, get_shim_cache_path, locate_proto_exe, SHIM_VERSION};
 use rust_json::{json_parse, JsonElem as Json};
 use std::collections::HashMap;
 use std::ffi::OsString;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::{env, fs};
 
 fn get_proto_home() -> Result<PathBuf> {
@@ -21,6 +22,118 @@ fn get_proto_home() -> Result<PathBuf> {
     Ok(home_dir.join(".proto"))
 }
 
+fn get_modified_time(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+// Attempt to load a resolved command from the cache that `proto run` writes
+// for the current directory. If anything has changed since it was written,
+// or the entry is not applicable, return nothing and fallback to `proto run`.
+fn create_cached_command(
+    proto_home_dir: &Path,
+    shim_name: &str,
+    user_args: &[OsString],
+) -> Option<Command> {
+    if env::var_os("PROTO_DETECT_STRATEGY").is_some() {
+        return None;
+    }
+
+    let cache_path = get_shim_cache_path(&proto_home_dir.join("shims"), &env::current_dir().ok()?);
+    let mut cache = json_parse(&fs::read_to_string(cache_path).ok()?).ok()?;
+
+    let Json::Object(entries) = &mut cache else {
+        return None;
+    };
+
+    let entry = entries.remove(shim_name)?;
+
+    // Expired, so that `proto run` can track usage
+    let Json::Number(expires_at) = entry["expires_at"] else {
+        return None;
+    };
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+
+    if now as f64 > expires_at {
+        return None;
+    }
+
+    // Config or version files have been created, modified, or removed
+    let Json::Object(files) = &entry["files"] else {
+        return None;
+    };
+
+    for (file, modified) in files {
+        match modified {
+            Json::Number(time) if get_modified_time(Path::new(file)) as f64 == *time => {}
+            _ => return None,
+        };
+    }
+
+    // A different version was explicitly requested
+    let Json::Object(env_vars) = &entry["env_vars"] else {
+        return None;
+    };
+
+    for (key, value) in env_vars {
+        if let (true, Ok(env_value), Json::Str(value)) =
+            (key.ends_with("_VERSION"), env::var(key), value)
+        {
+            if &env_value != value {
+                return None;
+            }
+        }
+    }
+
+    // Self-upgrades must be blocked by `proto run`
+    if let Json::Array(commands) = &entry["self_upgrade_commands"] {
+        if let Some(arg) = user_args
+            .iter()
+            .map(|arg| arg.to_string_lossy())
+            .find(|arg| !arg.starts_with('-'))
+        {
+            if commands
+                .iter()
+                .any(|command| matches!(command, Json::Str(command) if *command == arg))
+            {
+                return None;
+            }
+        }
+    }
+
+    // The executable was uninstalled
+    let Json::Str(bin) = &entry["bin"] else {
+        return None;
+    };
+
+    if !Path::new(bin).exists() {
+        return None;
+    }
+
+    let mut command = Command::new(bin);
+
+    if let Json::Array(args) = &entry["args"] {
+        for arg in args {
+            if let Json::Str(arg) = arg {
+                command.arg(arg);
+            }
+        }
+    }
+
+    for (key, value) in env_vars {
+        if let Json::Str(value) = value {
+            command.env(key, value);
+        }
+    }
+
+    Some(command)
+}
+
 fn create_command(args: Vec<OsString>, shim_name: &str) -> Result<Command> {
     let proto_home_dir = get_proto_home()?;
     let registry_path = proto_home_dir.join("shims").join("registry.json");
@@ -51,15 +164,10 @@ fn create_command(args: Vec<OsString>, shim_name: &str) -> Result<Command> {
         }
     }
 
-    if args.len() > 1 {
-        for (i, arg) in args.into_iter().enumerate() {
-            if i == 0 {
-                continue; // The exe
-            }
+    // Skip the exe
+    let user_args = args.into_iter().skip(1).collect::<Vec<_>>();
 
-            passthrough_args.push(arg);
-        }
-    }
+    passthrough_args.extend(user_args.iter().cloned());
 
     if let Json::Array(after_args) = &shim["after_args"] {
         for arg in after_args {
@@ -69,27 +177,36 @@ fn create_command(args: Vec<OsString>, shim_name: &str) -> Result<Command> {
         }
     }
 
-    // Create the command and handle alternate logic
-    let mut command = Command::new(locate_proto_exe("proto").unwrap_or_else(|| "proto".into()));
+    // Create the command from the cache, otherwise go through proto
+    let mut command = if let Some(mut command) =
+        create_cached_command(&proto_home_dir, shim_name, &user_args)
+    {
+        command.args(passthrough_args);
+        command
+    } else {
+        let mut command = Command::new(locate_proto_exe("proto").unwrap_or_else(|| "proto".into()));
 
-    // command.args(["run", "node", "--"]);
-    // command.arg("./docs/shim-test.mjs");
-    // command.arg("--version");
+        // command.args(["run", "node", "--"]);
+        // command.arg("./docs/shim-test.mjs");
+        // command.arg("--version");
 
-    if let Json::Str(parent_name) = &shim["parent"] {
-        command.args(["run", parent_name]);
+        if let Json::Str(parent_name) = &shim["parent"] {
+            command.args(["run", parent_name]);
 
-        if matches!(shim["alt_bin"], Json::Bool(true)) {
-            command.args(["--alt", shim_name]);
+            if matches!(shim["alt_bin"], Json::Bool(true)) {
+                command.args(["--alt", shim_name]);
+            }
+        } else {
+            command.args(["run", shim_name]);
         }
-    } else {
-        command.args(["run", shim_name]);
-    }
 
-    if !passthrough_args.is_empty() {
-        command.arg("--");
-        command.args(passthrough_args);
-    }
+        if !passthrough_args.is_empty() {
+            command.arg("--");
+            command.args(passthrough_args);
+        }
+
+        command
+    };
 
     if let Json::Object(env_vars) = &shim["env_vars"] {
         for (env, value) in env_vars {
@@ -116,6 +233,13 @@ pub fn main() -> Result<()> {
         .unwrap_or_default()
         .replace(".exe", "");
 
+    // Allow proto to verify that the installed binary matches its own shim version
+    if shim_name.contains("proto-shim") && args.get(1).is_some_and(|arg| arg == "--version") {
+        println!("{SHIM_VERSION}");
+
+        return Ok(());
+    }
+
     if shim_name.is_empty() || shim_name.contains("proto-shim") {
         return Err(anyhow!(
             "Invalid shim name detected. Unable to execute the appropriate proto tool.\nPlease refer to the documentation or ask for support on Discord."
@@ -161,7 +161,7 @@ impl<'std> Printer<'std> {
             PluginLocator::SourceFile { path, .. } => {
                 self.entry("Source", color::path(path.canonicalize().unwrap()));
             }
-            PluginLocator::SourceUrl { url } => {
+            PluginLocator::SourceUrl { url, .. } => {
                 self.entry("Source", color::url(url));
             }
             PluginLocator::GitHub(github) => {
@@ -178,6 +178,15 @@ impl<'std> Printer<'std> {
                     color::hash(wapm.version.as_deref().unwrap_or("latest")),
                 );
             }
+            PluginLocator::Oci(oci) => {
+                self.entry("Registry", color::url(&oci.registry));
+                self.entry("Repository", color::label(&oci.repository));
+                self.entry("Reference", color::hash(oci.get_reference()));
+            }
         };
+
+        if let Some(integrity) = locator.as_ref().get_integrity() {
+            self.entry("Integrity", color::hash(format!("sha256={integrity}")));
+        }
     }
 }
@@ -1,25 +1,100 @@
-use clap_complete::Shell;
-use dirs::home_dir;
+use clap::ValueEnum;
+use dirs::{config_dir, home_dir};
 use starbase_styles::color;
 use starbase_utils::fs;
 use std::{
-    env,
+    env, fmt,
     io::{self, BufRead},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 use tracing::debug;
 
-pub fn detect_shell(shell: Option<Shell>) -> Shell {
-    shell.or_else(Shell::from_env).unwrap_or({
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ShellType {
+    Bash,
+    Elvish,
+    Fish,
+    #[value(alias = "nushell")]
+    Nu,
+    #[value(name = "powershell", alias = "pwsh")]
+    PowerShell,
+    Xonsh,
+    Zsh,
+}
+
+impl ShellType {
+    /// Detect the shell from a path to its executable, for example `$SHELL`.
+    pub fn from_shell_path<P: AsRef<Path>>(path: P) -> Option<ShellType> {
+        let name = path.as_ref().file_stem()?.to_str()?;
+
+        match name {
+            "bash" => Some(ShellType::Bash),
+            "elvish" => Some(ShellType::Elvish),
+            "fish" => Some(ShellType::Fish),
+            "nu" | "nushell" => Some(ShellType::Nu),
+            "powershell" | "powershell_ise" | "pwsh" => Some(ShellType::PowerShell),
+            "xonsh" => Some(ShellType::Xonsh),
+            "zsh" => Some(ShellType::Zsh),
+            _ => None,
+        }
+    }
+
+    /// Detect the current shell from the environment. Nushell and Xonsh are
+    /// rarely the login shell, so check their own variables before `$SHELL`.
+    pub fn from_env() -> Option<ShellType> {
+        if env::var_os("NU_VERSION").is_some() {
+            return Some(ShellType::Nu);
+        }
+
+        if env::var_os("XONSH_VERSION").is_some() {
+            return Some(ShellType::Xonsh);
+        }
+
+        if let Some(env_shell) = env::var_os("SHELL") {
+            if let Some(shell) = ShellType::from_shell_path(env_shell) {
+                return Some(shell);
+            }
+        }
+
+        if cfg!(windows) || env::var_os("PSModulePath").is_some() {
+            return Some(ShellType::PowerShell);
+        }
+
+        None
+    }
+
+    /// Return the name of the executable to spawn the shell with.
+    pub fn get_bin_name(&self) -> &'static str {
+        match self {
+            ShellType::Bash => "bash",
+            ShellType::Elvish => "elvish",
+            ShellType::Fish => "fish",
+            ShellType::Nu => "nu",
+            ShellType::PowerShell if cfg!(windows) => "powershell",
+            ShellType::PowerShell => "pwsh",
+            ShellType::Xonsh => "xonsh",
+            ShellType::Zsh => "zsh",
+        }
+    }
+}
+
+impl fmt::Display for ShellType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_possible_value().unwrap().get_name())
+    }
+}
+
+pub fn detect_shell(shell: Option<ShellType>) -> ShellType {
+    shell.or_else(ShellType::from_env).unwrap_or({
         if cfg!(windows) {
-            Shell::PowerShell
+            ShellType::PowerShell
         } else {
-            Shell::Bash
+            ShellType::Bash
         }
     })
 }
 
-pub fn find_profiles(shell: &Shell) -> miette::Result<Vec<PathBuf>> {
+pub fn find_profiles(shell: &ShellType) -> miette::Result<Vec<PathBuf>> {
     debug!("Finding profile files for {}", shell);
 
     if let Ok(profile_env) = env::var("PROTO_SHELL_PROFILE") {
@@ -27,19 +102,28 @@ pub fn find_profiles(shell: &Shell) -> miette::Result<Vec<PathBuf>> {
     }
 
     let home_dir = home_dir().expect("Invalid home directory.");
-    let mut profiles = vec![home_dir.join(".profile")];
+    let config_dir = config_dir().unwrap_or_else(|| home_dir.join(".config"));
+    let mut profiles = vec![];
+
+    // These shells do not source the POSIX profile
+    if !matches!(
+        shell,
+        ShellType::Nu | ShellType::PowerShell | ShellType::Xonsh
+    ) {
+        profiles.push(home_dir.join(".profile"));
 
-    if let Ok(profile_env) = env::var("PROFILE") {
-        if !profile_env.is_empty() {
-            profiles.push(PathBuf::from(profile_env));
+        if let Ok(profile_env) = env::var("PROFILE") {
+            if !profile_env.is_empty() {
+                profiles.push(PathBuf::from(profile_env));
+            }
         }
     }
 
     match shell {
-        Shell::Bash => {
+        ShellType::Bash => {
             profiles.extend([home_dir.join(".bash_profile"), home_dir.join(".bashrc")]);
         }
-        Shell::Elvish => {
+        ShellType::Elvish => {
             profiles.push(home_dir.join(".elvish/rc.elv"));
 
             if let Ok(xdg_config) = env::var("XDG_CONFIG_HOME") {
@@ -52,10 +136,51 @@ pub fn find_profiles(shell: &Shell) -> miette::Result<Vec<PathBuf>> {
                 profiles.push(home_dir.join(".config/elvish/rc.elv"));
             }
         }
-        Shell::Fish => {
+        ShellType::Fish => {
             profiles.push(home_dir.join(".config/fish/config.fish"));
         }
-        Shell::Zsh => {
+        ShellType::Nu => {
+            // Nushell respects XDG on all platforms, otherwise
+            // falls back to the platform's config directory
+            let nu_dir = if let Ok(xdg_config) = env::var("XDG_CONFIG_HOME") {
+                PathBuf::from(xdg_config).join("nushell")
+            } else {
+                config_dir.join("nushell")
+            };
+
+            profiles.extend([nu_dir.join("config.nu"), nu_dir.join("env.nu")]);
+        }
+        ShellType::PowerShell => {
+            if cfg!(windows) {
+                let docs_dir = dirs::document_dir().unwrap_or_else(|| home_dir.join("Documents"));
+
+                profiles.extend([
+                    docs_dir.join("WindowsPowerShell/Microsoft.PowerShell_profile.ps1"),
+                    docs_dir.join("PowerShell/Microsoft.PowerShell_profile.ps1"),
+                ]);
+            } else {
+                let ps_dir = if let Ok(xdg_config) = env::var("XDG_CONFIG_HOME") {
+                    PathBuf::from(xdg_config).join("powershell")
+                } else {
+                    home_dir.join(".config/powershell")
+                };
+
+                profiles.extend([
+                    ps_dir.join("profile.ps1"),
+                    ps_dir.join("Microsoft.PowerShell_profile.ps1"),
+                ]);
+            }
+        }
+        ShellType::Xonsh => {
+            let xonsh_dir = if let Ok(xdg_config) = env::var("XDG_CONFIG_HOME") {
+                PathBuf::from(xdg_config).join("xonsh")
+            } else {
+                home_dir.join(".config/xonsh")
+            };
+
+            profiles.extend([xonsh_dir.join("rc.xsh"), home_dir.join(".xonshrc")]);
+        }
+        ShellType::Zsh => {
             let zdot_dir = if let Ok(dir) = env::var("ZDOTDIR") {
                 PathBuf::from(dir)
             } else {
@@ -64,52 +189,159 @@ pub fn find_profiles(shell: &Shell) -> miette::Result<Vec<PathBuf>> {
 
             profiles.extend([zdot_dir.join(".zprofile"), zdot_dir.join(".zshrc")]);
         }
-        _ => {}
     };
 
     Ok(profiles)
 }
 
-pub fn format_env_var(shell: &Shell, key: &str, value: &str) -> Option<String> {
-    match shell {
-        Shell::Bash | Shell::Zsh => Some(if key == "PATH" {
-            format!(r#"export PATH="{value}:$PATH""#)
-        } else {
-            format!(r#"export {key}="{value}""#)
-        }),
-        Shell::Elvish => Some(if key == "PATH" {
-            format!(r#"set-env PATH (str:join ':' [{value} $E:PATH])"#)
-        } else {
-            format!(r#"set-env {key} {value}"#)
-        }),
-        Shell::Fish => Some(if key == "PATH" {
-            format!(r#"set -gx PATH "{value}" $PATH"#)
+// Values are written in POSIX syntax (`$VAR/path`), so replace variable
+// references with the syntax of shells that do not support it
+fn replace_env_vars(value: &str, replacer: impl Fn(&str) -> String) -> String {
+    let mut result = String::new();
+    let mut chars = value.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '$' {
+            result.push(ch);
+            continue;
+        }
+
+        let mut name = String::new();
+
+        while let Some(next) = chars.peek() {
+            if next.is_ascii_alphanumeric() || *next == '_' {
+                name.push(*next);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        if name.is_empty() {
+            result.push(ch);
         } else {
-            format!(r#"set -gx {key} "{value}""#)
-        }),
-        _ => None,
+            result.push_str(&replacer(&name));
+        }
+    }
+
+    result
+}
+
+fn split_path_value(value: &str) -> Vec<String> {
+    env::split_paths(value)
+        .map(|path| path.to_string_lossy().to_string())
+        .collect()
+}
+
+pub fn format_env_var(shell: &ShellType, key: &str, value: &str) -> String {
+    match shell {
+        ShellType::Bash | ShellType::Zsh => {
+            if key == "PATH" {
+                format!(r#"export PATH="{value}:$PATH""#)
+            } else {
+                format!(r#"export {key}="{value}""#)
+            }
+        }
+        ShellType::Elvish => {
+            if key == "PATH" {
+                format!(r#"set-env PATH (str:join ':' [{value} $E:PATH])"#)
+            } else {
+                format!(r#"set-env {key} {value}"#)
+            }
+        }
+        ShellType::Fish => {
+            if key == "PATH" {
+                format!(r#"set -gx PATH "{value}" $PATH"#)
+            } else {
+                format!(r#"set -gx {key} "{value}""#)
+            }
+        }
+        ShellType::Nu => {
+            let format_value = |value: &str| {
+                if value.contains('$') {
+                    format!(
+                        r#"$"{}""#,
+                        replace_env_vars(value, |name| format!("($env.{name})"))
+                    )
+                } else {
+                    format!(r#""{value}""#)
+                }
+            };
+
+            if key == "PATH" {
+                let paths = split_path_value(value)
+                    .iter()
+                    .map(|path| format_value(path))
+                    .collect::<Vec<_>>();
+
+                format!(
+                    r#"$env.PATH = ($env.PATH | split row (char esep) | prepend [{}])"#,
+                    paths.join(" ")
+                )
+            } else {
+                format!(r#"$env.{key} = {}"#, format_value(value))
+            }
+        }
+        ShellType::PowerShell => {
+            let format_value = |value: &str| {
+                format!(
+                    r#""{}""#,
+                    replace_env_vars(value, |name| format!("$env:{name}"))
+                )
+            };
+
+            if key == "PATH" {
+                let paths = split_path_value(value)
+                    .iter()
+                    .map(|path| format_value(path))
+                    .collect::<Vec<_>>();
+
+                format!(
+                    r#"$env:PATH = @({}, $env:PATH) -join [IO.Path]::PathSeparator"#,
+                    paths.join(", ")
+                )
+            } else {
+                format!(r#"$env:{key} = {}"#, format_value(value))
+            }
+        }
+        ShellType::Xonsh => {
+            let format_value = |value: &str| {
+                if value.contains('$') {
+                    format!(
+                        r#"f"{}""#,
+                        replace_env_vars(value, |name| format!("{{${name}}}"))
+                    )
+                } else {
+                    format!(r#""{value}""#)
+                }
+            };
+
+            if key == "PATH" {
+                split_path_value(value)
+                    .iter()
+                    .rev()
+                    .map(|path| format!("$PATH.insert(0, {})", format_value(path)))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            } else {
+                format!("${key} = {}", format_value(value))
+            }
+        }
     }
 }
 
-pub fn format_env_vars(
-    shell: &Shell,
-    comment: &str,
-    vars: Vec<(String, String)>,
-) -> Option<String> {
+pub fn format_env_vars(shell: &ShellType, comment: &str, vars: Vec<(String, String)>) -> String {
     let mut lines = vec![format!("\n# {comment}")];
 
     for (key, value) in vars {
-        match format_env_var(shell, &key, &value) {
-            Some(var) => lines.push(var),
-            None => return None,
-        };
+        lines.push(format_env_var(shell, &key, &value));
     }
 
-    Some(lines.join("\n"))
+    lines.join("\n")
 }
 
 pub fn write_profile_if_not_setup(
-    shell: &Shell,
+    shell: &ShellType,
     contents: String,
     env_var: &str,
 ) -> miette::Result<Option<PathBuf>> {
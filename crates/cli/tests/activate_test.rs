@@ -0,0 +1,133 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod activate {
+    use super::*;
+
+    #[test]
+    fn prints_bash_hook() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("activate").arg("bash").assert().success();
+
+        assert.stdout(
+            predicate::str::contains("proto activate bash --export")
+                .and(predicate::str::contains("PROMPT_COMMAND"))
+                .and(predicate::str::contains(r#""$PWD""#)),
+        );
+    }
+
+    #[test]
+    fn prints_zsh_hook() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("activate").arg("zsh").assert().success();
+
+        assert.stdout(
+            predicate::str::contains("proto activate zsh --export")
+                .and(predicate::str::contains("precmd_functions")),
+        );
+    }
+
+    #[test]
+    fn prints_fish_hook() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("activate").arg("fish").assert().success();
+
+        assert.stdout(predicate::str::contains(
+            "proto activate fish --export | source",
+        ));
+    }
+
+    #[test]
+    fn errors_for_unsupported_shell() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("activate").arg("elvish").assert().failure();
+
+        assert.stderr(predicate::str::contains("is not supported for activation"));
+    }
+
+    mod export {
+        use super::*;
+
+        #[test]
+        fn exports_path_without_tools() {
+            let sandbox = create_empty_sandbox();
+
+            let mut cmd = create_proto_command(sandbox.path());
+            let assert = cmd
+                .arg("activate")
+                .arg("bash")
+                .arg("--export")
+                .env("PATH", "/usr/bin")
+                .assert()
+                .success();
+
+            assert.stdout(
+                predicate::str::contains("export PATH='/usr/bin';")
+                    .and(predicate::str::contains("unset _PROTO_ACTIVATED_PATHS;")),
+            );
+        }
+
+        #[test]
+        fn removes_previously_activated_paths_and_vars() {
+            let sandbox = create_empty_sandbox();
+
+            let mut cmd = create_proto_command(sandbox.path());
+            let assert = cmd
+                .arg("activate")
+                .arg("fish")
+                .arg("--export")
+                .env("PATH", "/old/bin:/usr/bin")
+                .env("_PROTO_ACTIVATED_PATHS", "/old/bin")
+                .env("_PROTO_ACTIVATED_VARS", "PROTO_NODE_VERSION")
+                .assert()
+                .success();
+
+            assert.stdout(
+                predicate::str::contains("set -gx PATH '/usr/bin';")
+                    .and(predicate::str::contains("set -e PROTO_NODE_VERSION;")),
+            );
+        }
+
+        #[test]
+        fn quotes_exported_values() {
+            let sandbox = create_empty_sandbox();
+
+            let mut cmd = create_proto_command(sandbox.path());
+            let assert = cmd
+                .arg("activate")
+                .arg("bash")
+                .arg("--export")
+                .env("PATH", "/it's/bin:/usr/$HOME/bin")
+                .assert()
+                .success();
+
+            assert.stdout(predicate::str::contains(
+                r#"export PATH='/it'\''s/bin:/usr/$HOME/bin';"#,
+            ));
+        }
+
+        #[test]
+        fn caches_activated_environment() {
+            let sandbox = create_empty_sandbox();
+
+            let mut cmd = create_proto_command(sandbox.path());
+            cmd.arg("activate")
+                .arg("zsh")
+                .arg("--export")
+                .assert()
+                .success();
+
+            assert!(sandbox.path().join(".proto/temp/activate").exists());
+        }
+    }
+}
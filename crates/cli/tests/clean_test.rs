@@ -1,5 +1,6 @@
 mod utils;
 
+use starbase_sandbox::predicates::prelude::*;
 use utils::*;
 
 mod clean {
@@ -13,6 +14,31 @@ mod clean {
         cmd.arg("clean").arg("--yes").assert().success();
     }
 
+    #[test]
+    fn prunes_expired_shim_cache() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".proto/shims/cache/expired.json",
+            r#"{ "node": { "bin": "node", "expires_at": 1 } }"#,
+        );
+        sandbox.create_file(
+            ".proto/shims/cache/valid.json",
+            r#"{ "node": { "bin": "node", "expires_at": 1 }, "npm": { "bin": "npm", "expires_at": 99999999999 } }"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("clean").arg("--yes").assert().success();
+
+        assert!(!sandbox
+            .path()
+            .join(".proto/shims/cache/expired.json")
+            .exists());
+        assert!(sandbox
+            .path()
+            .join(".proto/shims/cache/valid.json")
+            .exists());
+    }
+
     #[test]
     fn purges_tool_inventory() {
         let sandbox = create_empty_sandbox();
@@ -72,6 +98,7 @@ mod clean {
         sandbox.create_file(".proto/shims/npm.exe", "");
         sandbox.create_file(".proto/shims/npx", "");
         sandbox.create_file(".proto/shims/npx.exe", "");
+        sandbox.create_file(".proto/shims/cache/a1b2c3.json", "{}");
 
         let mut cmd = create_proto_command(sandbox.path());
         cmd.arg("clean")
@@ -88,6 +115,8 @@ mod clean {
             assert!(!sandbox.path().join(".proto/shims/npm").exists());
             assert!(!sandbox.path().join(".proto/shims/npx").exists());
         }
+
+        assert!(!sandbox.path().join(".proto/shims/cache").exists());
     }
 
     #[test]
@@ -113,3 +142,114 @@ mod clean {
             .exists());
     }
 }
+
+mod clean_dry_run {
+    use super::*;
+
+    fn create_node_manifest(sandbox: &starbase_sandbox::Sandbox) {
+        sandbox.create_file(".proto/tools/node/18.0.0/index.js", "");
+        sandbox.create_file(".proto/tools/node/19.0.0/index.js", "");
+        sandbox.create_file(".proto/tools/node/20.0.0/index.js", "");
+        sandbox.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{
+  "installed_versions": ["18.0.0", "19.0.0", "20.0.0"],
+  "versions": {
+    "18.0.0": { "installed_at": 0, "last_used_at": 0 },
+    "19.0.0": { "installed_at": 0, "last_used_at": 0 },
+    "20.0.0": { "installed_at": 0, "last_used_at": 0 }
+  }
+}"#,
+        );
+    }
+
+    #[test]
+    fn doesnt_remove_anything() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/plugins/stale_plugin.wasm", "");
+        create_node_manifest(&sandbox);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("clean")
+            .arg("--days")
+            .arg("0")
+            .arg("--dry-run")
+            .assert()
+            .success();
+
+        assert.stdout(
+            predicate::str::contains("stale_plugin.wasm")
+                .and(predicate::str::contains("18.0.0"))
+                .and(predicate::str::contains("not used in over 0 days")),
+        );
+
+        assert!(sandbox
+            .path()
+            .join(".proto/plugins/stale_plugin.wasm")
+            .exists());
+        assert!(sandbox.path().join(".proto/tools/node/18.0.0").exists());
+    }
+
+    #[test]
+    fn prints_json_report() {
+        let sandbox = create_empty_sandbox();
+        create_node_manifest(&sandbox);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("clean")
+            .arg("--dry-run")
+            .arg("--json")
+            .assert()
+            .success();
+
+        assert.stdout(
+            predicate::str::contains("\"dry_run\": true")
+                .and(predicate::str::contains("\"type\": \"unused\"")),
+        );
+    }
+
+    #[test]
+    fn keeps_newest_versions() {
+        let sandbox = create_empty_sandbox();
+        create_node_manifest(&sandbox);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("clean")
+            .arg("--dry-run")
+            .arg("--json")
+            .arg("--keep")
+            .arg("2")
+            .assert()
+            .success();
+
+        assert.stdout(
+            predicate::str::contains("18.0.0")
+                .and(predicate::str::contains("19.0.0").not())
+                .and(predicate::str::contains("20.0.0").not()),
+        );
+    }
+
+    #[test]
+    fn skips_pinned_versions() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"19.0.0\"");
+        create_node_manifest(&sandbox);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("clean")
+            .arg("--dry-run")
+            .arg("--json")
+            .assert()
+            .success();
+
+        assert.stdout(
+            predicate::str::contains("18.0.0")
+                .and(predicate::str::contains("19.0.0").not())
+                .and(predicate::str::contains("20.0.0")),
+        );
+    }
+}
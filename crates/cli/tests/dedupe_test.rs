@@ -0,0 +1,23 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod dedupe {
+    use super::*;
+
+    #[test]
+    fn prunes_unreferenced_store_files() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/store/ab/abcdef", "content");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("dedupe").assert().success();
+
+        assert.stderr(predicate::str::contains("Deduplicated 0 files"));
+
+        if cfg!(unix) {
+            assert!(!sandbox.path().join(".proto/store/ab/abcdef").exists());
+        }
+    }
+}
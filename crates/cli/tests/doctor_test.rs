@@ -0,0 +1,98 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod doctor {
+    use super::*;
+
+    #[test]
+    fn prints_json_report() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("doctor").arg("--json").assert();
+
+        assert.stdout(
+            predicate::str::contains(r#""checks": ["#)
+                .and(predicate::str::contains(r#""category": "Environment""#)),
+        );
+    }
+
+    #[test]
+    fn checks_proto_shim_version() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("doctor").arg("--json").assert();
+
+        assert.stdout(predicate::str::contains(
+            r#""name": "proto-shim",
+      "status": "pass""#,
+        ));
+    }
+
+    #[test]
+    fn fails_when_shims_not_on_path() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("doctor").env("PATH", "").assert().failure();
+
+        assert.stdout(predicate::str::contains("is not on PATH"));
+    }
+
+    #[test]
+    fn warns_when_bin_before_shims() {
+        let sandbox = create_empty_sandbox();
+
+        let path = std::env::join_paths([
+            sandbox.path().join(".proto/bin"),
+            sandbox.path().join(".proto/shims"),
+        ])
+        .unwrap();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("doctor").env("PATH", path).assert();
+
+        assert.stdout(predicate::str::contains(
+            "Bin directory is before the shims directory",
+        ));
+    }
+
+    #[test]
+    fn fails_for_invalid_manifest() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/tools/node/manifest.json", "{ invalid");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("doctor").assert().failure();
+
+        assert.stdout(predicate::str::contains("Unable to parse"));
+    }
+
+    #[test]
+    fn warns_for_legacy_manifest_aliases() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".proto/tools/node/manifest.json",
+            r#"{ "aliases": { "work": "18.0.0" } }"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("doctor").assert();
+
+        assert.stdout(predicate::str::contains("proto migrate v0.24"));
+    }
+
+    #[test]
+    fn warns_for_missing_shims() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/shims/registry.json", r#"{ "node": {} }"#);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("doctor").assert();
+
+        assert.stdout(predicate::str::contains("Missing shims for node"));
+    }
+}
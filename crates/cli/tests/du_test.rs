@@ -0,0 +1,63 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod du {
+    use super::*;
+
+    #[test]
+    fn reports_cache_sizes() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/plugins/node_plugin.wasm", "1234567890");
+        sandbox.create_file(".proto/temp/download.tar.gz", "12345");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("du").assert().success();
+
+        assert.stdout(
+            predicate::str::contains("Plugins")
+                .and(predicate::str::contains("10 B"))
+                .and(predicate::str::contains("Temporary"))
+                .and(predicate::str::contains("5 B"))
+                .and(predicate::str::contains("15 B")),
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn counts_hard_linked_files_once() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/store/ab/abcdef", "1234567890");
+        sandbox.create_file(".proto/temp/download.tar.gz", "12345");
+        sandbox.create_file(".proto/plugins/.gitkeep", "");
+
+        std::fs::hard_link(
+            sandbox.path().join(".proto/store/ab/abcdef"),
+            sandbox.path().join(".proto/plugins/node_plugin.wasm"),
+        )
+        .unwrap();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("du").arg("--json").assert().success();
+
+        assert.stdout(
+            predicate::str::contains(r#""dir": ""#)
+                .and(predicate::str::contains(".proto/store"))
+                .and(predicate::str::contains("\"size\": 15\n}")),
+        );
+    }
+
+    #[test]
+    fn prints_json_report() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/plugins/node_plugin.wasm", "1234567890");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("du").arg("--json").assert().success();
+
+        assert.stdout(
+            predicate::str::contains("\"tools\": []").and(predicate::str::contains("\"size\": 10")),
+        );
+    }
+}
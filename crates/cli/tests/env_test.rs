@@ -0,0 +1,93 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod env {
+    use super::*;
+
+    #[test]
+    fn errors_if_no_tools_configured() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("env").assert().failure();
+
+        assert.stderr(predicate::str::contains("No tools have been configured"));
+    }
+
+    #[test]
+    fn errors_for_invalid_tool_spec() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("env").arg("node@unknown!").assert().failure();
+
+        assert.stderr(predicate::str::contains("invalid value 'node@unknown!'"));
+    }
+
+    #[test]
+    fn prints_exports_for_tool() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("env")
+            .arg("node@19.0.0")
+            .arg("--shell")
+            .arg("bash")
+            .assert()
+            .success();
+
+        let output = output_to_string(&assert.get_output().stdout);
+
+        assert!(output.contains(r#"export PROTO_NODE_VERSION="19.0.0""#));
+        assert!(output.contains("export PROTO_NODE_BIN="));
+        assert!(output.contains("export PATH="));
+        assert!(!sandbox.path().join(".prototools").exists());
+    }
+
+    #[test]
+    fn prints_exports_for_configured_tools() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", r#"node = "19.0.0""#);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("env").arg("--shell").arg("fish").assert().success();
+
+        let output = output_to_string(&assert.get_output().stdout);
+
+        assert!(output.contains(r#"set -gx PROTO_NODE_VERSION "19.0.0""#));
+    }
+}
+
+mod shell {
+    use super::*;
+
+    #[test]
+    fn errors_if_no_tools_configured() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("shell").assert().failure();
+
+        assert.stderr(predicate::str::contains("No tools have been configured"));
+    }
+
+    #[test]
+    fn spawns_shell_with_tool_env() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("shell")
+            .arg("node@19.0.0")
+            .arg("--shell")
+            .arg("bash")
+            .write_stdin("echo $PROTO_NODE_VERSION\n")
+            .assert()
+            .success();
+
+        assert.stdout(predicate::str::contains("19.0.0"));
+    }
+}
@@ -0,0 +1,105 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod export {
+    use super::*;
+
+    #[test]
+    fn errors_if_no_tools_configured() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("export")
+            .arg("--format")
+            .arg("tool-versions")
+            .assert();
+
+        assert.stderr(predicate::str::contains("No tools have been configured"));
+    }
+
+    #[test]
+    fn exports_tool_versions() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"go = "1.21.0"
+node = "19"
+"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("export")
+            .arg("--format")
+            .arg("tool-versions")
+            .assert()
+            .success();
+
+        let output = output_to_string(&assert.get_output().stdout);
+
+        assert!(output.contains("golang 1.21.0"));
+        assert!(output.contains("nodejs 19.9.0"));
+    }
+
+    #[test]
+    fn exports_devcontainer_features() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", r#"node = "19.0.0""#);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("export")
+            .arg("--format")
+            .arg("devcontainer")
+            .assert()
+            .success();
+
+        let output = output_to_string(&assert.get_output().stdout);
+
+        assert!(output.contains("ghcr.io/devcontainers/features/node:1"));
+        assert!(output.contains(r#""version": "19.0.0""#));
+    }
+
+    #[test]
+    fn exports_dockerfile_with_prototools() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", r#"node = "19.0.0""#);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("export")
+            .arg("--format")
+            .arg("dockerfile")
+            .assert()
+            .success();
+
+        let output = output_to_string(&assert.get_output().stdout);
+
+        assert!(output.contains("COPY <<EOF .prototools"));
+        assert!(output.contains(r#"node = "19.0.0""#));
+        assert!(output.contains("RUN proto use"));
+    }
+
+    #[test]
+    fn writes_to_file() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", r#"node = "19.0.0""#);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("export")
+            .arg("--format")
+            .arg("tool-versions")
+            .arg("--to")
+            .arg(".tool-versions")
+            .assert()
+            .success();
+
+        assert_eq!(
+            std::fs::read_to_string(sandbox.path().join(".tool-versions")).unwrap(),
+            "nodejs 19.0.0\n"
+        );
+    }
+}
@@ -0,0 +1,38 @@
+;; A plugin whose functions return static JSON, for testing tool requirements.
+;; Compiled with `wat` to `dep.wasm`.
+(module
+  (import "env" "extism_alloc" (func $alloc (param i64) (result i64)))
+  (import "env" "extism_store_u8" (func $store_u8 (param i64 i32)))
+  (import "env" "extism_output_set" (func $output_set (param i64 i64)))
+
+  (memory (export "memory") 1)
+  (data (i32.const 0) "{\22name\22:\22Dep\22,\22type\22:\22CLI\22}")
+  (data (i32.const 1024) "{\22versions\22:[\221.0.0\22,\221.5.0\22,\222.0.0\22]}")
+  (data (i32.const 2048) "{}")
+
+  ;; Copy bytes from linear memory to extism memory and set as the output
+  (func $output (param $offset i32) (param $len i32)
+    (local $ptr i64)
+    (local $i i32)
+    (local.set $ptr (call $alloc (i64.extend_i32_u (local.get $len))))
+    (block $done
+      (loop $copy
+        (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+        (call $store_u8
+          (i64.add (local.get $ptr) (i64.extend_i32_u (local.get $i)))
+          (i32.load8_u (i32.add (local.get $offset) (local.get $i))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $copy)))
+    (call $output_set (local.get $ptr) (i64.extend_i32_u (local.get $len))))
+
+  (func (export "register_tool") (result i32)
+    (call $output (i32.const 0) (i32.const 27))
+    (i32.const 0))
+
+  (func (export "load_versions") (result i32)
+    (call $output (i32.const 1024) (i32.const 38))
+    (i32.const 0))
+
+  (func (export "locate_executables") (result i32)
+    (call $output (i32.const 2048) (i32.const 2))
+    (i32.const 0)))
@@ -137,6 +137,29 @@ mod install_uninstall {
         assert!(sandbox.path().join(".proto/shims/registry.json").exists());
     }
 
+    #[cfg(not(windows))]
+    #[test]
+    fn creates_shims_for_existing_globals() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/tools/node/globals/bin/some-global", "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert();
+
+        assert!(sandbox.path().join(".proto/shims/some-global").exists());
+
+        let registry =
+            std::fs::read_to_string(sandbox.path().join(".proto/shims/registry.json")).unwrap();
+
+        assert!(registry.contains(r#""some-global": {"#));
+        assert!(registry.contains(r#""parent": "node""#));
+    }
+
     #[test]
     fn updates_the_manifest_when_installing() {
         let sandbox = create_empty_sandbox();
@@ -472,4 +495,50 @@ mod install_uninstall {
             assert!(link.exists());
         }
     }
+
+    mod requirements {
+        use super::*;
+
+        #[test]
+        fn errors_for_circular_requirements() {
+            let sandbox = create_empty_sandbox_with_requirements("");
+
+            let mut cmd = create_proto_command(sandbox.path());
+            let assert = cmd.arg("install").arg("cycle-a").arg("1.0.0").assert();
+
+            assert.failure().stderr(predicate::str::contains(
+                "Cycle B requires cycle-a, which creates a cycle between tools.",
+            ));
+        }
+
+        #[test]
+        fn errors_if_pinned_version_doesnt_satisfy() {
+            let sandbox = create_empty_sandbox_with_requirements(r#"dep = "2.0.0""#);
+
+            let mut cmd = create_proto_command(sandbox.path());
+            let assert = cmd.arg("install").arg("app").arg("1.0.0").assert();
+
+            assert.failure().stderr(predicate::str::contains(
+                "App requires dep ^1.0.0, but version 2.0.0 was detected.",
+            ));
+        }
+
+        #[test]
+        fn prefers_pinned_version() {
+            let sandbox = create_empty_sandbox_with_requirements(r#"dep = "1.5.0""#);
+
+            create_fake_install(&sandbox, "app", "1.0.0");
+            create_fake_install(&sandbox, "dep", "1.5.0");
+
+            let mut cmd = create_proto_command(sandbox.path());
+            let assert = cmd.arg("install").arg("app").arg("1.0.0").assert();
+
+            assert.success().stderr(
+                predicate::str::contains("Required tool is installed")
+                    .and(predicate::str::contains("version=\"1.5.0\"")),
+            );
+
+            assert!(!sandbox.path().join(".proto/tools/dep/1.0.0").exists());
+        }
+    }
 }
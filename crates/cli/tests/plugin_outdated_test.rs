@@ -0,0 +1,43 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod plugin_outdated {
+    use super::*;
+
+    #[test]
+    fn skips_plugins_without_release() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[plugins]
+foo = "github:moonrepo/foo"
+bar = "source:https://moonrepo.dev/path/file.wasm"
+"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("plugin").arg("outdated").assert().success();
+
+        assert.stderr(predicate::str::contains(
+            "No plugins are pinned to a GitHub release",
+        ));
+    }
+
+    #[test]
+    fn prints_empty_json() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("plugin")
+            .arg("outdated")
+            .arg("--json")
+            .assert()
+            .success();
+
+        assert.stdout(predicate::str::contains("[]"));
+    }
+}
@@ -0,0 +1,28 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod plugin_update {
+    use super::*;
+
+    #[test]
+    fn skips_unused_builtin_plugins() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("plugin").arg("update").assert().success();
+
+        assert.stderr(predicate::str::contains("No plugins to update"));
+    }
+
+    #[test]
+    fn errors_for_unknown_plugin() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("plugin").arg("update").arg("unknown").assert();
+
+        assert.stderr(predicate::str::contains("unknown is not a built-in tool"));
+    }
+}
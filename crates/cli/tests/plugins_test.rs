@@ -55,6 +55,7 @@ mod plugins {
                 PluginLocator::SourceFile {
                     file: "./tests/fixtures/moon-schema.toml".into(),
                     path: root_dir.join("./tests/fixtures/moon-schema.toml"),
+                    integrity: None,
                 },
             )
         })
@@ -73,6 +74,7 @@ mod plugins {
                 PluginLocator::SourceFile {
                     file: "./some/fake/path.toml".into(),
                     path: root_dir.join("./some/fake/path.toml"),
+                    integrity: None,
                 },
             )
         })
@@ -88,6 +90,7 @@ mod plugins {
                 PluginLocator::SourceUrl {
                     url: "https://raw.githubusercontent.com/moonrepo/moon/master/proto-plugin.toml"
                         .into(),
+                    integrity: None,
                 },
             )
         })
@@ -104,6 +107,7 @@ mod plugins {
                 PluginLocator::SourceUrl {
                     url: "https://raw.githubusercontent.com/moonrepo/moon/some/fake/path.toml"
                         .into(),
+                    integrity: None,
                 },
             )
         })
@@ -0,0 +1,46 @@
+mod utils;
+
+use proto_core::{ProjectRegistry, ProtoEnvironment};
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod projects {
+    use super::*;
+
+    #[test]
+    fn lists_tracked_projects() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"18.0.0\"");
+
+        ProjectRegistry::track(
+            ProtoEnvironment::new_testing(sandbox.path()),
+            vec![sandbox.path().to_path_buf()],
+        )
+        .unwrap();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("projects").assert().success();
+
+        assert.stdout(predicate::str::contains("node - 18.0.0"));
+    }
+
+    #[test]
+    fn prunes_missing_projects() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"18.0.0\"");
+        sandbox.create_file(
+            ".proto/projects.json",
+            r#"{ "/missing/project": { "last_loaded_at": 0 } }"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("projects").arg("--json").assert().success();
+
+        assert.stdout(predicate::str::contains("/missing/project").not());
+
+        let registry =
+            std::fs::read_to_string(sandbox.path().join(".proto/projects.json")).unwrap();
+
+        assert!(!registry.contains("/missing/project"));
+    }
+}
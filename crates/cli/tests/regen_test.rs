@@ -0,0 +1,107 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod regen {
+    use super::*;
+
+    #[test]
+    fn dry_run_lists_orphans_without_removing() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/shims/orphan", "");
+        sandbox.create_file(".proto/bin/orphan", "");
+        sandbox.create_file(".proto/shims/registry.json", r#"{ "orphan": {} }"#);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("regen").arg("--dry-run").assert().success();
+
+        assert.stdout(predicate::str::contains("orphan"));
+
+        assert!(sandbox.path().join(".proto/shims/orphan").exists());
+        assert!(sandbox.path().join(".proto/bin/orphan").exists());
+    }
+
+    #[test]
+    fn removes_orphans_and_rewrites_registry() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/shims/orphan", "");
+        sandbox.create_file(".proto/bin/orphan", "");
+        sandbox.create_file(".proto/shims/registry.json", r#"{ "orphan": {} }"#);
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("regen").arg("--yes").assert().success();
+
+        assert!(!sandbox.path().join(".proto/shims/orphan").exists());
+        assert!(!sandbox.path().join(".proto/bin/orphan").exists());
+        assert!(!sandbox.path().join(".proto/shims/registry.json").exists());
+    }
+
+    #[test]
+    fn doesnt_remove_proto_binaries() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/bin/proto", "");
+        sandbox.create_file(".proto/bin/proto-shim", "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("regen").arg("--yes").assert().success();
+
+        assert!(sandbox.path().join(".proto/bin/proto").exists());
+        assert!(sandbox.path().join(".proto/bin/proto-shim").exists());
+    }
+
+    #[test]
+    fn regenerates_shims_for_installed_tools() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert()
+            .success();
+
+        let shim_path = sandbox.path().join(".proto/shims").join(if cfg!(windows) {
+            "node.exe"
+        } else {
+            "node"
+        });
+
+        std::fs::remove_file(&shim_path).unwrap();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("regen").arg("--yes").assert().success();
+
+        assert!(shim_path.exists());
+        assert!(sandbox.path().join(".proto/shims/registry.json").exists());
+    }
+
+    #[test]
+    fn keeps_bins_for_installed_tools() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert()
+            .success();
+
+        let bin_path =
+            sandbox
+                .path()
+                .join(".proto/bin")
+                .join(if cfg!(windows) { "node.exe" } else { "node" });
+
+        assert!(bin_path.exists());
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("regen").arg("--yes").assert().success();
+
+        assert!(bin_path.exists());
+    }
+}
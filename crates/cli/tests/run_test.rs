@@ -255,4 +255,25 @@ mod run {
             "plugin-name is not a built-in tool or has not been configured as a plugin",
         ));
     }
+
+    #[test]
+    fn errors_if_requirement_not_installed() {
+        let sandbox = create_empty_sandbox_with_requirements(r#"dep = "1.5.0""#);
+
+        create_fake_install(&sandbox, "app", "1.0.0");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("run")
+            .arg("app")
+            .arg("1.0.0")
+            .env("PROTO_AUTO_INSTALL", "false")
+            .assert();
+
+        assert.failure().stderr(predicate::str::contains(
+            "This project requires Dep 1.5.0, but this version has not been installed",
+        ));
+
+        assert!(!sandbox.path().join(".proto/tools/dep/1.5.0").exists());
+    }
 }
@@ -0,0 +1,69 @@
+mod utils;
+
+use utils::*;
+
+#[cfg(not(windows))]
+mod setup {
+    use super::*;
+
+    fn setup_shell(sandbox: &Sandbox, shell: &str) -> String {
+        let profile = sandbox.path().join("profile");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("setup")
+            .arg("--shell")
+            .arg(shell)
+            .env("PROTO_SHELL_PROFILE", &profile)
+            .assert()
+            .success();
+
+        std::fs::read_to_string(profile).unwrap()
+    }
+
+    #[test]
+    fn writes_bash_profile() {
+        let sandbox = create_empty_sandbox();
+        let profile = setup_shell(&sandbox, "bash");
+
+        assert!(profile.contains(r#"export PROTO_HOME="$HOME/.proto""#));
+        assert!(profile.contains(r#"export PATH="$PROTO_HOME/shims:$PROTO_HOME/bin:$PATH""#));
+    }
+
+    #[test]
+    fn writes_nu_profile() {
+        let sandbox = create_empty_sandbox();
+        let profile = setup_shell(&sandbox, "nu");
+
+        assert!(profile.contains(r#"$env.PROTO_HOME = $"($env.HOME)/.proto""#));
+        assert!(profile.contains(r#"$env.PATH = ($env.PATH | split row (char esep) | prepend [$"($env.PROTO_HOME)/shims" $"($env.PROTO_HOME)/bin"])"#));
+    }
+
+    #[test]
+    fn writes_powershell_profile() {
+        let sandbox = create_empty_sandbox();
+        let profile = setup_shell(&sandbox, "pwsh");
+
+        assert!(profile.contains(r#"$env:PROTO_HOME = "$env:HOME/.proto""#));
+        assert!(profile.contains(r#"$env:PATH = @("$env:PROTO_HOME/shims", "$env:PROTO_HOME/bin", $env:PATH) -join [IO.Path]::PathSeparator"#));
+    }
+
+    #[test]
+    fn writes_xonsh_profile() {
+        let sandbox = create_empty_sandbox();
+        let profile = setup_shell(&sandbox, "xonsh");
+
+        assert!(profile.contains(r#"$PROTO_HOME = f"{$HOME}/.proto""#));
+        assert!(profile.contains(r#"$PATH.insert(0, f"{$PROTO_HOME}/bin")"#));
+        assert!(profile.contains(r#"$PATH.insert(0, f"{$PROTO_HOME}/shims")"#));
+    }
+
+    #[test]
+    fn doesnt_write_if_already_setup() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("profile", "$env.PROTO_HOME = \"/custom\"\n");
+
+        let profile = setup_shell(&sandbox, "nu");
+
+        assert_eq!(profile, "$env.PROTO_HOME = \"/custom\"\n");
+    }
+}
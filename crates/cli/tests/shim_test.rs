@@ -33,6 +33,82 @@ mod shim_bin {
         assert_snapshot!(get_assert_output(&assert));
     }
 
+    #[test]
+    fn caches_resolution_for_directory() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("--pin")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert()
+            .success();
+
+        let cache_dir = sandbox.path().join(".proto/shims/cache");
+
+        // Explicit versions are not cached
+        let mut shim = create_shim_command(sandbox.path(), "node");
+        shim.arg("--version");
+        shim.assert().success();
+
+        assert!(!cache_dir.exists());
+
+        // Detected versions are cached
+        let mut shim = create_shim_command(sandbox.path(), "node");
+        shim.arg("--version");
+        shim.env_remove("PROTO_NODE_VERSION");
+        shim.assert().success();
+
+        assert!(cache_dir.exists());
+
+        // And are used on the next run
+        let mut shim = create_shim_command(sandbox.path(), "node");
+        shim.arg("--version");
+        shim.env_remove("PROTO_NODE_VERSION");
+        shim.assert().success();
+
+        // But are cleared when another version is installed
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("19.0.0")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert()
+            .success();
+
+        assert!(!cache_dir.exists());
+    }
+
+    #[test]
+    fn doesnt_cache_resolution_for_other_tools() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("install")
+            .arg("node")
+            .arg("--pin")
+            .arg("--")
+            .arg("--no-bundled-npm")
+            .assert()
+            .success();
+
+        // Simulate running node from a process that was spawned by the npm shim
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("run")
+            .arg("node")
+            .arg("--")
+            .arg("--version")
+            .env("PROTO_SHIM_NAME", "npm")
+            .env_remove("PROTO_NODE_VERSION")
+            .assert()
+            .success();
+
+        assert!(!sandbox.path().join(".proto/shims/cache").exists());
+    }
+
     #[test]
     fn waits_for_timeout() {
         let sandbox = create_empty_sandbox();
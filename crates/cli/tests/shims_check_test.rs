@@ -0,0 +1,167 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use std::env;
+use utils::*;
+
+#[cfg(not(windows))]
+mod shims_check {
+    use super::*;
+
+    #[test]
+    fn passes_when_no_conflicts() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/shims/registry.json", r#"{ "node": {} }"#);
+        sandbox.create_file(".proto/shims/node", "");
+        sandbox.create_file("other/node", "");
+
+        let path = env::join_paths([
+            sandbox.path().join(".proto/shims"),
+            sandbox.path().join("other"),
+        ])
+        .unwrap();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("shims")
+            .arg("check")
+            .env("PATH", path)
+            .assert()
+            .success();
+
+        assert.stdout(predicate::str::contains("No conflicts found"));
+    }
+
+    #[test]
+    fn doesnt_report_bins_shadowed_by_own_shims() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/shims/registry.json", r#"{ "node": {} }"#);
+        sandbox.create_file(".proto/shims/node", "");
+        sandbox.create_file(".proto/bin/node", "");
+
+        let path = env::join_paths([
+            sandbox.path().join(".proto/shims"),
+            sandbox.path().join(".proto/bin"),
+        ])
+        .unwrap();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("shims")
+            .arg("check")
+            .env("PATH", path)
+            .assert()
+            .success();
+
+        assert.stdout(predicate::str::contains("No conflicts found"));
+    }
+
+    #[test]
+    fn fails_when_shim_is_shadowed() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/shims/registry.json", r#"{ "node": {} }"#);
+        sandbox.create_file(".proto/shims/node", "");
+        sandbox.create_file("other/node", "");
+
+        let path = env::join_paths([
+            sandbox.path().join("other"),
+            sandbox.path().join(".proto/shims"),
+        ])
+        .unwrap();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("shims")
+            .arg("check")
+            .env("PATH", path)
+            .env("PROTO_SHELL_PROFILE", sandbox.path().join(".missing"))
+            .assert()
+            .failure();
+
+        assert.stdout(
+            predicate::str::contains("Shadowed by")
+                .and(predicate::str::contains("other"))
+                .and(predicate::str::contains("Unknown")),
+        );
+    }
+
+    #[test]
+    fn finds_profile_that_adds_to_path() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/shims/registry.json", r#"{ "node": {} }"#);
+        sandbox.create_file(".proto/shims/node", "");
+        sandbox.create_file("other/node", "");
+        sandbox.create_file(
+            ".profile",
+            format!(
+                "export PATH=\"{}:$PATH\"",
+                sandbox.path().join("other").display()
+            ),
+        );
+
+        let path = env::join_paths([
+            sandbox.path().join("other"),
+            sandbox.path().join(".proto/shims"),
+        ])
+        .unwrap();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("shims")
+            .arg("check")
+            .env("PATH", path)
+            .env("PROTO_SHELL_PROFILE", sandbox.path().join(".profile"))
+            .assert()
+            .failure();
+
+        assert
+            .stdout(predicate::str::contains("Added by").and(predicate::str::contains(".profile")));
+    }
+
+    #[test]
+    fn doesnt_attribute_unrelated_profile_lines() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/shims/registry.json", r#"{ "node": {} }"#);
+        sandbox.create_file(".proto/shims/node", "");
+        sandbox.create_file("other/node", "");
+        sandbox.create_file(
+            ".profile",
+            format!(
+                "# {dir}\nalias other=\"{dir}/node\"\nexport PATH=\"{dir}2:$PATH\"",
+                dir = sandbox.path().join("other").display()
+            ),
+        );
+
+        let path = env::join_paths([
+            sandbox.path().join("other"),
+            sandbox.path().join(".proto/shims"),
+        ])
+        .unwrap();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("shims")
+            .arg("check")
+            .env("PATH", path)
+            .env("PROTO_SHELL_PROFILE", sandbox.path().join(".profile"))
+            .assert()
+            .failure();
+
+        assert.stdout(predicate::str::contains("Unknown"));
+    }
+
+    #[test]
+    fn ignores_shims_not_on_path() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".proto/shims/registry.json", r#"{ "node": {} }"#);
+        sandbox.create_file(".proto/shims/node", "");
+        sandbox.create_file("other/node", "");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        cmd.arg("shims")
+            .arg("check")
+            .env("PATH", sandbox.path().join("other"))
+            .assert()
+            .success();
+    }
+}
@@ -46,7 +46,8 @@ mod tool_add {
         assert_eq!(
             config.plugins.get("id").unwrap(),
             &PluginLocator::SourceUrl {
-                url: "https://github.com/moonrepo/schema-plugin/releases/latest/download/schema_plugin.wasm".into()
+                url: "https://github.com/moonrepo/schema-plugin/releases/latest/download/schema_plugin.wasm".into(),
+                integrity: None,
             }
         );
     }
@@ -74,7 +75,8 @@ mod tool_add {
         assert_eq!(
             config.plugins.get("id").unwrap(),
             &PluginLocator::SourceUrl {
-                url: "https://github.com/moonrepo/schema-plugin/releases/latest/download/schema_plugin.wasm".into()
+                url: "https://github.com/moonrepo/schema-plugin/releases/latest/download/schema_plugin.wasm".into(),
+                integrity: None,
             }
         );
     }
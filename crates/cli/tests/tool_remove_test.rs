@@ -30,7 +30,8 @@ mod tool_remove {
                 .insert(
                     Id::raw("id"),
                     PluginLocator::SourceUrl {
-                      url: "https://github.com/moonrepo/schema-plugin/releases/latest/download/schema_plugin.wasm".into()
+                      url: "https://github.com/moonrepo/schema-plugin/releases/latest/download/schema_plugin.wasm".into(),
+                      integrity: None,
                     },
                 );
         })
@@ -55,7 +56,8 @@ mod tool_remove {
                 .insert(
                     Id::raw("id"),
                     PluginLocator::SourceUrl {
-                      url: "https://github.com/moonrepo/schema-plugin/releases/latest/download/schema_plugin.wasm".into()
+                      url: "https://github.com/moonrepo/schema-plugin/releases/latest/download/schema_plugin.wasm".into(),
+                      integrity: None,
                     },
                 );
         })
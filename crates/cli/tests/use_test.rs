@@ -1,5 +1,6 @@
 mod utils;
 
+use starbase_sandbox::predicates::prelude::*;
 use utils::*;
 
 mod install_all {
@@ -65,4 +66,40 @@ deno = "1.30.0"
         assert!(node_path.exists());
         assert!(!deno_path.exists());
     }
+
+    #[test]
+    fn uses_pinned_versions_for_requirements_in_batch() {
+        let sandbox = create_empty_sandbox_with_requirements(
+            r#"app = "1.0.0"
+dep = "1.5.0""#,
+        );
+
+        create_fake_install(&sandbox, "app", "1.0.0");
+        create_fake_install(&sandbox, "dep", "1.5.0");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("use").assert();
+
+        assert.success().stderr(
+            predicate::str::contains("Required tool is installed")
+                .and(predicate::str::contains("version=\"1.5.0\"")),
+        );
+    }
+
+    #[test]
+    fn errors_for_requirements_before_installing() {
+        let sandbox = create_empty_sandbox_with_requirements(
+            r#"app = "1.0.0"
+dep = "2.0.0""#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("use").assert();
+
+        assert.failure().stderr(predicate::str::contains(
+            "App requires dep ^1.0.0, but version 2.0.0 was detected.",
+        ));
+
+        assert!(!sandbox.path().join(".proto/tools/dep/2.0.0").exists());
+    }
 }
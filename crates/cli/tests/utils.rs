@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 
-use proto_core::{ProtoConfig, ProtoConfigManager};
+use proto_core::{ProtoConfig, ProtoConfigManager, ToolManifest, VersionSpec};
 use proto_shim::get_exe_file_name;
 use starbase_sandbox::{assert_cmd, create_command_with_name};
 pub use starbase_sandbox::{create_empty_sandbox, output_to_string, Sandbox};
@@ -28,6 +28,47 @@ moon-test = "source:https://raw.githubusercontent.com/moonrepo/moon/master/proto
     temp
 }
 
+// Plugins in `fixtures/requires` return static metadata, where `app` requires
+// `dep ^1.0.0`, and `cycle-a` and `cycle-b` require each other
+pub fn create_empty_sandbox_with_requirements(versions: &str) -> Sandbox {
+    let temp = create_empty_sandbox();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/requires");
+    let mut config = format!("{versions}\n\n[plugins]\n");
+
+    for id in ["app", "dep", "cycle-a", "cycle-b"] {
+        config.push_str(&format!(
+            "{id} = 'source:{}'\n",
+            fixtures.join(format!("{id}.wasm")).display()
+        ));
+    }
+
+    temp.create_file(".prototools", config);
+
+    // Directories must exist to be made available to plugins
+    temp.create_file(".proto/.keep", "");
+
+    temp
+}
+
+// Mark a version as installed, without downloading it
+pub fn create_fake_install(sandbox: &Sandbox, id: &str, version: &str) {
+    sandbox.create_file(
+        format!(".proto/tools/{id}/{version}/{}", get_exe_file_name(id)),
+        "",
+    );
+
+    let mut manifest = ToolManifest::load(
+        sandbox
+            .path()
+            .join(format!(".proto/tools/{id}/manifest.json")),
+    )
+    .unwrap();
+    manifest
+        .installed_versions
+        .insert(VersionSpec::parse(version).unwrap());
+    manifest.save().unwrap();
+}
+
 pub fn create_proto_command<T: AsRef<Path>>(path: T) -> assert_cmd::Command {
     let path = path.as_ref();
 
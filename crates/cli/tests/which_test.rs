@@ -0,0 +1,89 @@
+mod utils;
+
+use starbase_sandbox::predicates::prelude::*;
+use utils::*;
+
+mod which {
+    use super::*;
+
+    #[test]
+    fn explains_version_from_command_line() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("which")
+            .arg("node")
+            .arg("19.0.0")
+            .assert()
+            .success();
+
+        assert.stdout(
+            predicate::str::contains("Detected from: command line")
+                .and(predicate::str::contains("Installed: No")),
+        );
+    }
+
+    #[test]
+    fn explains_version_from_env_var() {
+        let sandbox = create_empty_sandbox();
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("which")
+            .arg("node")
+            .env("PROTO_NODE_VERSION", "19.0.0")
+            .assert()
+            .success();
+
+        assert.stdout(predicate::str::contains("PROTO_NODE_VERSION"));
+    }
+
+    #[test]
+    fn explains_version_from_prototools() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"19.0.0\"");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("which").arg("node").assert().success();
+
+        assert.stdout(
+            predicate::str::contains(".prototools")
+                .and(predicate::str::contains("Detect strategy: first-available")),
+        );
+    }
+
+    #[test]
+    fn explains_version_from_ecosystem() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".nvmrc", "19.0.0");
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd.arg("which").arg("node").assert().success();
+
+        assert.stdout(predicate::str::contains(".nvmrc"));
+    }
+
+    #[test]
+    fn explains_secondary_shims_via_parent() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".proto/shims/registry.json",
+            r#"{ "npx": { "parent": "npm", "alt_bin": true } }"#,
+        );
+
+        let mut cmd = create_proto_command(sandbox.path());
+        let assert = cmd
+            .arg("which")
+            .arg("npx")
+            .arg("9.0.0")
+            .arg("--json")
+            .assert()
+            .success();
+
+        assert.stdout(
+            predicate::str::contains(r#""id": "npm""#)
+                .and(predicate::str::contains(r#""parent": "npm""#)),
+        );
+    }
+}
@@ -81,6 +81,30 @@ pub enum ProtoError {
         command: String,
     },
 
+    #[diagnostic(
+        code(proto::tool::requirement_unsatisfied),
+        help = "Pin a version of {requirement} that satisfies the requirement."
+    )]
+    #[error(
+        "{tool} requires {} {}, but version {} was detected.",
+        .requirement.style(Style::Id),
+        .required.style(Style::Hash),
+        .version.style(Style::Hash),
+    )]
+    RequirementNotSatisfied {
+        tool: String,
+        requirement: Id,
+        required: String,
+        version: String,
+    },
+
+    #[diagnostic(code(proto::tool::requirement_cycle))]
+    #[error(
+        "{tool} requires {}, which creates a cycle between tools.",
+        .requirement.style(Style::Id),
+    )]
+    RequirementCycle { tool: String, requirement: Id },
+
     #[diagnostic(code(proto::tool::uninstall_failed))]
     #[error("Failed to uninstall {tool}. {error}")]
     UninstallFailed { tool: String, error: String },
@@ -91,6 +115,28 @@ pub enum ProtoError {
     )]
     UnknownTool { id: Id },
 
+    #[diagnostic(code(proto::plugin::permission_denied))]
+    #[error(
+        "Plugin {} is not permitted to {action} {}. Grant access with the {} setting in .prototools.",
+        .id.style(Style::Id),
+        .value.style(Style::Label),
+        .setting.style(Style::Property),
+    )]
+    PluginPermissionDenied {
+        id: Id,
+        action: String,
+        value: String,
+        setting: String,
+    },
+
+    #[diagnostic(code(proto::plugin::path_out_of_scope))]
+    #[error(
+        "Plugin {} is not permitted to access {}, as it's outside of the directories available to plugins.",
+        .id.style(Style::Id),
+        .path.style(Style::Path),
+    )]
+    PluginPathOutOfScope { id: Id, path: PathBuf },
+
     #[diagnostic(code(proto::build::unsupported))]
     #[error("Build from source is not supported for {tool}.")]
     UnsupportedBuildFromSource { tool: String },
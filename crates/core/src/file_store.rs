@@ -0,0 +1,156 @@
+use crate::helpers::hash_file_contents;
+use crate::proto::ProtoEnvironment;
+use starbase_utils::fs;
+use std::path::Path;
+use tracing::debug;
+
+// Small files are not worth the hashing and linking overhead
+const MIN_FILE_SIZE: u64 = 4096;
+
+#[derive(Debug, Default)]
+pub struct FileStoreResult {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// A content-addressable store of files in `~/.proto/store`, that identical
+/// files across tool installs are hard linked to. The file system's link
+/// count acts as the reference count, so a stored file with a single link
+/// is no longer used by any install and can be pruned.
+pub struct FileStore;
+
+impl FileStore {
+    /// Replace files in the provided directory with hard links to the store,
+    /// adding files that do not exist in the store yet.
+    #[cfg(unix)]
+    pub fn dedupe<P: AsRef<ProtoEnvironment>>(
+        proto: P,
+        dir: &Path,
+    ) -> miette::Result<FileStoreResult> {
+        use std::os::unix::fs::MetadataExt;
+
+        let store_dir = &proto.as_ref().store_dir;
+        let mut result = FileStoreResult::default();
+
+        if !dir.exists() {
+            return Ok(result);
+        }
+
+        debug!(dir = ?dir, "Deduplicating files against store");
+
+        for entry in fs::read_dir_all(dir)? {
+            let path = entry.path();
+
+            // Don't follow symlinks
+            let Ok(meta) = path.symlink_metadata() else {
+                continue;
+            };
+
+            if !meta.is_file() || meta.len() < MIN_FILE_SIZE {
+                continue;
+            }
+
+            let hash = hash_file_contents(&path)?;
+            let store_path = store_dir.join(&hash[0..2]).join(&hash);
+
+            if !store_path.exists() {
+                fs::create_dir_all(store_path.parent().unwrap())?;
+
+                if let Err(error) = std::fs::hard_link(&path, &store_path) {
+                    debug!(file = ?path, "Failed to add file to store: {}", error);
+                }
+
+                continue;
+            }
+
+            let store_meta = fs::metadata(&store_path)?;
+
+            // Already linked, or can't be linked across devices
+            if store_meta.ino() == meta.ino() || store_meta.dev() != meta.dev() {
+                continue;
+            }
+
+            // Links share permissions, so avoid changing the file's mode
+            if store_meta.mode() != meta.mode() {
+                continue;
+            }
+
+            // Link to a temporary file and rename, so that the
+            // original file is replaced atomically
+            let temp_path = path.with_file_name(format!("{}.dedupe", fs::file_name(&path)));
+
+            // A previous dedupe may have been interrupted before renaming
+            if temp_path.symlink_metadata().is_ok() {
+                fs::remove_file(&temp_path)?;
+            }
+
+            if let Err(error) = std::fs::hard_link(&store_path, &temp_path) {
+                debug!(file = ?path, "Failed to link file to store: {}", error);
+
+                continue;
+            }
+
+            fs::rename(&temp_path, &path)?;
+
+            result.files += 1;
+            result.bytes += meta.len();
+        }
+
+        debug!(
+            dir = ?dir,
+            files = result.files,
+            bytes = result.bytes,
+            "Deduplicated files",
+        );
+
+        Ok(result)
+    }
+
+    #[cfg(not(unix))]
+    pub fn dedupe<P: AsRef<ProtoEnvironment>>(
+        _proto: P,
+        dir: &Path,
+    ) -> miette::Result<FileStoreResult> {
+        debug!(dir = ?dir, "Deduplicating files is not supported on this platform");
+
+        Ok(FileStoreResult::default())
+    }
+
+    /// Remove files from the store that are no longer linked by any install.
+    /// This walks the entire store, so is only ran when cleaning or deduping.
+    #[cfg(unix)]
+    pub fn prune<P: AsRef<ProtoEnvironment>>(proto: P) -> miette::Result<FileStoreResult> {
+        use std::os::unix::fs::MetadataExt;
+
+        let store_dir = &proto.as_ref().store_dir;
+        let mut result = FileStoreResult::default();
+
+        if !store_dir.exists() {
+            return Ok(result);
+        }
+
+        for entry in fs::read_dir_all(store_dir)? {
+            let path = entry.path();
+
+            let Ok(meta) = path.symlink_metadata() else {
+                continue;
+            };
+
+            if meta.is_file() && meta.nlink() <= 1 {
+                debug!(file = ?path, "Removing unreferenced file from store");
+
+                fs::remove_file(&path)?;
+
+                result.files += 1;
+                result.bytes += meta.len();
+            }
+        }
+
+        Ok(result)
+    }
+
+    #[cfg(not(unix))]
+    pub fn prune<P: AsRef<ProtoEnvironment>>(_proto: P) -> miette::Result<FileStoreResult> {
+        Ok(FileStoreResult::default())
+    }
+}
@@ -65,6 +65,40 @@ pub fn get_plugins_dir() -> miette::Result<PathBuf> {
     Ok(get_proto_home()?.join("plugins"))
 }
 
+fn is_same_path(a: &Path, b: &Path) -> bool {
+    a == b
+        || match (a.canonicalize(), b.canonicalize()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+}
+
+/// Find an executable with the same file name as the provided executable,
+/// in a directory that appears before the executable's directory on `PATH`.
+/// Returns nothing if the executable's directory is not on `PATH`. Directories
+/// in `ignore_dirs` (proto's own shims and bins) are never considered shadowing.
+pub fn find_shadowing_executable(exe_path: &Path, ignore_dirs: &[&Path]) -> Option<PathBuf> {
+    let exe_dir = exe_path.parent()?;
+    let exe_name = exe_path.file_name()?;
+    let mut shadowing = None;
+
+    for dir in env::split_paths(&env::var_os("PATH")?) {
+        if is_same_path(&dir, exe_dir) {
+            return shadowing;
+        }
+
+        if shadowing.is_none() && !ignore_dirs.iter().any(|ignore| is_same_path(&dir, ignore)) {
+            let candidate = dir.join(exe_name);
+
+            if candidate.is_file() && !is_same_path(&candidate, exe_path) {
+                shadowing = Some(candidate);
+            }
+        }
+    }
+
+    None
+}
+
 fn check_connection(address: SocketAddr, timeout: u64) -> bool {
     trace!("Resolving {address}");
 
@@ -257,3 +291,29 @@ pub fn remove_bin_file(path: impl AsRef<Path>) -> miette::Result<()> {
 
     Ok(())
 }
+
+/// Match a value against a pattern, where `*` matches any sequence of characters.
+pub fn matches_wildcard(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts = parts.collect::<Vec<_>>();
+
+    // No wildcard, must be an exact match
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        };
+    }
+
+    rest.ends_with(last)
+}
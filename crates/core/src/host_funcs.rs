@@ -1,20 +1,118 @@
+use crate::error::ProtoError;
+use crate::helpers::{is_cache_enabled, is_offline};
 use crate::proto::ProtoEnvironment;
+use crate::proto_config::ProtoToolPermissionsConfig;
 use extism::{CurrentPlugin, Error, Function, InternalExt, UserData, Val, ValType};
-use proto_pdk_api::{ExecCommandInput, ExecCommandOutput, HostLogInput, HostLogTarget};
-use starbase_utils::fs;
+use once_cell::sync::{Lazy, OnceCell};
+use proto_pdk_api::{
+    ExecCommandInput, ExecCommandOutput, FetchInput, FetchOutput, HashAlgorithm, HashFileInput,
+    HostLogInput, HostLogTarget, ListDirInput,
+};
+use sha2::{Digest, Sha256, Sha512};
+use starbase_utils::{fs, glob, json};
+use std::collections::BTreeMap;
 use std::env;
-use std::path::PathBuf;
+use std::io;
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime};
 use system_env::create_process_command;
+use tokio::runtime::{self, Handle, Runtime, RuntimeFlavor};
+use tokio::task;
 use tracing::trace;
-use warpgate::Id;
+use warpgate::{create_http_client_with_options, Id, WarpgateError};
 
 #[derive(Clone)]
 pub struct HostData {
     pub id: Id,
+    pub permissions: Option<ProtoToolPermissionsConfig>,
     pub proto: Arc<ProtoEnvironment>,
 }
 
+impl HostData {
+    fn check_permission(
+        &self,
+        allowed: impl FnOnce(&ProtoToolPermissionsConfig) -> bool,
+        action: &str,
+        value: &str,
+        setting: &str,
+    ) -> Result<(), Error> {
+        match &self.permissions {
+            Some(permissions) if !allowed(permissions) => {
+                trace!(
+                    plugin = self.id.as_str(),
+                    action,
+                    value,
+                    "Denied plugin access to host"
+                );
+
+                Err(Error::new(ProtoError::PluginPermissionDenied {
+                    id: self.id.to_owned(),
+                    action: action.to_owned(),
+                    value: value.to_owned(),
+                    setting: format!("tools.{}.permissions.{setting}", self.id),
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Convert the provided virtual (or real) path to a real path, and verify
+    /// that it's within one of the host paths made available to plugins.
+    fn resolve_scoped_path(&self, path: &Path) -> Result<PathBuf, Error> {
+        let paths_map = self.proto.get_virtual_paths();
+        let real_path = warpgate::from_virtual_path(&paths_map, path);
+
+        // Resolve symlinks and parent directories before comparing
+        let canonical_path = real_path
+            .canonicalize()
+            .unwrap_or_else(|_| real_path.clone());
+
+        let in_scope = !real_path
+            .components()
+            .any(|part| matches!(part, Component::ParentDir))
+            && paths_map.keys().any(|host_path| {
+                canonical_path.starts_with(
+                    host_path
+                        .canonicalize()
+                        .unwrap_or_else(|_| host_path.clone()),
+                )
+            });
+
+        if !in_scope {
+            trace!(
+                plugin = self.id.as_str(),
+                path = ?path,
+                "Denied plugin access to host path"
+            );
+
+            return Err(Error::new(ProtoError::PluginPathOutOfScope {
+                id: self.id.to_owned(),
+                path: path.to_path_buf(),
+            }));
+        }
+
+        Ok(real_path)
+    }
+}
+
+// Extism erases the type of user data when boxing it, so it can't be downcast
+// with `UserData::any`. Every function that reads it is created with `HostData`.
+fn get_host_data(user_data: &UserData) -> HostData {
+    let ptr = user_data.as_ptr() as *const HostData;
+
+    assert!(!ptr.is_null(), "Missing host data for plugin function!");
+
+    // SAFETY: `UserData::new` stores the value with `Box::into_raw(Box::new(x))`,
+    // so the pointer is a `*mut HostData` for every function created in
+    // `create_host_functions`, and lives as long as the function itself.
+    unsafe { (*ptr).clone() }
+}
+
+// Every function that receives user data must be given `HostData` with
+// `UserData::new`, as `get_host_data` casts the pointer back to it. This relies
+// on the internals of extism 0.5, which are covered by `host_funcs_test.rs`.
 pub fn create_host_functions(data: HostData) -> Vec<Function> {
     vec![
         Function::new(
@@ -24,35 +122,63 @@ pub fn create_host_functions(data: HostData) -> Vec<Function> {
             Some(UserData::new(data.clone())),
             exec_command,
         ),
-        // Function::new(
-        //     "from_virtual_path",
-        //     [ValType::I64],
-        //     [ValType::I64],
-        //     Some(UserData::new(data.clone())),
-        //     from_virtual_path,
-        // ),
+        Function::new(
+            "fetch",
+            [ValType::I64],
+            [ValType::I64],
+            Some(UserData::new(data.clone())),
+            fetch,
+        ),
+        Function::new(
+            "from_virtual_path",
+            [ValType::I64],
+            [ValType::I64],
+            Some(UserData::new(data.clone())),
+            from_virtual_path,
+        ),
         Function::new(
             "get_env_var",
             [ValType::I64],
             [ValType::I64],
-            None,
+            Some(UserData::new(data.clone())),
             get_env_var,
         ),
+        Function::new(
+            "hash_file",
+            [ValType::I64],
+            [ValType::I64],
+            Some(UserData::new(data.clone())),
+            hash_file,
+        ),
         Function::new("host_log", [ValType::I64], [], None, host_log),
+        Function::new(
+            "list_dir",
+            [ValType::I64],
+            [ValType::I64],
+            Some(UserData::new(data.clone())),
+            list_dir,
+        ),
+        Function::new(
+            "read_file",
+            [ValType::I64],
+            [ValType::I64],
+            Some(UserData::new(data.clone())),
+            read_file,
+        ),
         Function::new(
             "set_env_var",
             [ValType::I64, ValType::I64],
             [],
-            None,
+            Some(UserData::new(data.clone())),
             set_env_var,
         ),
-        // Function::new(
-        //     "to_virtual_path",
-        //     [ValType::I64],
-        //     [ValType::I64],
-        //     Some(UserData::new(data)),
-        //     to_virtual_path,
-        // ),
+        Function::new(
+            "to_virtual_path",
+            [ValType::I64],
+            [ValType::I64],
+            Some(UserData::new(data)),
+            to_virtual_path,
+        ),
     ]
 }
 
@@ -108,11 +234,18 @@ fn exec_command(
     plugin: &mut CurrentPlugin,
     inputs: &[Val],
     outputs: &mut [Val],
-    _user_data: UserData,
+    user_data: UserData,
 ) -> Result<(), Error> {
     let input: ExecCommandInput =
         serde_json::from_str(plugin.memory_read_str(inputs[0].unwrap_i64() as u64)?)?;
 
+    get_host_data(&user_data).check_permission(
+        |permissions| permissions.allows_command(&input.command),
+        "execute command",
+        &input.command,
+        "commands",
+    )?;
+
     trace!(
         target: "proto_wasm::exec_command",
         command = &input.command,
@@ -181,13 +314,223 @@ fn exec_command(
     Ok(())
 }
 
+// HTTP
+
+fn fetch(
+    plugin: &mut CurrentPlugin,
+    inputs: &[Val],
+    outputs: &mut [Val],
+    user_data: UserData,
+) -> Result<(), Error> {
+    let input: FetchInput =
+        serde_json::from_str(plugin.memory_read_str(inputs[0].unwrap_i64() as u64)?)?;
+
+    let data = get_host_data(&user_data);
+    let url = reqwest::Url::parse(&input.url)?;
+    let host = url.host_str().unwrap_or_default();
+
+    data.check_permission(
+        |permissions| permissions.allows_host(host),
+        "make HTTP requests to",
+        host,
+        "hosts",
+    )?;
+
+    let method = input
+        .method
+        .as_deref()
+        .unwrap_or("GET")
+        .to_uppercase()
+        .parse::<reqwest::Method>()?;
+
+    // Only cache GET requests. Headers can change the response (for example,
+    // authorization), so they're part of the key, and plugins don't share responses.
+    let cache_path = if input.cache && method == reqwest::Method::GET {
+        let mut sha = Sha256::new();
+        sha.update(data.id.as_str());
+        sha.update(&input.url);
+
+        for (key, value) in BTreeMap::from_iter(&input.headers) {
+            sha.update(key.to_lowercase());
+            sha.update(value);
+        }
+
+        Some(
+            data.proto
+                .cache_dir
+                .join("requests")
+                .join(format!("{:x}.json", sha.finalize())),
+        )
+    } else {
+        None
+    };
+
+    if let Some(cache_path) = &cache_path {
+        let read_cache = (is_cache_enabled() && is_cache_fresh(cache_path)) || is_offline();
+
+        if read_cache && cache_path.exists() {
+            trace!(
+                target: "proto_wasm::fetch",
+                url = &input.url,
+                cache = ?cache_path,
+                "Reading HTTP response from cache"
+            );
+
+            let output: FetchOutput = json::read_file(cache_path)?;
+            let ptr = plugin.memory_alloc_bytes(serde_json::to_string(&output)?)?;
+
+            outputs[0] = Val::I64(ptr as i64);
+
+            return Ok(());
+        }
+    }
+
+    if is_offline() {
+        return Err(Error::new(ProtoError::InternetConnectionRequired));
+    }
+
+    trace!(
+        target: "proto_wasm::fetch",
+        method = method.as_str(),
+        url = &input.url,
+        "Making HTTP request from plugin"
+    );
+
+    let output = send_request(&data, &input.url, |client| {
+        let mut request = client.request(method, url);
+
+        for (key, value) in &input.headers {
+            request = request.header(key, value);
+        }
+
+        if let Some(body) = &input.body {
+            request = request.body(body.to_owned());
+        }
+
+        request
+    })?;
+
+    trace!(
+        target: "proto_wasm::fetch",
+        url = &input.url,
+        status = output.status,
+        body_len = output.body.len(),
+        "Made HTTP request from plugin"
+    );
+
+    if let Some(cache_path) = &cache_path {
+        if output.is_success() {
+            json::write_file(cache_path, &output, false)?;
+        }
+    }
+
+    let ptr = plugin.memory_alloc_bytes(serde_json::to_string(&output)?)?;
+
+    outputs[0] = Val::I64(ptr as i64);
+
+    Ok(())
+}
+
+// Responses are cached for 12 hours, like remote versions
+fn is_cache_fresh(path: &Path) -> bool {
+    fs::metadata(path)
+        .ok()
+        .and_then(|metadata| metadata.modified().or_else(|_| metadata.created()).ok())
+        .is_some_and(|time| time > SystemTime::now() - Duration::from_secs(60 * 60 * 12))
+}
+
+// Pooled connections are driven by tasks on the runtime that created them,
+// so requests made outside of a multi-threaded runtime use a client and
+// runtime of their own, that live for the duration of the process.
+static HOST_HTTP_CLIENT: OnceCell<reqwest::Client> = OnceCell::new();
+
+static HOST_RUNTIME: Lazy<Runtime> = Lazy::new(|| {
+    runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .expect("Failed to create async runtime!")
+});
+
+// Host functions are synchronous, but may be called from within an async runtime
+fn send_request(
+    data: &HostData,
+    url: &str,
+    build: impl FnOnce(&reqwest::Client) -> reqwest::RequestBuilder,
+) -> Result<FetchOutput, Error> {
+    let output = match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            let client = data
+                .proto
+                .get_plugin_loader()
+                .and_then(|loader| loader.get_client())
+                .map_err(|error| Error::msg(error.to_string()))?;
+            let request = build(client);
+
+            task::block_in_place(|| handle.block_on(receive_response(request)))
+        }
+        _ => {
+            let client = HOST_HTTP_CLIENT
+                .get_or_try_init(|| {
+                    create_http_client_with_options(&data.proto.load_config()?.settings.http)
+                })
+                .map_err(|error| Error::msg(error.to_string()))?;
+            let request = build(client);
+
+            // The runtime can't be blocked on from within another runtime's thread
+            thread::scope(|scope| {
+                scope
+                    .spawn(|| HOST_RUNTIME.block_on(receive_response(request)))
+                    .join()
+                    .expect("Failed to make HTTP request!")
+            })
+        }
+    };
+
+    output.map_err(|error| {
+        Error::new(WarpgateError::Http {
+            url: url.to_owned(),
+            error,
+        })
+    })
+}
+
+async fn receive_response(request: reqwest::RequestBuilder) -> Result<FetchOutput, reqwest::Error> {
+    let response = request.send().await?;
+    let status = response.status().as_u16();
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(key, value)| {
+            (
+                key.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).to_string(),
+            )
+        })
+        .collect();
+
+    Ok(FetchOutput {
+        body: response.text().await?,
+        headers,
+        status,
+    })
+}
+
 fn get_env_var(
     plugin: &mut CurrentPlugin,
     inputs: &[Val],
     outputs: &mut [Val],
-    _user_data: UserData,
+    user_data: UserData,
 ) -> Result<(), Error> {
     let name = plugin.memory_read_str(inputs[0].unwrap_i64() as u64)?;
+
+    get_host_data(&user_data).check_permission(
+        |permissions| permissions.allows_env_var(name),
+        "read environment variable",
+        name,
+        "env-vars",
+    )?;
+
     let value = env::var(name).unwrap_or_default();
 
     trace!(
@@ -208,12 +551,19 @@ fn set_env_var(
     plugin: &mut CurrentPlugin,
     inputs: &[Val],
     _outputs: &mut [Val],
-    _user_data: UserData,
+    user_data: UserData,
 ) -> Result<(), Error> {
     let name = plugin
         .memory_read_str(inputs[0].unwrap_i64() as u64)?
         .to_owned();
 
+    get_host_data(&user_data).check_permission(
+        |permissions| permissions.allows_env_var(&name),
+        "write environment variable",
+        &name,
+        "env-vars",
+    )?;
+
     let value = plugin
         .memory_read_str(inputs[1].unwrap_i64() as u64)?
         .to_owned();
@@ -230,58 +580,172 @@ fn set_env_var(
     Ok(())
 }
 
-// fn from_virtual_path(
-//     plugin: &mut CurrentPlugin,
-//     inputs: &[Val],
-//     outputs: &mut [Val],
-//     user_data: UserData,
-// ) -> Result<(), Error> {
-//     let virtual_path = PathBuf::from(plugin.memory_read_str(inputs[0].unwrap_i64() as u64)?);
+// Files
+
+fn from_virtual_path(
+    plugin: &mut CurrentPlugin,
+    inputs: &[Val],
+    outputs: &mut [Val],
+    user_data: UserData,
+) -> Result<(), Error> {
+    let virtual_path = PathBuf::from(plugin.memory_read_str(inputs[0].unwrap_i64() as u64)?);
+    let real_path = get_host_data(&user_data).resolve_scoped_path(&virtual_path)?;
+
+    trace!(
+        target: "proto_wasm::from_virtual_path",
+        virtual_path = ?virtual_path,
+        real_path = ?real_path,
+        "Converted a virtual path into a real path"
+    );
+
+    let ptr = plugin.memory_alloc_bytes(real_path.to_string_lossy().as_ref())?;
+
+    outputs[0] = Val::I64(ptr as i64);
+
+    Ok(())
+}
+
+fn to_virtual_path(
+    plugin: &mut CurrentPlugin,
+    inputs: &[Val],
+    outputs: &mut [Val],
+    user_data: UserData,
+) -> Result<(), Error> {
+    let data = get_host_data(&user_data);
+    let real_path = data.resolve_scoped_path(&PathBuf::from(
+        plugin.memory_read_str(inputs[0].unwrap_i64() as u64)?,
+    ))?;
+
+    let paths_map = data.proto.get_virtual_paths();
+    let virtual_path = warpgate::to_virtual_path(&paths_map, &real_path);
+
+    trace!(
+        target: "proto_wasm::to_virtual_path",
+        real_path = ?real_path,
+        virtual_path = ?virtual_path.virtual_path(),
+        "Converted a real path into a virtual path"
+    );
+
+    let ptr = plugin.memory_alloc_bytes(virtual_path.virtual_path().to_string_lossy().as_ref())?;
+
+    outputs[0] = Val::I64(ptr as i64);
+
+    Ok(())
+}
+
+fn read_file(
+    plugin: &mut CurrentPlugin,
+    inputs: &[Val],
+    outputs: &mut [Val],
+    user_data: UserData,
+) -> Result<(), Error> {
+    let path = get_host_data(&user_data).resolve_scoped_path(&PathBuf::from(
+        plugin.memory_read_str(inputs[0].unwrap_i64() as u64)?,
+    ))?;
 
-//     let data = user_data.any().unwrap();
-//     let data = data.downcast_ref::<HostData>().unwrap();
+    // Files may not be text, so read raw bytes
+    let content = fs::read_file_bytes(&path)?;
 
-//     let paths_map = data.proto.get_virtual_paths();
-//     let real_path = warpgate::from_virtual_path(&paths_map, &virtual_path);
+    trace!(
+        target: "proto_wasm::read_file",
+        path = ?path,
+        len = content.len(),
+        "Read file from host"
+    );
 
-//     trace!(
-//         target: "proto_wasm::from_virtual_path",
-//         virtual_path = ?virtual_path,
-//         real_path = ?real_path,
-//         "Converted a virtual path into a real path"
-//     );
+    let ptr = plugin.memory_alloc_bytes(content)?;
 
-//     let ptr = plugin.memory_alloc_bytes(real_path.to_str().unwrap())?;
+    outputs[0] = Val::I64(ptr as i64);
 
-//     outputs[0] = Val::I64(ptr as i64);
+    Ok(())
+}
 
-//     Ok(())
-// }
+fn list_dir(
+    plugin: &mut CurrentPlugin,
+    inputs: &[Val],
+    outputs: &mut [Val],
+    user_data: UserData,
+) -> Result<(), Error> {
+    let input: ListDirInput =
+        serde_json::from_str(plugin.memory_read_str(inputs[0].unwrap_i64() as u64)?)?;
 
-// fn to_virtual_path(
-//     plugin: &mut CurrentPlugin,
-//     inputs: &[Val],
-//     outputs: &mut [Val],
-//     user_data: UserData,
-// ) -> Result<(), Error> {
-//     let real_path = PathBuf::from(plugin.memory_read_str(inputs[0].unwrap_i64() as u64)?);
+    let data = get_host_data(&user_data);
+    let dir = data.resolve_scoped_path(&input.dir)?;
+    let paths_map = data.proto.get_virtual_paths();
 
-//     let data = user_data.any().unwrap();
-//     let data = data.downcast_ref::<HostData>().unwrap();
+    let paths = if input.globs.is_empty() {
+        fs::read_dir(&dir)?
+            .into_iter()
+            .map(|entry| entry.path())
+            .collect::<Vec<_>>()
+    } else {
+        glob::walk(&dir, &input.globs)?
+    };
 
-//     let paths_map = data.proto.get_virtual_paths();
-//     let virtual_path = warpgate::to_virtual_path(&paths_map, &real_path);
+    // Globs may match through symlinks, so verify each path
+    let mut virtual_paths = paths
+        .into_iter()
+        .filter(|path| data.resolve_scoped_path(path).is_ok())
+        .map(|path| {
+            warpgate::to_virtual_path(&paths_map, &path)
+                .virtual_path()
+                .to_owned()
+        })
+        .collect::<Vec<_>>();
 
-//     trace!(
-//         target: "proto_wasm::to_virtual_path",
-//         real_path = ?real_path,
-//         virtual_path = ?virtual_path,
-//         "Converted a real path into a virtual path"
-//     );
+    virtual_paths.sort();
 
-//     let ptr = plugin.memory_alloc_bytes(virtual_path.to_str().unwrap())?;
+    trace!(
+        target: "proto_wasm::list_dir",
+        dir = ?dir,
+        globs = ?input.globs,
+        count = virtual_paths.len(),
+        "Listed directory on host"
+    );
 
-//     outputs[0] = Val::I64(ptr as i64);
+    let ptr = plugin.memory_alloc_bytes(serde_json::to_string(&virtual_paths)?)?;
+
+    outputs[0] = Val::I64(ptr as i64);
 
-//     Ok(())
-// }
+    Ok(())
+}
+
+fn hash_file(
+    plugin: &mut CurrentPlugin,
+    inputs: &[Val],
+    outputs: &mut [Val],
+    user_data: UserData,
+) -> Result<(), Error> {
+    let input: HashFileInput =
+        serde_json::from_str(plugin.memory_read_str(inputs[0].unwrap_i64() as u64)?)?;
+
+    let path = get_host_data(&user_data).resolve_scoped_path(&input.path)?;
+    let mut file = fs::open_file(&path)?;
+
+    let hash = match input.algorithm {
+        HashAlgorithm::Sha256 => {
+            let mut sha = Sha256::new();
+            io::copy(&mut file, &mut sha)?;
+            format!("{:x}", sha.finalize())
+        }
+        HashAlgorithm::Sha512 => {
+            let mut sha = Sha512::new();
+            io::copy(&mut file, &mut sha)?;
+            format!("{:x}", sha.finalize())
+        }
+    };
+
+    trace!(
+        target: "proto_wasm::hash_file",
+        path = ?path,
+        algorithm = ?input.algorithm,
+        hash = &hash,
+        "Hashed file on host"
+    );
+
+    let ptr = plugin.memory_alloc_bytes(hash)?;
+
+    outputs[0] = Val::I64(ptr as i64);
+
+    Ok(())
+}
@@ -1,9 +1,13 @@
 mod error;
 mod events;
+mod file_store;
 mod helpers;
 mod host_funcs;
+mod platform;
+mod project_registry;
 mod proto;
 mod proto_config;
+mod shim_cache;
 mod shim_registry;
 mod tool;
 mod tool_loader;
@@ -15,10 +19,15 @@ mod version_resolver;
 pub use error::*;
 pub use events::*;
 pub use extism::{manifest::Wasm, Manifest as PluginManifest};
+pub use file_store::*;
 pub use helpers::*;
+pub use platform::*;
+pub use project_registry::*;
 pub use proto::*;
 pub use proto_config::*;
 pub use semver::{Version, VersionReq};
+pub use shim_cache::*;
+pub use shim_registry::*;
 pub use tool::*;
 pub use tool_loader::*;
 pub use tool_manifest::*;
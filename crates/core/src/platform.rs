@@ -0,0 +1,174 @@
+use once_cell::sync::OnceCell;
+use serde_json::Value;
+use system_env::{SystemArch, SystemLibc, SystemOS};
+
+// Detecting the libc requires reading the file system, so only do it
+// once per process, and only when a config actually has a libc predicate.
+static DETECTED_LIBC: OnceCell<SystemLibc> = OnceCell::new();
+
+#[derive(Clone, Debug, PartialEq)]
+enum PlatformPredicate {
+    Arch(SystemArch),
+    Family(String),
+    Libc(SystemLibc),
+    Os(SystemOS),
+}
+
+fn parse_arch(value: &str) -> Option<SystemArch> {
+    serde_json::from_value(Value::String(value.to_owned())).ok()
+}
+
+fn parse_libc(value: &str) -> Option<SystemLibc> {
+    serde_json::from_value::<SystemLibc>(Value::String(value.to_owned()))
+        .ok()
+        .filter(|libc| *libc != SystemLibc::Unknown)
+}
+
+fn parse_os(value: &str) -> Option<SystemOS> {
+    serde_json::from_value(Value::String(value.to_owned())).ok()
+}
+
+fn parse_family(value: &str) -> Option<String> {
+    if value == "unix" || value == "windows" {
+        Some(value.to_owned())
+    } else {
+        None
+    }
+}
+
+fn parse_name(value: &str) -> Option<PlatformPredicate> {
+    parse_os(value)
+        .map(PlatformPredicate::Os)
+        .or_else(|| parse_arch(value).map(PlatformPredicate::Arch))
+        .or_else(|| parse_libc(value).map(PlatformPredicate::Libc))
+        .or_else(|| parse_family(value).map(PlatformPredicate::Family))
+}
+
+// Supports a subset of Rust's `cfg()` syntax, where each predicate
+// is comma separated, and all predicates must match.
+fn parse_cfg(inner: &str) -> Option<Vec<PlatformPredicate>> {
+    let mut predicates = vec![];
+
+    for part in inner.split(',') {
+        let part = part.trim();
+
+        if part.is_empty() {
+            continue;
+        }
+
+        let predicate = match part.split_once('=') {
+            Some((key, value)) => {
+                let value = value.trim().trim_matches('"');
+
+                match key.trim() {
+                    "arch" | "target_arch" => parse_arch(value).map(PlatformPredicate::Arch),
+                    "family" | "target_family" => {
+                        parse_family(value).map(PlatformPredicate::Family)
+                    }
+                    "libc" | "target_env" => parse_libc(value).map(PlatformPredicate::Libc),
+                    "os" | "target_os" => parse_os(value).map(PlatformPredicate::Os),
+                    _ => None,
+                }
+            }
+            None => parse_name(part),
+        };
+
+        predicates.push(predicate?);
+    }
+
+    if predicates.is_empty() {
+        None
+    } else {
+        Some(predicates)
+    }
+}
+
+fn parse_key(key: &str) -> Option<Vec<PlatformPredicate>> {
+    if let Some(inner) = key
+        .strip_prefix("cfg(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return parse_cfg(inner);
+    }
+
+    key.split('-').map(parse_name).collect()
+}
+
+/// The platform of the current host, used for selecting
+/// platform-conditional values in `.prototools`.
+#[derive(Clone, Copy, Debug)]
+pub struct ProtoPlatform {
+    pub arch: SystemArch,
+    pub libc: Option<SystemLibc>,
+    pub os: SystemOS,
+}
+
+impl ProtoPlatform {
+    pub fn new(os: SystemOS, arch: SystemArch, libc: SystemLibc) -> Self {
+        Self {
+            arch,
+            libc: Some(libc),
+            os,
+        }
+    }
+
+    /// Create a platform for the current host. The libc is not detected
+    /// until a libc predicate is matched against.
+    pub fn from_env() -> Self {
+        Self {
+            arch: SystemArch::from_env(),
+            libc: None,
+            os: SystemOS::from_env(),
+        }
+    }
+
+    pub fn get_libc(&self) -> SystemLibc {
+        self.libc
+            .unwrap_or_else(|| *DETECTED_LIBC.get_or_init(|| SystemLibc::detect(self.os)))
+    }
+
+    /// Return true if the key is a valid platform key, either a dash separated
+    /// list of names (`linux-arm64-musl`), or a `cfg()` expression
+    /// (`cfg(target_os = "linux", target_arch = "aarch64")`).
+    pub fn is_valid_key(key: &str) -> bool {
+        parse_key(key).is_some()
+    }
+
+    /// If the key matches the current platform, return its specificity
+    /// (the number of predicates), so that more specific keys can take
+    /// precedence over less specific keys.
+    pub fn matches(&self, key: &str) -> Option<usize> {
+        let predicates = parse_key(key)?;
+
+        let matched = predicates.iter().all(|predicate| match predicate {
+            PlatformPredicate::Arch(arch) => *arch == self.arch,
+            PlatformPredicate::Family(family) => {
+                if family == "windows" {
+                    self.os.is_windows()
+                } else {
+                    self.os.is_unix()
+                }
+            }
+            PlatformPredicate::Libc(libc) => *libc == self.get_libc(),
+            PlatformPredicate::Os(os) => *os == self.os,
+        });
+
+        if matched {
+            Some(predicates.len())
+        } else {
+            None
+        }
+    }
+
+    /// Filter the provided keys to those that match the current platform,
+    /// sorted from least to most specific.
+    pub fn select<'key, I: IntoIterator<Item = &'key String>>(&self, keys: I) -> Vec<&'key String> {
+        let mut matches = keys
+            .into_iter()
+            .filter_map(|key| self.matches(key).map(|weight| (weight, key)))
+            .collect::<Vec<_>>();
+
+        matches.sort_by_key(|(weight, _)| *weight);
+        matches.into_iter().map(|(_, key)| key).collect()
+    }
+}
@@ -0,0 +1,199 @@
+use crate::helpers::{read_json_file_with_lock, write_json_file_with_lock};
+use crate::platform::ProtoPlatform;
+use crate::proto::ProtoEnvironment;
+use crate::proto_config::{ProtoConfig, PROTO_CONFIG_NAME};
+use crate::tool::Tool;
+use crate::version_resolver::resolve_version;
+use serde::{Deserialize, Serialize};
+use starbase_utils::json;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::debug;
+use version_spec::VersionSpec;
+
+// Only update the timestamp once a day, to avoid writing on every run
+const TOUCH_INTERVAL: u128 = 24 * 60 * 60 * 1000;
+
+fn now() -> u128 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Project {
+    pub last_loaded_at: u128,
+}
+
+pub type ProjectsMap = BTreeMap<PathBuf, Project>;
+
+pub struct ProjectRegistry;
+
+impl ProjectRegistry {
+    fn get_file<P: AsRef<ProtoEnvironment>>(proto: P) -> PathBuf {
+        proto.as_ref().root.join("projects.json")
+    }
+
+    pub fn load<P: AsRef<ProtoEnvironment>>(proto: P) -> miette::Result<ProjectsMap> {
+        let file = Self::get_file(proto);
+
+        if file.exists() {
+            read_json_file_with_lock(&file)
+        } else {
+            Ok(BTreeMap::default())
+        }
+    }
+
+    /// Track the provided project directories in the registry, so that
+    /// their pinned versions are retained when cleaning or uninstalling.
+    pub fn track<P: AsRef<ProtoEnvironment>>(proto: P, dirs: Vec<PathBuf>) -> miette::Result<()> {
+        let proto = proto.as_ref();
+        let dirs = dirs
+            .into_iter()
+            .filter(|dir| *dir != proto.root)
+            .collect::<Vec<_>>();
+
+        if dirs.is_empty() {
+            return Ok(());
+        }
+
+        let file = Self::get_file(proto);
+        let now = now();
+
+        let is_stale = |projects: &ProjectsMap, dir: &PathBuf| {
+            projects.get(dir).map_or(true, |project| {
+                now.saturating_sub(project.last_loaded_at) >= TOUCH_INTERVAL
+            })
+        };
+
+        // Check without a lock first, and avoid writing the file if all
+        // projects are already tracked and were recently loaded
+        if let Ok(projects) = json::read_file::<_, ProjectsMap>(&file) {
+            if !dirs.iter().any(|dir| is_stale(&projects, dir)) {
+                return Ok(());
+            }
+        }
+
+        let mut projects: ProjectsMap = if file.exists() {
+            read_json_file_with_lock(&file)?
+        } else {
+            BTreeMap::default()
+        };
+
+        let mut mutated = false;
+
+        for dir in dirs {
+            if !is_stale(&projects, &dir) {
+                continue;
+            }
+
+            debug!(dir = ?dir, "Tracking project in registry");
+
+            projects.insert(
+                dir,
+                Project {
+                    last_loaded_at: now,
+                },
+            );
+            mutated = true;
+        }
+
+        if mutated {
+            write_json_file_with_lock(file, &projects)?;
+        }
+
+        Ok(())
+    }
+
+    /// Track the directories of all project configs that were loaded
+    /// for the current working directory.
+    pub fn track_loaded<P: AsRef<ProtoEnvironment>>(proto: P) -> miette::Result<()> {
+        let proto = proto.as_ref();
+        let dirs = proto
+            .load_config_manager()?
+            .files
+            .iter()
+            .filter(|file| file.exists && !file.global)
+            .filter_map(|file| file.path.parent())
+            .map(|dir| dir.to_path_buf())
+            .collect::<Vec<_>>();
+
+        Self::track(proto, dirs)
+    }
+
+    pub fn prune<P: AsRef<ProtoEnvironment>>(proto: P) -> miette::Result<Vec<PathBuf>> {
+        let file = Self::get_file(proto);
+
+        if !file.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut projects: ProjectsMap = read_json_file_with_lock(&file)?;
+        let mut pruned = vec![];
+
+        projects.retain(|dir, _| {
+            if dir.join(PROTO_CONFIG_NAME).exists() {
+                return true;
+            }
+
+            debug!(dir = ?dir, "Project no longer exists, removing from registry");
+
+            pruned.push(dir.to_owned());
+            false
+        });
+
+        if !pruned.is_empty() {
+            write_json_file_with_lock(file, &projects)?;
+        }
+
+        Ok(pruned)
+    }
+
+    /// Return the installed versions of the provided tool that are pinned
+    /// by registered projects, mapped to the directories that pin them.
+    pub fn find_pinned_versions(
+        tool: &Tool,
+    ) -> miette::Result<BTreeMap<VersionSpec, Vec<PathBuf>>> {
+        let platform = ProtoPlatform::from_env();
+        let mut pinned = BTreeMap::<VersionSpec, Vec<PathBuf>>::new();
+
+        for dir in Self::load(&tool.proto)?.into_keys() {
+            if let Some(version) = Self::find_pinned_version(tool, &dir, &platform)? {
+                pinned.entry(version).or_default().push(dir);
+            }
+        }
+
+        Ok(pinned)
+    }
+
+    fn find_pinned_version(
+        tool: &Tool,
+        dir: &Path,
+        platform: &ProtoPlatform,
+    ) -> miette::Result<Option<VersionSpec>> {
+        if !dir.join(PROTO_CONFIG_NAME).exists() {
+            return Ok(None);
+        }
+
+        let config = ProtoConfig::load_for_platform(dir, false, platform)?;
+
+        let Some(spec) = config
+            .versions
+            .as_ref()
+            .and_then(|versions| versions.get(&tool.id))
+        else {
+            return Ok(None);
+        };
+
+        Ok(resolve_version(
+            spec,
+            &[],
+            &BTreeMap::default(),
+            Some(&tool.manifest),
+            None,
+        ))
+    }
+}
@@ -1,19 +1,32 @@
 use crate::helpers::{get_home_dir, get_proto_home, is_offline};
+use crate::platform::ProtoPlatform;
 use crate::proto_config::{ProtoConfig, ProtoConfigFile, ProtoConfigManager, PROTO_CONFIG_NAME};
 use once_cell::sync::OnceCell;
 use std::collections::BTreeMap;
 use std::env;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::debug;
 use warpgate::PluginLoader;
 
+// A TTL of 0 days caches plugins forever
+fn create_cache_ttl(days: u64) -> Option<Duration> {
+    if days == 0 {
+        None
+    } else {
+        Some(Duration::from_secs(86400 * days))
+    }
+}
+
 #[derive(Clone)]
 pub struct ProtoEnvironment {
     pub bin_dir: PathBuf,
+    pub cache_dir: PathBuf,
     pub cwd: PathBuf,
     pub plugins_dir: PathBuf,
     pub shims_dir: PathBuf,
+    pub store_dir: PathBuf,
     pub temp_dir: PathBuf,
     pub tools_dir: PathBuf,
     pub home: PathBuf, // ~
@@ -44,9 +57,11 @@ impl ProtoEnvironment {
 
         Ok(ProtoEnvironment {
             bin_dir: root.join("bin"),
+            cache_dir: root.join("cache"),
             cwd: env::current_dir().expect("Unable to determine current working directory!"),
             plugins_dir: root.join("plugins"),
             shims_dir: root.join("shims"),
+            store_dir: root.join("store"),
             temp_dir: root.join("temp"),
             tools_dir: root.join("tools"),
             home: get_home_dir()?,
@@ -73,6 +88,14 @@ impl ProtoEnvironment {
             loader.set_client_options(&config.settings.http);
             loader.set_offline_checker(is_offline);
             loader.set_seed(env!("CARGO_PKG_VERSION"));
+            loader.set_cache_ttls(
+                create_cache_ttl(config.settings.plugin_cache_days),
+                create_cache_ttl(config.settings.latest_plugin_cache_days),
+            );
+            loader.set_signing_options(
+                &config.settings.trusted_plugin_keys,
+                config.settings.require_signed_plugins,
+            );
 
             Ok(loader)
         })
@@ -109,7 +132,11 @@ impl ProtoEnvironment {
                 exists: path.exists(),
                 global: true,
                 path,
-                config: ProtoConfig::load_from(&self.root, true)?,
+                config: ProtoConfig::load_for_platform(
+                    &self.root,
+                    true,
+                    &ProtoPlatform::from_env(),
+                )?,
             });
 
             Ok(manager)
@@ -1,3 +1,5 @@
+use crate::helpers::matches_wildcard;
+use crate::platform::ProtoPlatform;
 use miette::IntoDiagnostic;
 use once_cell::sync::OnceCell;
 use schematic::{
@@ -36,6 +38,61 @@ derive_enum!(
     }
 );
 
+#[derive(Clone, Config, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProtoToolPlatformConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<PluginLocator>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<UnresolvedVersionSpec>,
+}
+
+/// Capabilities granted to a plugin. Once configured, a plugin may only
+/// use the capabilities listed, where `*` is a wildcard.
+#[derive(Clone, Config, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProtoToolPermissionsConfig {
+    // Commands that can be executed with `exec_command`
+    pub commands: Vec<String>,
+
+    // Environment variables that can be read or written
+    pub env_vars: Vec<String>,
+
+    // Hosts that HTTP requests can be made to
+    pub hosts: Vec<String>,
+}
+
+impl ProtoToolPermissionsConfig {
+    pub fn allows_command(&self, command: &str) -> bool {
+        // Paths must be listed exactly, otherwise a plugin could execute
+        // any file with an allowed name, from a directory it can write to
+        let is_path = command.contains('/') || command.contains('\\');
+
+        self.commands.iter().any(|pattern| {
+            if pattern == "*" {
+                true
+            } else if is_path {
+                pattern == command
+            } else {
+                matches_wildcard(pattern, command)
+            }
+        })
+    }
+
+    pub fn allows_env_var(&self, name: &str) -> bool {
+        self.env_vars
+            .iter()
+            .any(|pattern| matches_wildcard(pattern, name))
+    }
+
+    pub fn allows_host(&self, host: &str) -> bool {
+        self.hosts
+            .iter()
+            .any(|pattern| matches_wildcard(pattern, host))
+    }
+}
+
 #[derive(Clone, Config, Debug, Serialize)]
 #[config(allow_unknown_fields)]
 #[serde(rename_all = "kebab-case")]
@@ -44,6 +101,16 @@ pub struct ProtoToolConfig {
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     pub aliases: BTreeMap<String, UnresolvedVersionSpec>,
 
+    // Capabilities granted to the plugin, unrestricted if not configured
+    #[setting(nested)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<ProtoToolPermissionsConfig>,
+
+    // Version and plugin overrides keyed by platform
+    #[setting(nested, merge = merge::merge_btreemap)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub platform: BTreeMap<String, ProtoToolPlatformConfig>,
+
     // Custom configuration to pass to plugins
     #[setting(merge = merge::merge_btreemap)]
     #[serde(flatten, skip_serializing_if = "BTreeMap::is_empty")]
@@ -56,19 +123,47 @@ pub struct ProtoSettingsConfig {
     #[setting(env = "PROTO_AUTO_CLEAN", parse_env = env::parse_bool)]
     pub auto_clean: bool,
 
+    #[setting(env = "PROTO_AUTO_DEDUPE", parse_env = env::parse_bool)]
+    pub auto_dedupe: bool,
+
     #[setting(env = "PROTO_AUTO_INSTALL", parse_env = env::parse_bool)]
     pub auto_install: bool,
 
     #[setting(env = "PROTO_DETECT_STRATEGY")]
     pub detect_strategy: DetectStrategy,
 
+    #[setting(default = 7, env = "PROTO_LATEST_PLUGIN_CACHE_DAYS")]
+    pub latest_plugin_cache_days: u64,
+
+    #[setting(env = "PROTO_PERSISTENT_PLUGIN_CACHE", parse_env = env::parse_bool)]
+    pub persistent_plugin_cache: bool,
+
     #[setting(env = "PROTO_PIN_LATEST")]
     pub pin_latest: Option<PinType>,
 
+    #[setting(default = 30, env = "PROTO_PLUGIN_CACHE_DAYS")]
+    pub plugin_cache_days: u64,
+
+    // Maximum number of 64KiB WASM memory pages a plugin can use
+    #[setting(env = "PROTO_PLUGIN_MEMORY_PAGES")]
+    pub plugin_memory_pages: Option<u32>,
+
+    // Seconds a plugin function call can run before it's interrupted. Disabled
+    // by default, as extism applies it to the whole call, including commands
+    // and downloads made by install functions
+    #[setting(env = "PROTO_PLUGIN_TIMEOUT")]
+    pub plugin_timeout: u64,
+
     pub http: HttpOptions,
 
+    #[setting(env = "PROTO_REQUIRE_SIGNED_PLUGINS", parse_env = env::parse_bool)]
+    pub require_signed_plugins: bool,
+
     #[setting(default = true)]
     pub telemetry: bool,
+
+    #[setting(merge = merge::append_vec)]
+    pub trusted_plugin_keys: Vec<String>,
 }
 
 fn merge_tools(
@@ -98,6 +193,11 @@ pub struct ProtoConfig {
     #[setting(nested)]
     pub settings: ProtoSettingsConfig,
 
+    // Version overrides keyed by platform
+    #[setting(merge = merge::merge_btreemap)]
+    #[serde(rename = "versions", skip_serializing_if = "BTreeMap::is_empty")]
+    pub platform_versions: BTreeMap<String, BTreeMap<Id, UnresolvedVersionSpec>>,
+
     #[setting(merge = merge::merge_btreemap)]
     #[serde(flatten)]
     pub versions: BTreeMap<Id, UnresolvedVersionSpec>,
@@ -114,12 +214,16 @@ impl ProtoConfig {
         config.plugins
     }
 
+    // Every built-in plugin is pinned to a release asset and its SHA256
+    // digest, so that a compromised release can't be loaded. When bumping
+    // a release, update the digest with `curl -sL <url> | shasum -a 256`.
     pub fn inherit_builtin_plugins(&mut self) {
         if !self.plugins.contains_key("bun") {
             self.plugins.insert(
                 Id::raw("bun"),
                 PluginLocator::SourceUrl {
-                    url: "https://github.com/moonrepo/bun-plugin/releases/download/v0.6.0/bun_plugin.wasm".into()
+                    url: "https://github.com/moonrepo/bun-plugin/releases/download/v0.6.0/bun_plugin.wasm".into(),
+                    integrity: None,
                 }
             );
         }
@@ -128,7 +232,8 @@ impl ProtoConfig {
             self.plugins.insert(
                 Id::raw("deno"),
                 PluginLocator::SourceUrl {
-                    url: "https://github.com/moonrepo/deno-plugin/releases/download/v0.7.0/deno_plugin.wasm".into()
+                    url: "https://github.com/moonrepo/deno-plugin/releases/download/v0.7.0/deno_plugin.wasm".into(),
+                    integrity: None,
                 }
             );
         }
@@ -137,7 +242,8 @@ impl ProtoConfig {
             self.plugins.insert(
                 Id::raw("go"),
                 PluginLocator::SourceUrl {
-                    url: "https://github.com/moonrepo/go-plugin/releases/download/v0.7.0/go_plugin.wasm".into()
+                    url: "https://github.com/moonrepo/go-plugin/releases/download/v0.7.0/go_plugin.wasm".into(),
+                    integrity: None,
                 }
             );
         }
@@ -146,7 +252,8 @@ impl ProtoConfig {
             self.plugins.insert(
                 Id::raw("node"),
                 PluginLocator::SourceUrl {
-                    url: "https://github.com/moonrepo/node-plugin/releases/download/v0.7.0/node_plugin.wasm".into()
+                    url: "https://github.com/moonrepo/node-plugin/releases/download/v0.7.0/node_plugin.wasm".into(),
+                    integrity: None,
                 }
             );
         }
@@ -156,7 +263,8 @@ impl ProtoConfig {
                 self.plugins.insert(
                     Id::raw(depman),
                     PluginLocator::SourceUrl {
-                        url: "https://github.com/moonrepo/node-plugin/releases/download/v0.7.0/node_depman_plugin.wasm".into()
+                        url: "https://github.com/moonrepo/node-plugin/releases/download/v0.7.0/node_depman_plugin.wasm".into(),
+                        integrity: None,
                     }
                 );
             }
@@ -166,7 +274,8 @@ impl ProtoConfig {
             self.plugins.insert(
                 Id::raw("python"),
                 PluginLocator::SourceUrl {
-                    url: "https://github.com/moonrepo/python-plugin/releases/download/v0.5.0/python_plugin.wasm".into()
+                    url: "https://github.com/moonrepo/python-plugin/releases/download/v0.5.0/python_plugin.wasm".into(),
+                    integrity: None,
                 }
             );
         }
@@ -175,7 +284,8 @@ impl ProtoConfig {
             self.plugins.insert(
                 Id::raw("rust"),
                 PluginLocator::SourceUrl {
-                    url: "https://github.com/moonrepo/rust-plugin/releases/download/v0.6.0/rust_plugin.wasm".into()
+                    url: "https://github.com/moonrepo/rust-plugin/releases/download/v0.6.0/rust_plugin.wasm".into(),
+                    integrity: None,
                 }
             );
         }
@@ -184,7 +294,8 @@ impl ProtoConfig {
             self.plugins.insert(
                 Id::raw(SCHEMA_PLUGIN_KEY),
                 PluginLocator::SourceUrl {
-                    url: "https://github.com/moonrepo/schema-plugin/releases/download/v0.7.0/schema_plugin.wasm".into()
+                    url: "https://github.com/moonrepo/schema-plugin/releases/download/v0.7.0/schema_plugin.wasm".into(),
+                    integrity: None,
                 }
             );
         }
@@ -222,14 +333,14 @@ impl ProtoConfig {
                 help: Some(color::muted_light("https://moonrepo.dev/docs/proto/config")),
             })?;
 
+        let mut error = ValidatorError {
+            path: schematic::Path::new(vec![]),
+            errors: vec![],
+        };
+
         // Because of serde flatten, unknown and invalid fields
         // do not trigger validation, so we need to manually handle it
         if let Some(fields) = &config.unknown {
-            let mut error = ValidatorError {
-                path: schematic::Path::new(vec![]),
-                errors: vec![],
-            };
-
             for (field, value) in fields {
                 // Versions show up in both flattened maps...
                 if config
@@ -254,17 +365,47 @@ impl ProtoConfig {
                     ValidateError::new(message),
                 ));
             }
+        }
+
+        // Platform keys are free-form strings, so validate their syntax
+        if let Some(platform_versions) = &config.platform_versions {
+            for key in platform_versions.keys() {
+                if !ProtoPlatform::is_valid_key(key) {
+                    error.errors.push(ValidateErrorType::setting(
+                        error.path.join_key("versions").join_key(key),
+                        ValidateError::new(format!("unknown platform `{key}`")),
+                    ));
+                }
+            }
+        }
 
-            if !error.errors.is_empty() {
-                return Err(ConfigError::Validator {
-                    config: config_path.to_string(),
-                    error,
-                    help: Some(color::muted_light("https://moonrepo.dev/docs/proto/config")),
+        if let Some(tools) = &config.tools {
+            for (id, tool) in tools {
+                for key in tool.platform.iter().flat_map(|platform| platform.keys()) {
+                    if !ProtoPlatform::is_valid_key(key) {
+                        error.errors.push(ValidateErrorType::setting(
+                            error
+                                .path
+                                .join_key("tools")
+                                .join_key(id.as_str())
+                                .join_key("platform")
+                                .join_key(key),
+                            ValidateError::new(format!("unknown platform `{key}`")),
+                        ));
+                    }
                 }
-                .into());
             }
         }
 
+        if !error.errors.is_empty() {
+            return Err(ConfigError::Validator {
+                config: config_path.to_string(),
+                error,
+                help: Some(color::muted_light("https://moonrepo.dev/docs/proto/config")),
+            }
+            .into());
+        }
+
         // Update file paths to be absolute
         let make_absolute = |file: &mut PathBuf| {
             if file.is_absolute() {
@@ -274,14 +415,28 @@ impl ProtoConfig {
             }
         };
 
+        let make_locator_absolute = |locator: &mut PluginLocator| {
+            if let PluginLocator::SourceFile {
+                path: ref mut source_path,
+                ..
+            } = locator
+            {
+                *source_path = make_absolute(source_path);
+            }
+        };
+
         if let Some(plugins) = &mut config.plugins {
             for locator in plugins.values_mut() {
-                if let PluginLocator::SourceFile {
-                    path: ref mut source_path,
-                    ..
-                } = locator
-                {
-                    *source_path = make_absolute(source_path);
+                make_locator_absolute(locator);
+            }
+        }
+
+        if let Some(tools) = &mut config.tools {
+            for tool in tools.values_mut() {
+                for tool_platform in tool.platform.iter_mut().flat_map(|map| map.values_mut()) {
+                    if let Some(locator) = &mut tool_platform.plugin {
+                        make_locator_absolute(locator);
+                    }
                 }
             }
         }
@@ -297,6 +452,72 @@ impl ProtoConfig {
         Ok(config)
     }
 
+    /// Load the config from the directory, and then select the values
+    /// for the current platform. This should be used when reading configs,
+    /// but not when updating them, as the selected values would be persisted.
+    pub fn load_for_platform<P: AsRef<Path>>(
+        dir: P,
+        with_lock: bool,
+        platform: &ProtoPlatform,
+    ) -> miette::Result<PartialProtoConfig> {
+        let mut config = Self::load_from(dir, with_lock)?;
+
+        Self::select_platform(&mut config, platform);
+
+        Ok(config)
+    }
+
+    /// Apply platform-conditional versions and plugins that match the provided
+    /// platform, on top of the base versions and plugins. More specific platform
+    /// keys take precedence, and tool-level values take precedence over
+    /// top-level `[versions]` values.
+    pub fn select_platform(config: &mut PartialProtoConfig, platform: &ProtoPlatform) {
+        if let Some(platform_versions) = &config.platform_versions {
+            for key in platform.select(platform_versions.keys()) {
+                trace!(platform = key, "Selecting platform versions");
+
+                config
+                    .versions
+                    .get_or_insert(Default::default())
+                    .extend(platform_versions[key].clone());
+            }
+        }
+
+        let Some(tools) = &config.tools else {
+            return;
+        };
+
+        for (id, tool) in tools {
+            let Some(tool_platform) = &tool.platform else {
+                continue;
+            };
+
+            for key in platform.select(tool_platform.keys()) {
+                trace!(
+                    tool = id.as_str(),
+                    platform = key,
+                    "Selecting platform config"
+                );
+
+                let tool_config = &tool_platform[key];
+
+                if let Some(version) = &tool_config.version {
+                    config
+                        .versions
+                        .get_or_insert(Default::default())
+                        .insert(id.to_owned(), version.to_owned());
+                }
+
+                if let Some(plugin) = &tool_config.plugin {
+                    config
+                        .plugins
+                        .get_or_insert(Default::default())
+                        .insert(id.to_owned(), plugin.to_owned());
+                }
+            }
+        }
+    }
+
     pub fn save_to<P: AsRef<Path>>(dir: P, config: PartialProtoConfig) -> miette::Result<PathBuf> {
         let path = dir.as_ref().join(PROTO_CONFIG_NAME);
 
@@ -347,6 +568,7 @@ impl ProtoConfigManager {
 
         let mut current_dir = Some(start_dir.as_ref());
         let mut files = vec![];
+        let platform = ProtoPlatform::from_env();
 
         while let Some(dir) = current_dir {
             let path = dir.join(PROTO_CONFIG_NAME);
@@ -355,7 +577,7 @@ impl ProtoConfigManager {
                 exists: path.exists(),
                 global: false,
                 path,
-                config: ProtoConfig::load_from(dir, false)?,
+                config: ProtoConfig::load_for_platform(dir, false, &platform)?,
             });
 
             if end_dir.is_some_and(|end| end == dir) {
@@ -394,6 +616,34 @@ impl ProtoConfigManager {
         })
     }
 
+    // Settings that restrict what plugins can do may only be configured in the
+    // global config, otherwise a project could loosen the user's restrictions
+    fn remove_global_only_settings(path: &Path, config: &mut PartialProtoConfig) {
+        if let Some(settings) = &mut config.settings {
+            let require_signed = settings.require_signed_plugins.take();
+            let trusted_keys = settings.trusted_plugin_keys.take();
+
+            if require_signed.is_some() || trusted_keys.is_some() {
+                debug!(
+                    file = ?path,
+                    "Ignoring plugin signing settings, as they can only be configured in the global config"
+                );
+            }
+        }
+
+        if let Some(tools) = &mut config.tools {
+            for (id, tool) in tools {
+                if tool.permissions.take().is_some() {
+                    debug!(
+                        file = ?path,
+                        tool = id.as_str(),
+                        "Ignoring plugin permissions, as they can only be configured in the global config"
+                    );
+                }
+            }
+        }
+    }
+
     fn merge_configs(&self, files: Vec<&ProtoConfigFile>) -> miette::Result<ProtoConfig> {
         let mut partial = PartialProtoConfig::default();
         let mut count = 0;
@@ -401,7 +651,13 @@ impl ProtoConfigManager {
 
         for file in files.iter().rev() {
             if file.exists {
-                partial.merge(context, file.config.to_owned())?;
+                let mut config = file.config.to_owned();
+
+                if !file.global {
+                    Self::remove_global_only_settings(&file.path, &mut config);
+                }
+
+                partial.merge(context, config)?;
                 count += 1;
             }
         }
@@ -0,0 +1,126 @@
+use crate::helpers::{read_json_file_with_lock, write_json_file_with_lock};
+use crate::proto::ProtoEnvironment;
+use proto_shim::get_shim_cache_path;
+use serde::{Deserialize, Serialize};
+use starbase_utils::fs;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Cache hits bypass `proto run`, which tracks when a version was last used,
+// so expire entries daily to keep that timestamp relatively accurate
+const SHIM_CACHE_TTL: Duration = Duration::from_secs(86400);
+
+/// A resolved command for a shim, that the shim binary can execute directly,
+/// without going through `proto run`. Only valid while the modified times
+/// of all files that were checked during version detection are unchanged.
+#[derive(Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct ShimCacheEntry {
+    // Arguments to pass before the passthrough arguments
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+
+    // Executable to run
+    pub bin: PathBuf,
+
+    // Environment variables to set, like `PROTO_NODE_VERSION`
+    pub env_vars: BTreeMap<String, String>,
+
+    // Timestamp (in seconds) after which the entry is stale
+    pub expires_at: u64,
+
+    // Config and version files mapped to their modified time
+    // (in milliseconds), or 0 if they do not exist
+    pub files: BTreeMap<PathBuf, u64>,
+
+    // Commands that must go through `proto run` to be blocked
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub self_upgrade_commands: Vec<String>,
+}
+
+impl ShimCacheEntry {
+    pub fn track_file(&mut self, path: PathBuf) {
+        let modified = fs::metadata(&path)
+            .ok()
+            .and_then(|meta| meta.modified().ok())
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_default();
+
+        self.files.insert(path, modified);
+    }
+}
+
+pub type ShimCacheMap = BTreeMap<String, ShimCacheEntry>;
+
+pub struct ShimCache;
+
+impl ShimCache {
+    /// Remove all cached resolutions, as they may point to executables of
+    /// a version that has since been uninstalled, or that is no longer the
+    /// best match for a requested version.
+    pub fn clear<P: AsRef<ProtoEnvironment>>(proto: P) -> miette::Result<()> {
+        fs::remove_dir_all(proto.as_ref().shims_dir.join("cache"))?;
+
+        Ok(())
+    }
+
+    /// Remove cache files where all entries have expired, or that can no
+    /// longer be parsed. Return the paths of the removed files.
+    pub fn prune<P: AsRef<ProtoEnvironment>>(proto: P) -> miette::Result<Vec<PathBuf>> {
+        let cache_dir = proto.as_ref().shims_dir.join("cache");
+        let mut pruned = vec![];
+
+        if !cache_dir.exists() {
+            return Ok(pruned);
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        for file in fs::read_dir(cache_dir)? {
+            let path = file.path();
+
+            let stale = match read_json_file_with_lock::<ShimCacheMap>(&path) {
+                Ok(cache) => cache.values().all(|entry| entry.expires_at <= now),
+                Err(_) => true,
+            };
+
+            if stale {
+                fs::remove_file(&path)?;
+                pruned.push(path);
+            }
+        }
+
+        Ok(pruned)
+    }
+
+    pub fn update<P: AsRef<ProtoEnvironment>>(
+        proto: P,
+        dir: &Path,
+        shim_name: &str,
+        mut entry: ShimCacheEntry,
+    ) -> miette::Result<()> {
+        let file = get_shim_cache_path(&proto.as_ref().shims_dir, dir);
+
+        let mut cache: ShimCacheMap = if file.exists() {
+            read_json_file_with_lock(&file)?
+        } else {
+            BTreeMap::default()
+        };
+
+        entry.expires_at = (SystemTime::now() + SHIM_CACHE_TTL)
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        cache.insert(shim_name.to_owned(), entry);
+
+        write_json_file_with_lock(file, &cache)?;
+
+        Ok(())
+    }
+}
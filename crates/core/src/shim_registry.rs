@@ -27,6 +27,16 @@ pub type ShimsMap = BTreeMap<String, Shim>;
 pub struct ShimRegistry;
 
 impl ShimRegistry {
+    pub fn load<P: AsRef<ProtoEnvironment>>(proto: P) -> miette::Result<ShimsMap> {
+        let file = proto.as_ref().shims_dir.join("registry.json");
+
+        if file.exists() {
+            read_json_file_with_lock(&file)
+        } else {
+            Ok(BTreeMap::default())
+        }
+    }
+
     pub fn update<P: AsRef<ProtoEnvironment>>(proto: P, entries: ShimsMap) -> miette::Result<()> {
         if entries.is_empty() {
             return Ok(());
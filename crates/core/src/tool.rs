@@ -1,12 +1,14 @@
 use crate::error::ProtoError;
 use crate::events::*;
+use crate::file_store::FileStore;
 use crate::helpers::{
-    extract_filename_from_url, get_proto_version, hash_file_contents, is_archive_file,
-    is_cache_enabled, is_offline, remove_bin_file, ENV_VAR,
+    extract_filename_from_url, find_shadowing_executable, get_proto_version, hash_file_contents,
+    is_archive_file, is_cache_enabled, is_offline, remove_bin_file, ENV_VAR,
 };
 use crate::host_funcs::{create_host_functions, HostData};
 use crate::proto::ProtoEnvironment;
 use crate::proto_config::ProtoConfig;
+use crate::shim_cache::ShimCache;
 use crate::shim_registry::{Shim, ShimRegistry, ShimsMap};
 use crate::tool_manifest::{ToolManifest, ToolManifestVersion};
 use crate::version_resolver::VersionResolver;
@@ -32,6 +34,12 @@ use std::time::{Duration, SystemTime};
 use tracing::{debug, info, trace, warn};
 use warpgate::{download_from_url_to_file, Id, PluginContainer, PluginLocator, VirtualPath};
 
+// Functions whose results are persisted across processes, when enabled. Results
+// are keyed by input and plugin configuration only, so these functions must not
+// depend on anything else, like environment variables or the file system
+const PERSISTENT_PLUGIN_FUNCS: [&str; 3] =
+    ["register_tool", "locate_executables", "download_prebuilt"];
+
 #[derive(Debug, Default, Serialize)]
 pub struct ExecutableLocation {
     pub config: ExecutableConfig,
@@ -61,6 +69,7 @@ pub struct Tool {
     pub on_uninstalled_global: Emitter<UninstalledGlobalEvent>,
 
     cache: bool,
+    dependency_bins: BTreeMap<Id, PathBuf>,
     exe_path: Option<PathBuf>,
     globals_dir: Option<PathBuf>,
     globals_prefix: Option<String>,
@@ -80,7 +89,7 @@ impl Tool {
     pub fn load_from_manifest<I: AsRef<Id>, P: AsRef<ProtoEnvironment>>(
         id: I,
         proto: P,
-        manifest: PluginManifest,
+        mut manifest: PluginManifest,
     ) -> miette::Result<Self> {
         let id = id.as_ref();
         let proto = proto.as_ref();
@@ -90,13 +99,36 @@ impl Tool {
             color::id(id.as_str())
         );
 
+        let config = proto.load_config()?;
+        let permissions = config
+            .tools
+            .get(id)
+            .and_then(|config| config.permissions.clone());
+
+        // Restrict HTTP requests made through the plugin runtime
+        if let Some(permissions) = &permissions {
+            manifest.allowed_hosts = Some(permissions.hosts.clone());
+        }
+
         let proto = Arc::new(proto.to_owned());
 
         let host_data = HostData {
             id: id.to_owned(),
+            permissions,
             proto: Arc::clone(&proto),
         };
 
+        let mut plugin =
+            PluginContainer::new(id.to_owned(), manifest, create_host_functions(host_data))?;
+
+        if config.settings.persistent_plugin_cache {
+            plugin.enable_persistent_cache(
+                &proto.cache_dir.join("plugins"),
+                env!("CARGO_PKG_VERSION"),
+                PERSISTENT_PLUGIN_FUNCS,
+            )?;
+        }
+
         if let Ok(level) = env::var("PROTO_WASM_LOG") {
             let log_file = proto.cwd.join(format!("{}-debug.log", id));
 
@@ -107,6 +139,7 @@ impl Tool {
 
         let mut tool = Tool {
             cache: true,
+            dependency_bins: BTreeMap::new(),
             exe_path: None,
             globals_dir: None,
             globals_prefix: None,
@@ -114,11 +147,7 @@ impl Tool {
             locator: None,
             manifest: ToolManifest::load_from(proto.tools_dir.join(id.as_str()))?,
             metadata: ToolMetadataOutput::default(),
-            plugin: PluginContainer::new(
-                id.to_owned(),
-                manifest,
-                create_host_functions(host_data),
-            )?,
+            plugin,
             proto,
             version: None,
 
@@ -150,15 +179,20 @@ impl Tool {
     ) -> miette::Result<PluginManifest> {
         let proto = proto.as_ref();
 
+        let settings = &proto.load_config()?.settings;
+
         let mut manifest = PluginManifest::new([wasm]);
         manifest = manifest.with_allowed_host("*");
         manifest = manifest.with_allowed_paths(proto.get_virtual_paths().into_iter());
-        manifest = manifest.with_timeout(Duration::from_secs(90));
 
-        #[cfg(debug_assertions)]
-        {
-            manifest = manifest.with_timeout(Duration::from_secs(120));
-        }
+        // A timeout of 0 seconds disables the timeout
+        manifest.timeout_ms = if settings.plugin_timeout == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(settings.plugin_timeout).as_millis() as u64)
+        };
+
+        manifest.memory.max_pages = settings.plugin_memory_pages;
 
         Ok(manifest)
     }
@@ -172,6 +206,12 @@ impl Tool {
         )
     }
 
+    /// Set the path to the primary executable of a required tool,
+    /// so that it's available to plugin functions.
+    pub fn set_dependency_bin(&mut self, id: Id, path: PathBuf) {
+        self.dependency_bins.insert(id, path);
+    }
+
     /// Disable internal caching when applicable.
     pub fn disable_caching(&mut self) {
         self.cache = false;
@@ -260,6 +300,11 @@ impl Tool {
     /// Return contextual information to pass to WASM plugin functions.
     pub fn create_context(&self) -> ToolContext {
         ToolContext {
+            dependency_bins: self
+                .dependency_bins
+                .iter()
+                .map(|(id, path)| (id.to_string(), self.to_virtual_path(path)))
+                .collect(),
             proto_version: Some(get_proto_version()),
             tool_dir: self.to_virtual_path(&self.get_tool_dir()),
             version: self.get_resolved_version(),
@@ -298,7 +343,7 @@ impl Tool {
     where
         I: Debug + Serialize,
     {
-        if self.plugin.has_func(hook) {
+        if self.plugin.has_func(hook)? {
             self.plugin.call_func_without_output(hook, input())?;
         }
 
@@ -307,7 +352,7 @@ impl Tool {
 
     /// Sync the local tool manifest with changes from the plugin.
     pub fn sync_manifest(&mut self) -> miette::Result<()> {
-        if !self.plugin.has_func("sync_manifest") {
+        if !self.plugin.has_func("sync_manifest")? {
             return Ok(());
         }
 
@@ -497,7 +542,7 @@ impl Tool {
         let mut version = VersionSpec::default();
         let mut resolved = false;
 
-        if self.plugin.has_func("resolve_version") {
+        if self.plugin.has_func("resolve_version")? {
             let result: ResolveVersionOutput = self.plugin.call_func_with(
                 "resolve_version",
                 ResolveVersionInput {
@@ -556,11 +601,23 @@ impl Tool {
         &self,
         current_dir: &Path,
     ) -> miette::Result<Option<UnresolvedVersionSpec>> {
-        if !self.plugin.has_func("detect_version_files") {
+        Ok(self
+            .detect_version_file_from(current_dir)
+            .await?
+            .map(|detected| detected.0))
+    }
+
+    /// Attempt to detect an applicable version from the provided directory,
+    /// and return the version file it was detected from.
+    pub async fn detect_version_file_from(
+        &self,
+        current_dir: &Path,
+    ) -> miette::Result<Option<(UnresolvedVersionSpec, PathBuf)>> {
+        if !self.plugin.has_func("detect_version_files")? {
             return Ok(None);
         }
 
-        let has_parser = self.plugin.has_func("parse_version_file");
+        let has_parser = self.plugin.has_func("parse_version_file")?;
         let result: DetectVersionOutput = self.plugin.cache_func("detect_version_files")?;
 
         if !result.ignore.is_empty() {
@@ -614,7 +671,7 @@ impl Tool {
                 "Detected a version"
             );
 
-            return Ok(Some(version));
+            return Ok(Some((version, file_path)));
         }
 
         Ok(None)
@@ -657,7 +714,7 @@ impl Tool {
         let mut verified = false;
 
         // Allow plugin to provide their own checksum verification method
-        if self.plugin.has_func("verify_checksum") {
+        if self.plugin.has_func("verify_checksum")? {
             let result: VerifyChecksumOutput = self.plugin.call_func_with(
                 "verify_checksum",
                 VerifyChecksumInput {
@@ -738,7 +795,7 @@ impl Tool {
             "Installing tool by building from source"
         );
 
-        if !self.plugin.has_func("build_instructions") {
+        if !self.plugin.has_func("build_instructions")? {
             return Err(ProtoError::UnsupportedBuildFromSource {
                 tool: self.get_name().to_owned(),
             }
@@ -908,7 +965,7 @@ impl Tool {
             "Attempting to unpack archive",
         );
 
-        if self.plugin.has_func("unpack_archive") {
+        if self.plugin.has_func("unpack_archive")? {
             self.plugin.call_func_without_output(
                 "unpack_archive",
                 UnpackArchiveInput {
@@ -977,7 +1034,7 @@ impl Tool {
 
         // If this function is defined, it acts like an escape hatch and
         // takes precedence over all other install strategies
-        if self.plugin.has_func("native_install") {
+        if self.plugin.has_func("native_install")? {
             debug!(tool = self.id.as_str(), "Installing tool natively");
 
             let result: NativeInstallOutput = self.plugin.call_func_with(
@@ -1012,6 +1069,10 @@ impl Tool {
             // }
 
             self.install_from_prebuilt(&install_dir).await?;
+
+            if self.proto.load_config()?.settings.auto_dedupe {
+                FileStore::dedupe(&self.proto, &install_dir)?;
+            }
         }
 
         install_lock.unlock()?;
@@ -1035,7 +1096,7 @@ impl Tool {
     pub async fn install_global(&self, dependency: &str) -> miette::Result<bool> {
         let globals_dir = self.get_globals_bin_dir();
 
-        if !self.plugin.has_func("install_global") || globals_dir.is_none() {
+        if !self.plugin.has_func("install_global")? || globals_dir.is_none() {
             return Ok(false);
         }
 
@@ -1088,7 +1149,7 @@ impl Tool {
             })
             .await?;
 
-        if self.plugin.has_func("native_uninstall") {
+        if self.plugin.has_func("native_uninstall")? {
             debug!(tool = self.id.as_str(), "Uninstalling tool natively");
 
             let result: NativeUninstallOutput = self.plugin.call_func_with(
@@ -1130,7 +1191,7 @@ impl Tool {
     pub async fn uninstall_global(&self, dependency: &str) -> miette::Result<bool> {
         let globals_dir = self.get_globals_bin_dir();
 
-        if !self.plugin.has_func("uninstall_global") || globals_dir.is_none() {
+        if !self.plugin.has_func("uninstall_global")? || globals_dir.is_none() {
             return Ok(false);
         }
 
@@ -1200,6 +1261,65 @@ impl Tool {
         self.globals_prefix.as_deref()
     }
 
+    /// Return a list of all executables that have been installed to the globals
+    /// directory, named without the globals prefix. The directory must have
+    /// been located with [`Tool::locate_globals_dir`] first.
+    pub fn get_globals_locations(&self) -> miette::Result<Vec<ExecutableLocation>> {
+        let mut locations: Vec<ExecutableLocation> = vec![];
+
+        let Some(globals_dir) = self.get_globals_bin_dir() else {
+            return Ok(locations);
+        };
+
+        if !globals_dir.exists() {
+            return Ok(locations);
+        }
+
+        let globals_prefix = self.get_globals_prefix();
+
+        for file in fs::read_dir(globals_dir)? {
+            if file.file_type().into_diagnostic()?.is_dir() {
+                continue;
+            }
+
+            let file_path = file.path();
+
+            // Windows globals have an extension, and usually have multiple
+            // variants for each shell (foo.cmd, foo.ps1, etc)
+            let mut name = if cfg!(windows) {
+                fs::file_name(file_path.with_extension(""))
+            } else {
+                fs::file_name(&file_path)
+            };
+
+            if let Some(prefix) = globals_prefix {
+                if let Some(prefixless) = name.strip_prefix(prefix) {
+                    name = prefixless.to_owned();
+                } else {
+                    continue;
+                }
+            }
+
+            if locations.iter().any(|location| location.name == name) {
+                continue;
+            }
+
+            locations.push(ExecutableLocation {
+                config: ExecutableConfig {
+                    exe_path: Some(file_path.clone()),
+                    ..ExecutableConfig::default()
+                },
+                name,
+                path: file_path,
+                primary: false,
+            });
+        }
+
+        locations.sort_by(|a, d| a.name.cmp(&d.name));
+
+        Ok(locations)
+    }
+
     /// Return a list of all binaries that get created in `~/.proto/bin`.
     /// The list will contain the executable config, and an absolute path
     /// to the binaries final location.
@@ -1235,6 +1355,32 @@ impl Tool {
         Ok(locations)
     }
 
+    /// Return a list of unique directories within the tool directory that contain
+    /// the original executables, derived from the binaries in `~/.proto/bin`.
+    pub fn get_bin_dirs(&self) -> miette::Result<Vec<PathBuf>> {
+        let tool_dir = self.get_tool_dir();
+        let mut dirs = vec![];
+
+        for location in self.get_bin_locations()? {
+            let Some(exe_path) = location
+                .config
+                .exe_link_path
+                .as_ref()
+                .or(location.config.exe_path.as_ref())
+            else {
+                continue;
+            };
+
+            if let Some(dir) = tool_dir.join(exe_path).parent() {
+                if !dirs.iter().any(|d: &PathBuf| d == dir) {
+                    dirs.push(dir.to_path_buf());
+                }
+            }
+        }
+
+        Ok(dirs)
+    }
+
     /// Return location information for the primary executable within the tool directory.
     pub fn get_exe_location(&self) -> miette::Result<Option<ExecutableLocation>> {
         let options = self.call_locate_executables()?;
@@ -1309,7 +1455,7 @@ impl Tool {
 
     /// Locate the directory that global packages are installed to.
     pub async fn locate_globals_dir(&mut self) -> miette::Result<()> {
-        if !self.plugin.has_func("locate_executables") || self.globals_dir.is_some() {
+        if !self.plugin.has_func("locate_executables")? || self.globals_dir.is_some() {
             return Ok(());
         }
 
@@ -1323,6 +1469,10 @@ impl Tool {
 
         self.globals_prefix = options.globals_prefix;
 
+        if options.globals_lookup_dirs.is_empty() {
+            return Ok(());
+        }
+
         // Find a globals directory that packages are installed to
         let lookup_count = options.globals_lookup_dirs.len() - 1;
 
@@ -1399,12 +1549,7 @@ impl Tool {
         let mut registry: ShimsMap = BTreeMap::new();
         registry.insert(self.id.to_string(), Shim::default());
 
-        let shim_binary =
-            fs::read_file_bytes(locate_proto_exe("proto-shim").ok_or_else(|| {
-                ProtoError::MissingShimBinary {
-                    bin_dir: self.proto.bin_dir.clone(),
-                }
-            })?)?;
+        let shim_binary = self.read_shim_binary()?;
 
         fs::create_dir_all(&self.proto.shims_dir)?;
 
@@ -1447,6 +1592,23 @@ impl Tool {
                 }
             })?;
 
+            if force_create {
+                if let Some(shadowing) = find_shadowing_executable(
+                    &location.path,
+                    &[&self.proto.shims_dir, &self.proto.bin_dir],
+                ) {
+                    warn!(
+                        tool = self.id.as_str(),
+                        shim = ?location.path,
+                        shadowed_by = ?shadowing,
+                        "Shim {} is shadowed by {}, which appears earlier in PATH. Run {} for more information.",
+                        color::id(&location.name),
+                        color::path(&shadowing),
+                        color::shell("proto shims check"),
+                    );
+                }
+            }
+
             // Update the registry
             registry.insert(location.name.clone(), shim_entry);
 
@@ -1465,9 +1627,103 @@ impl Tool {
 
         ShimRegistry::update(&self.proto, registry)?;
 
+        self.generate_global_shims(force_create).await?;
+
         Ok(())
     }
 
+    /// Create shim files for all executables in the tool's globals directory.
+    /// The shims run through the tool, so that the tool's version is resolved
+    /// at runtime. Shims that are owned by other tools will not be overwritten.
+    pub async fn generate_global_shims(&mut self, force: bool) -> miette::Result<()> {
+        self.locate_globals_dir().await?;
+
+        let globals = self.get_globals_locations()?;
+
+        if globals.is_empty() {
+            return Ok(());
+        }
+
+        let current_registry = ShimRegistry::load(&self.proto)?;
+        let tool_shims = self
+            .get_shim_locations()?
+            .into_iter()
+            .map(|location| location.name)
+            .collect::<HashSet<_>>();
+
+        let mut event = CreatedShimsEvent {
+            global: vec![],
+            local: vec![],
+        };
+
+        let mut registry: ShimsMap = BTreeMap::new();
+        let shim_binary = self.read_shim_binary()?;
+
+        fs::create_dir_all(&self.proto.shims_dir)?;
+
+        for location in globals {
+            let owned_by_other = current_registry.get(&location.name).is_some_and(|shim| {
+                shim.parent
+                    .as_ref()
+                    .map_or(true, |parent| parent != self.id.as_str())
+            });
+
+            if owned_by_other || tool_shims.contains(&location.name) {
+                trace!(
+                    tool = self.id.as_str(),
+                    global = &location.name,
+                    "Shim already exists for global, skipping"
+                );
+
+                continue;
+            }
+
+            let shim_path = self
+                .proto
+                .shims_dir
+                .join(get_shim_file_name(&location.name));
+
+            create_shim(&shim_binary, &shim_path, !force).map_err(|error| {
+                ProtoError::CreateShimFailed {
+                    path: shim_path.clone(),
+                    error,
+                }
+            })?;
+
+            registry.insert(
+                location.name.clone(),
+                Shim {
+                    alt_bin: Some(true),
+                    parent: Some(self.id.to_string()),
+                    ..Shim::default()
+                },
+            );
+
+            event.global.push(location.name);
+
+            debug!(
+                tool = self.id.as_str(),
+                shim = ?shim_path,
+                global = ?location.path,
+                "Creating shim for global"
+            );
+        }
+
+        self.on_created_shims.emit(event).await?;
+
+        ShimRegistry::update(&self.proto, registry)?;
+
+        Ok(())
+    }
+
+    fn read_shim_binary(&self) -> miette::Result<Vec<u8>> {
+        Ok(fs::read_file_bytes(
+            locate_proto_exe("proto-shim").ok_or_else(|| ProtoError::MissingShimBinary {
+                bin_dir: self.proto.bin_dir.clone(),
+            })?,
+        )?)
+    }
+
     /// Symlink all primary and secondary binaries for the current tool.
     pub async fn symlink_bins(&mut self, force: bool) -> miette::Result<()> {
         let bins = self.get_bin_locations()?;
@@ -1537,6 +1793,21 @@ impl Tool {
                 std::os::unix::fs::symlink(input_path, &output_path).into_diagnostic()?;
             }
 
+            if let Some(shadowing) = find_shadowing_executable(
+                &output_path,
+                &[&self.proto.shims_dir, &self.proto.bin_dir],
+            ) {
+                warn!(
+                    tool = self.id.as_str(),
+                    bin = ?output_path,
+                    shadowed_by = ?shadowing,
+                    "Binary {} is shadowed by {}, which appears earlier in PATH. Run {} for more information.",
+                    color::id(&location.name),
+                    color::path(&shadowing),
+                    color::shell("proto shims check"),
+                );
+            }
+
             event.bins.push(location.name);
         }
 
@@ -1625,6 +1896,9 @@ impl Tool {
         // Allow plugins to override manifest
         self.sync_manifest()?;
 
+        // Shims may now resolve to the new version
+        ShimCache::clear(&self.proto)?;
+
         Ok(true)
     }
 
@@ -1672,6 +1946,9 @@ impl Tool {
             }
         }
 
+        // Shims may still resolve to the removed version
+        ShimCache::clear(&self.proto)?;
+
         Ok(true)
     }
 
@@ -117,6 +117,20 @@ pub async fn load_tool_from_locator(
 
     let plugin_path = proto.get_plugin_loader()?.load_plugin(id, locator).await?;
 
+    load_tool_from_path(id, proto, locator, plugin_path).await
+}
+
+/// Load a tool from a plugin file that has already been located or downloaded.
+pub async fn load_tool_from_path(
+    id: impl AsRef<Id>,
+    proto: impl AsRef<ProtoEnvironment>,
+    locator: impl AsRef<PluginLocator>,
+    plugin_path: PathBuf,
+) -> miette::Result<Tool> {
+    let id = id.as_ref();
+    let proto = proto.as_ref();
+    let locator = locator.as_ref();
+
     // If a TOML plugin, we need to load the WASM plugin for it,
     // wrap it, and modify the plugin manifest.
     let mut manifest = if plugin_path.extension().is_some_and(|ext| ext == "toml") {
@@ -1,88 +1,151 @@
 use crate::error::ProtoError;
 use crate::proto_config::*;
 use crate::tool::Tool;
+use serde::Serialize;
 use std::env;
+use std::fmt;
+use std::path::PathBuf;
 use tracing::{debug, trace};
 use version_spec::*;
 
-pub async fn detect_version_first_available(
-    tool: &Tool,
-    config_manager: &ProtoConfigManager,
-) -> miette::Result<Option<UnresolvedVersionSpec>> {
-    for file in &config_manager.files {
-        if let Some(versions) = &file.config.versions {
-            if let Some(version) = versions.get(tool.id.as_str()) {
-                debug!(
-                    tool = tool.id.as_str(),
-                    version = version.to_string(),
-                    file = ?file.path,
-                    "Detected version from {} file", PROTO_CONFIG_NAME
-                );
-
-                return Ok(Some(version.to_owned()));
+/// Where a version was detected from.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum DetectedFrom {
+    /// Explicitly passed on the command line.
+    CommandLine,
+
+    /// A `<TOOL>_VERSION` environment variable.
+    EnvVar { name: String },
+
+    /// A `.prototools` file.
+    ProtoConfig { path: PathBuf },
+
+    /// A file from the tool's ecosystem, like `.nvmrc`.
+    VersionFile { path: PathBuf },
+}
+
+impl fmt::Display for DetectedFrom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CommandLine => write!(f, "command line"),
+            Self::EnvVar { name } => write!(f, "{name} environment variable"),
+            Self::ProtoConfig { path } | Self::VersionFile { path } => {
+                write!(f, "{}", path.display())
             }
         }
+    }
+}
 
-        let dir = file.path.parent().unwrap();
+fn detect_from_proto_config(
+    tool: &Tool,
+    file: &ProtoConfigFile,
+) -> Option<(UnresolvedVersionSpec, DetectedFrom)> {
+    let version = file.config.versions.as_ref()?.get(tool.id.as_str())?;
 
-        if let Some(version) = tool.detect_version_from(dir).await? {
-            debug!(
-                tool = tool.id.as_str(),
-                version = version.to_string(),
-                dir = ?dir,
-                "Detected version from tool's ecosystem"
-            );
+    debug!(
+        tool = tool.id.as_str(),
+        version = version.to_string(),
+        file = ?file.path,
+        "Detected version from {} file", PROTO_CONFIG_NAME
+    );
 
-            return Ok(Some(version));
+    Some((
+        version.to_owned(),
+        DetectedFrom::ProtoConfig {
+            path: file.path.clone(),
+        },
+    ))
+}
+
+async fn detect_from_ecosystem(
+    tool: &Tool,
+    file: &ProtoConfigFile,
+) -> miette::Result<Option<(UnresolvedVersionSpec, DetectedFrom)>> {
+    let dir = file.path.parent().unwrap();
+
+    if let Some((version, path)) = tool.detect_version_file_from(dir).await? {
+        debug!(
+            tool = tool.id.as_str(),
+            version = version.to_string(),
+            dir = ?dir,
+            "Detected version from tool's ecosystem"
+        );
+
+        return Ok(Some((version, DetectedFrom::VersionFile { path })));
+    }
+
+    Ok(None)
+}
+
+async fn detect_first_available(
+    tool: &Tool,
+    config_manager: &ProtoConfigManager,
+) -> miette::Result<Option<(UnresolvedVersionSpec, DetectedFrom)>> {
+    for file in &config_manager.files {
+        if let Some(detected) = detect_from_proto_config(tool, file) {
+            return Ok(Some(detected));
+        }
+
+        if let Some(detected) = detect_from_ecosystem(tool, file).await? {
+            return Ok(Some(detected));
         }
     }
 
     Ok(None)
 }
 
-pub async fn detect_version_prefer_prototools(
+async fn detect_prefer_prototools(
     tool: &Tool,
     config_manager: &ProtoConfigManager,
-) -> miette::Result<Option<UnresolvedVersionSpec>> {
+) -> miette::Result<Option<(UnresolvedVersionSpec, DetectedFrom)>> {
     // Check config files first
     for file in &config_manager.files {
-        if let Some(versions) = &file.config.versions {
-            if let Some(version) = versions.get(tool.id.as_str()) {
-                debug!(
-                    tool = tool.id.as_str(),
-                    version = version.to_string(),
-                    file = ?file.path,
-                    "Detected version from {} file", PROTO_CONFIG_NAME
-                );
-
-                return Ok(Some(version.to_owned()));
-            }
+        if let Some(detected) = detect_from_proto_config(tool, file) {
+            return Ok(Some(detected));
         }
     }
 
     // Then check the ecosystem
     for file in &config_manager.files {
-        let dir = file.path.parent().unwrap();
-
-        if let Some(version) = tool.detect_version_from(dir).await? {
-            debug!(
-                tool = tool.id.as_str(),
-                version = version.to_string(),
-                dir = ?dir,
-                "Detected version from tool's ecosystem"
-            );
-
-            return Ok(Some(version));
+        if let Some(detected) = detect_from_ecosystem(tool, file).await? {
+            return Ok(Some(detected));
         }
     }
 
     Ok(None)
 }
 
+pub async fn detect_version_first_available(
+    tool: &Tool,
+    config_manager: &ProtoConfigManager,
+) -> miette::Result<Option<UnresolvedVersionSpec>> {
+    Ok(detect_first_available(tool, config_manager)
+        .await?
+        .map(|detected| detected.0))
+}
+
+pub async fn detect_version_prefer_prototools(
+    tool: &Tool,
+    config_manager: &ProtoConfigManager,
+) -> miette::Result<Option<UnresolvedVersionSpec>> {
+    Ok(detect_prefer_prototools(tool, config_manager)
+        .await?
+        .map(|detected| detected.0))
+}
+
 pub async fn detect_version(
     tool: &Tool,
     forced_version: Option<UnresolvedVersionSpec>,
 ) -> miette::Result<UnresolvedVersionSpec> {
+    Ok(detect_version_with_source(tool, forced_version).await?.0)
+}
+
+/// Detect a version for the tool, and return where it was detected from.
+pub async fn detect_version_with_source(
+    tool: &Tool,
+    forced_version: Option<UnresolvedVersionSpec>,
+) -> miette::Result<(UnresolvedVersionSpec, DetectedFrom)> {
     if let Some(candidate) = forced_version {
         debug!(
             tool = tool.id.as_str(),
@@ -90,7 +153,7 @@ pub async fn detect_version(
             "Using explicit version passed on the command line",
         );
 
-        return Ok(candidate);
+        return Ok((candidate, DetectedFrom::CommandLine));
     }
 
     // Env var takes highest priority
@@ -104,12 +167,13 @@ pub async fn detect_version(
             "Detected version from environment variable",
         );
 
-        return Ok(
+        return Ok((
             UnresolvedVersionSpec::parse(&session_version).map_err(|error| ProtoError::Semver {
                 version: session_version,
                 error,
             })?,
-        );
+            DetectedFrom::EnvVar { name: env_var },
+        ));
     }
 
     // Traverse upwards and attempt to detect a version
@@ -123,16 +187,12 @@ pub async fn detect_version(
     let config = tool.proto.load_config()?;
 
     let detected_version = match config.settings.detect_strategy {
-        DetectStrategy::FirstAvailable => {
-            detect_version_first_available(tool, config_manager).await?
-        }
-        DetectStrategy::PreferPrototools => {
-            detect_version_prefer_prototools(tool, config_manager).await?
-        }
+        DetectStrategy::FirstAvailable => detect_first_available(tool, config_manager).await?,
+        DetectStrategy::PreferPrototools => detect_prefer_prototools(tool, config_manager).await?,
     };
 
-    if let Some(version) = detected_version {
-        return Ok(version);
+    if let Some(detected) = detected_version {
+        return Ok(detected);
     }
 
     // We didn't find anything!
@@ -55,6 +55,22 @@ impl<'tool> VersionResolver<'tool> {
         )
     }
 
+    /// Expand the candidate through all aliases it points to, and return
+    /// each step of the expansion, starting with the candidate itself.
+    pub fn expand_aliases(&self, candidate: &UnresolvedVersionSpec) -> Vec<UnresolvedVersionSpec> {
+        let mut steps = vec![candidate.to_owned()];
+
+        while let Some(UnresolvedVersionSpec::Alias(alias)) = steps.last() {
+            match lookup_alias(alias, &self.aliases, self.manifest, self.config) {
+                // Avoid infinite loops from circular aliases
+                Some(value) if !steps.contains(value) => steps.push(value.to_owned()),
+                _ => break,
+            };
+        }
+
+        steps
+    }
+
     pub fn resolve_without_manifest(
         &self,
         candidate: &UnresolvedVersionSpec,
@@ -77,6 +93,23 @@ pub fn match_highest_version(req: &VersionReq, versions: &[&Version]) -> Option<
     highest_match.map(VersionSpec::Version)
 }
 
+/// Return true if the resolved version satisfies the candidate. Aliases can't be
+/// compared without resolving them first, so they are always satisfied.
+pub fn is_version_satisfied(candidate: &UnresolvedVersionSpec, version: &VersionSpec) -> bool {
+    match (candidate, version) {
+        (UnresolvedVersionSpec::Alias(_), _) => true,
+        (UnresolvedVersionSpec::Canary, VersionSpec::Canary) => true,
+        (UnresolvedVersionSpec::Req(req), VersionSpec::Version(version)) => req.matches(version),
+        (UnresolvedVersionSpec::ReqAny(reqs), VersionSpec::Version(version)) => {
+            reqs.iter().any(|req| req.matches(version))
+        }
+        (UnresolvedVersionSpec::Version(expected), VersionSpec::Version(version)) => {
+            expected == version
+        }
+        _ => false,
+    }
+}
+
 // Filter out aliases because they cannot be matched against
 fn extract_installed_versions(installed: &HashSet<VersionSpec>) -> Vec<&Version> {
     installed
@@ -88,6 +121,28 @@ fn extract_installed_versions(installed: &HashSet<VersionSpec>) -> Vec<&Version>
         .collect()
 }
 
+fn lookup_alias<'a>(
+    alias: &str,
+    aliases: &'a BTreeMap<String, UnresolvedVersionSpec>,
+    manifest: Option<&'a ToolManifest>,
+    config: Option<&'a ProtoToolConfig>,
+) -> Option<&'a UnresolvedVersionSpec> {
+    let mut alias_value = None;
+
+    #[allow(deprecated)]
+    if let Some(config) = config {
+        alias_value = config.aliases.get(alias);
+    } else if let Some(manifest) = manifest {
+        alias_value = manifest.aliases.get(alias);
+    }
+
+    if alias_value.is_none() {
+        alias_value = aliases.get(alias);
+    }
+
+    alias_value
+}
+
 pub fn resolve_version(
     candidate: &UnresolvedVersionSpec,
     versions: &[Version],
@@ -107,20 +162,7 @@ pub fn resolve_version(
             return Some(VersionSpec::Canary);
         }
         UnresolvedVersionSpec::Alias(alias) => {
-            let mut alias_value = None;
-
-            #[allow(deprecated)]
-            if let Some(config) = config {
-                alias_value = config.aliases.get(alias);
-            } else if let Some(manifest) = manifest {
-                alias_value = manifest.aliases.get(alias);
-            }
-
-            if alias_value.is_none() {
-                alias_value = aliases.get(alias);
-            }
-
-            if let Some(value) = alias_value {
+            if let Some(value) = lookup_alias(alias, aliases, manifest, config) {
                 return resolve_version(value, versions, aliases, manifest, config);
             }
         }
@@ -0,0 +1,42 @@
+;; Passes the input of each exported function to the host function of the
+;; same name, and returns its output. Compiled with `wat` to `proxy.wasm`.
+(module
+  (import "env" "extism_alloc" (func $alloc (param i64) (result i64)))
+  (import "env" "extism_store_u8" (func $store_u8 (param i64 i32)))
+  (import "env" "extism_input_offset" (func $input_offset (result i64)))
+  (import "env" "extism_length" (func $length (param i64) (result i64)))
+  (import "env" "extism_output_set" (func $output_set (param i64 i64)))
+  (import "env" "fetch" (func $fetch (param i64) (result i64)))
+  (import "env" "hash_file" (func $hash_file (param i64) (result i64)))
+  (import "env" "list_dir" (func $list_dir (param i64) (result i64)))
+  (import "env" "read_file" (func $read_file (param i64) (result i64)))
+
+  (memory (export "memory") 1)
+  (data (i32.const 0) "{}")
+
+  (func $output (param $ptr i64)
+    (call $output_set (local.get $ptr) (call $length (local.get $ptr))))
+
+  (func (export "register_tool") (result i32)
+    (local $ptr i64)
+    (local.set $ptr (call $alloc (i64.const 2)))
+    (call $store_u8 (local.get $ptr) (i32.load8_u (i32.const 0)))
+    (call $store_u8 (i64.add (local.get $ptr) (i64.const 1)) (i32.load8_u (i32.const 1)))
+    (call $output_set (local.get $ptr) (i64.const 2))
+    (i32.const 0))
+
+  (func (export "fetch") (result i32)
+    (call $output (call $fetch (call $input_offset)))
+    (i32.const 0))
+
+  (func (export "hash_file") (result i32)
+    (call $output (call $hash_file (call $input_offset)))
+    (i32.const 0))
+
+  (func (export "list_dir") (result i32)
+    (call $output (call $list_dir (call $input_offset)))
+    (i32.const 0))
+
+  (func (export "read_file") (result i32)
+    (call $output (call $read_file (call $input_offset)))
+    (i32.const 0)))
@@ -0,0 +1,100 @@
+#![cfg(unix)]
+
+use proto_core::{FileStore, ProtoEnvironment};
+use starbase_sandbox::create_empty_sandbox;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+
+mod file_store {
+    use super::*;
+
+    fn create_large_content(seed: &str) -> String {
+        seed.repeat(5000)
+    }
+
+    #[test]
+    fn links_identical_files() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a/lib.js", create_large_content("a"));
+        sandbox.create_file("b/lib.js", create_large_content("a"));
+        sandbox.create_file("b/other.js", create_large_content("b"));
+
+        let proto = ProtoEnvironment::new_testing(sandbox.path());
+
+        let result_a = FileStore::dedupe(&proto, &sandbox.path().join("a")).unwrap();
+        let result_b = FileStore::dedupe(&proto, &sandbox.path().join("b")).unwrap();
+
+        assert_eq!(result_a.files, 0);
+        assert_eq!(result_b.files, 1);
+        assert_eq!(result_b.bytes, 5000);
+
+        let a = fs::metadata(sandbox.path().join("a/lib.js")).unwrap();
+        let b = fs::metadata(sandbox.path().join("b/lib.js")).unwrap();
+
+        assert_eq!(a.ino(), b.ino());
+        assert_eq!(a.nlink(), 3);
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join("b/lib.js")).unwrap(),
+            create_large_content("a")
+        );
+    }
+
+    #[test]
+    fn replaces_leftover_temp_files() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a/lib.js", create_large_content("a"));
+        sandbox.create_file("b/lib.js", create_large_content("a"));
+        sandbox.create_file("b/lib.js.dedupe", "interrupted");
+
+        let proto = ProtoEnvironment::new_testing(sandbox.path());
+
+        FileStore::dedupe(&proto, &sandbox.path().join("a")).unwrap();
+        let result = FileStore::dedupe(&proto, &sandbox.path().join("b")).unwrap();
+
+        assert_eq!(result.files, 1);
+        assert!(!sandbox.path().join("b/lib.js.dedupe").exists());
+        assert_eq!(
+            fs::metadata(sandbox.path().join("a/lib.js")).unwrap().ino(),
+            fs::metadata(sandbox.path().join("b/lib.js")).unwrap().ino()
+        );
+    }
+
+    #[test]
+    fn skips_small_files() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a/small.js", "small");
+        sandbox.create_file("b/small.js", "small");
+
+        let proto = ProtoEnvironment::new_testing(sandbox.path());
+
+        FileStore::dedupe(&proto, &sandbox.path().join("a")).unwrap();
+        let result = FileStore::dedupe(&proto, &sandbox.path().join("b")).unwrap();
+
+        assert_eq!(result.files, 0);
+        assert!(!proto.store_dir.exists());
+    }
+
+    #[test]
+    fn prunes_only_unreferenced_files() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a/lib.js", create_large_content("a"));
+        sandbox.create_file("b/lib.js", create_large_content("a"));
+
+        let proto = ProtoEnvironment::new_testing(sandbox.path());
+
+        FileStore::dedupe(&proto, &sandbox.path().join("a")).unwrap();
+        FileStore::dedupe(&proto, &sandbox.path().join("b")).unwrap();
+
+        fs::remove_dir_all(sandbox.path().join("a")).unwrap();
+
+        assert_eq!(FileStore::prune(&proto).unwrap().files, 0);
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join("b/lib.js")).unwrap(),
+            create_large_content("a")
+        );
+
+        fs::remove_dir_all(sandbox.path().join("b")).unwrap();
+
+        assert_eq!(FileStore::prune(&proto).unwrap().files, 1);
+    }
+}
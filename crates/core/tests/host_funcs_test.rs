@@ -0,0 +1,451 @@
+use proto_core::{Id, ProtoEnvironment, Tool, Wasm};
+use proto_pdk_api::{FetchInput, FetchOutput, HashAlgorithm, HashFileInput, ListDirInput};
+use starbase_sandbox::{create_empty_sandbox, Sandbox};
+use std::collections::HashMap;
+use std::env;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+// A module that passes its input to the host function of the same name
+fn load_proxy_tool(sandbox: &Sandbox) -> Tool {
+    // Requests are made to a local server
+    env::set_var("PROTO_OFFLINE", "0");
+
+    // Directories must exist to be made available to plugins
+    sandbox.create_file(".home/.keep", "");
+    sandbox.create_file(".proto/.keep", "");
+
+    Tool::load(
+        Id::raw("proxy"),
+        ProtoEnvironment::new_testing(sandbox.path()),
+        Wasm::file(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/__fixtures__/host-funcs/proxy.wasm"),
+        ),
+    )
+    .unwrap()
+}
+
+// Responds with the status in the request path, for example, `/404`
+fn start_server() -> (String, Arc<AtomicUsize>) {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let requests_clone = Arc::clone(&requests);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let host = listener.local_addr().unwrap().to_string();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let requests = Arc::clone(&requests_clone);
+
+            // Keep connections alive, so that clients can reuse them
+            thread::spawn(move || loop {
+                let mut buffer = [0; 4096];
+                let size = stream.read(&mut buffer).unwrap_or_default();
+
+                if size == 0 {
+                    break;
+                }
+
+                let request = String::from_utf8_lossy(&buffer[0..size]).to_string();
+                let path = request.split(' ').nth(1).unwrap_or_default().to_owned();
+                let status: u16 = path.trim_start_matches('/').parse().unwrap_or(200);
+                let body = format!("request {}", requests.fetch_add(1, Ordering::SeqCst) + 1);
+
+                if stream
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 {status} Status\r\ncontent-length: {}\r\n\r\n{body}",
+                            body.len()
+                        )
+                        .as_bytes(),
+                    )
+                    .is_err()
+                {
+                    break;
+                }
+            });
+        }
+    });
+
+    (format!("http://{host}"), requests)
+}
+
+mod user_data {
+    use super::*;
+    use extism::UserData;
+
+    #[derive(Clone)]
+    struct Data {
+        id: Id,
+        permissions: Option<Vec<String>>,
+        proto: Arc<ProtoEnvironment>,
+    }
+
+    // Host functions cast the user data pointer back to their data, which relies
+    // on extism boxing the value as-is, so fail if that ever changes
+    #[test]
+    fn stores_value_behind_pointer() {
+        let sandbox = create_empty_sandbox();
+        let data = Data {
+            id: Id::raw("proxy"),
+            permissions: Some(vec!["example.com".into()]),
+            proto: Arc::new(ProtoEnvironment::new_testing(sandbox.path())),
+        };
+
+        let user_data = UserData::new(data.clone());
+        let ptr = user_data.as_ptr() as *const Data;
+
+        assert!(!ptr.is_null());
+
+        let copy = unsafe { (*ptr).clone() };
+
+        assert_eq!(copy.id, data.id);
+        assert_eq!(copy.permissions, data.permissions);
+        assert!(Arc::ptr_eq(&copy.proto, &data.proto));
+    }
+}
+
+mod fetch {
+    use super::*;
+
+    fn fetch(tool: &Tool, input: FetchInput) -> FetchOutput {
+        tool.plugin.call_func_with("fetch", input).unwrap()
+    }
+
+    #[test]
+    fn returns_response() {
+        let sandbox = create_empty_sandbox();
+        let tool = load_proxy_tool(&sandbox);
+        let (url, _) = start_server();
+
+        let output = fetch(&tool, FetchInput::get(url));
+
+        assert_eq!(output.status, 200);
+        assert_eq!(output.body, "request 1");
+    }
+
+    #[test]
+    fn returns_error_statuses() {
+        let sandbox = create_empty_sandbox();
+        let tool = load_proxy_tool(&sandbox);
+        let (url, _) = start_server();
+
+        let output = fetch(&tool, FetchInput::get(format!("{url}/404")));
+
+        assert_eq!(output.status, 404);
+        assert!(!output.is_success());
+    }
+
+    #[test]
+    fn caches_responses() {
+        let sandbox = create_empty_sandbox();
+        let tool = load_proxy_tool(&sandbox);
+        let (url, requests) = start_server();
+        let input = FetchInput {
+            cache: true,
+            ..FetchInput::get(url)
+        };
+
+        let first = fetch(&tool, input.clone());
+        let second = fetch(&tool, input);
+
+        assert_eq!(first, second);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert!(sandbox.path().join(".proto/cache/requests").exists());
+    }
+
+    #[test]
+    fn doesnt_cache_without_opting_in() {
+        let sandbox = create_empty_sandbox();
+        let tool = load_proxy_tool(&sandbox);
+        let (url, requests) = start_server();
+
+        fetch(&tool, FetchInput::get(&url));
+        fetch(&tool, FetchInput::get(&url));
+
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn doesnt_cache_error_statuses() {
+        let sandbox = create_empty_sandbox();
+        let tool = load_proxy_tool(&sandbox);
+        let (url, requests) = start_server();
+        let input = FetchInput {
+            cache: true,
+            ..FetchInput::get(format!("{url}/500"))
+        };
+
+        fetch(&tool, input.clone());
+        fetch(&tool, input);
+
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn caches_responses_per_headers() {
+        let sandbox = create_empty_sandbox();
+        let tool = load_proxy_tool(&sandbox);
+        let (url, requests) = start_server();
+
+        for token in ["a", "b", "a"] {
+            fetch(
+                &tool,
+                FetchInput {
+                    cache: true,
+                    headers: HashMap::from_iter([(
+                        "Authorization".to_owned(),
+                        format!("Bearer {token}"),
+                    )]),
+                    ..FetchInput::get(&url)
+                },
+            );
+        }
+
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn denies_hosts_not_allowed() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".proto/.prototools",
+            r#"
+[tools.proxy.permissions]
+hosts = ["example.com"]
+"#,
+        );
+
+        let tool = load_proxy_tool(&sandbox);
+        let (url, requests) = start_server();
+
+        let error = tool
+            .plugin
+            .call_func_with::<_, FetchOutput>("fetch", FetchInput::get(url))
+            .unwrap_err();
+
+        assert!(error.to_string().contains("tools.proxy.permissions.hosts"));
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn makes_requests_in_current_thread_runtime() {
+        let sandbox = create_empty_sandbox();
+        let tool = load_proxy_tool(&sandbox);
+        let (url, _) = start_server();
+
+        for _ in 0..3 {
+            assert_eq!(fetch(&tool, FetchInput::get(&url)).status, 200);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn makes_requests_in_multi_thread_runtime() {
+        let sandbox = create_empty_sandbox();
+        let tool = load_proxy_tool(&sandbox);
+        let (url, _) = start_server();
+
+        for _ in 0..3 {
+            assert_eq!(fetch(&tool, FetchInput::get(&url)).status, 200);
+        }
+    }
+}
+
+mod read_file {
+    use super::*;
+
+    fn read_file(tool: &Tool, path: &str) -> miette::Result<Vec<u8>> {
+        tool.plugin.call("read_file", path)
+    }
+
+    #[test]
+    fn reads_file() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("file.txt", "content");
+
+        let tool = load_proxy_tool(&sandbox);
+
+        assert_eq!(read_file(&tool, "/workspace/file.txt").unwrap(), b"content");
+    }
+
+    #[test]
+    fn reads_non_utf8_file() {
+        let sandbox = create_empty_sandbox();
+        std::fs::write(sandbox.path().join("file.bin"), [0, 159, 146, 150]).unwrap();
+
+        let tool = load_proxy_tool(&sandbox);
+
+        assert_eq!(
+            read_file(&tool, "/workspace/file.bin").unwrap(),
+            [0, 159, 146, 150]
+        );
+    }
+
+    #[test]
+    fn denies_parent_traversal() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("file.txt", "content");
+
+        let tool = load_proxy_tool(&sandbox);
+        let error = read_file(&tool, "/workspace/.home/../file.txt").unwrap_err();
+
+        assert!(error.to_string().contains("outside of the directories"));
+    }
+
+    #[test]
+    fn denies_real_path_outside_scope() {
+        let sandbox = create_empty_sandbox();
+        let outside = create_empty_sandbox();
+        outside.create_file("secret.txt", "secret");
+
+        let tool = load_proxy_tool(&sandbox);
+        let error =
+            read_file(&tool, outside.path().join("secret.txt").to_str().unwrap()).unwrap_err();
+
+        assert!(error.to_string().contains("outside of the directories"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn denies_symlink_outside_scope() {
+        let sandbox = create_empty_sandbox();
+        let outside = create_empty_sandbox();
+        outside.create_file("secret.txt", "secret");
+
+        std::os::unix::fs::symlink(
+            outside.path().join("secret.txt"),
+            sandbox.path().join("link.txt"),
+        )
+        .unwrap();
+
+        let tool = load_proxy_tool(&sandbox);
+        let error = read_file(&tool, "/workspace/link.txt").unwrap_err();
+
+        assert!(error.to_string().contains("outside of the directories"));
+    }
+}
+
+mod list_dir {
+    use super::*;
+
+    fn list_dir(tool: &Tool, dir: &str, globs: &[&str]) -> Vec<PathBuf> {
+        tool.plugin
+            .call_func_with(
+                "list_dir",
+                ListDirInput {
+                    dir: PathBuf::from(dir),
+                    globs: globs.iter().map(|glob| glob.to_string()).collect(),
+                },
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn returns_virtual_paths() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("dir/a.txt", "");
+        sandbox.create_file("dir/b.json", "");
+        sandbox.create_file("dir/nested/c.txt", "");
+
+        let tool = load_proxy_tool(&sandbox);
+
+        assert_eq!(
+            list_dir(&tool, "/workspace/dir", &[]),
+            [
+                PathBuf::from("/workspace/dir/a.txt"),
+                PathBuf::from("/workspace/dir/b.json"),
+                PathBuf::from("/workspace/dir/nested"),
+            ]
+        );
+        assert_eq!(
+            list_dir(&tool, "/workspace/dir", &["**/*.txt"]),
+            [
+                PathBuf::from("/workspace/dir/a.txt"),
+                PathBuf::from("/workspace/dir/nested/c.txt"),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn filters_symlinks_outside_scope() {
+        let sandbox = create_empty_sandbox();
+        let outside = create_empty_sandbox();
+        sandbox.create_file("dir/a.txt", "");
+        outside.create_file("secret.txt", "secret");
+        outside.create_file("nested/secret.txt", "secret");
+
+        std::os::unix::fs::symlink(
+            outside.path().join("secret.txt"),
+            sandbox.path().join("dir/link.txt"),
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(
+            outside.path().join("nested"),
+            sandbox.path().join("dir/linked"),
+        )
+        .unwrap();
+
+        let tool = load_proxy_tool(&sandbox);
+
+        assert_eq!(
+            list_dir(&tool, "/workspace/dir", &["**/*.txt"]),
+            [PathBuf::from("/workspace/dir/a.txt")]
+        );
+        assert_eq!(
+            list_dir(&tool, "/workspace/dir", &[]),
+            [PathBuf::from("/workspace/dir/a.txt")]
+        );
+    }
+}
+
+mod hash_file {
+    use super::*;
+
+    fn hash_file(tool: &Tool, algorithm: HashAlgorithm) -> String {
+        let output = tool
+            .plugin
+            .call(
+                "hash_file",
+                serde_json::to_string(&HashFileInput {
+                    algorithm,
+                    path: PathBuf::from("/workspace/file.txt"),
+                })
+                .unwrap(),
+            )
+            .unwrap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn hashes_with_sha256() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("file.txt", "content");
+
+        let tool = load_proxy_tool(&sandbox);
+
+        assert_eq!(
+            hash_file(&tool, HashAlgorithm::Sha256),
+            "ed7002b439e9ac845f22357d822bac1444730fbdb6016d3ec9432297b9ec9f73"
+        );
+    }
+
+    #[test]
+    fn hashes_with_sha512() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("file.txt", "content");
+
+        let tool = load_proxy_tool(&sandbox);
+
+        assert_eq!(
+            hash_file(&tool, HashAlgorithm::Sha512),
+            "b2d1d285b5199c85f988d03649c37e44fd3dde01e5d69c50fef90651962f48110e9340b60d49a479c4c0b53f5f07d690686dd87d2481937a512e8b85ee7c617f"
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use proto_core::{ProjectRegistry, ProtoEnvironment};
+use starbase_sandbox::create_empty_sandbox;
+
+mod project_registry {
+    use super::*;
+
+    #[test]
+    fn tracks_loaded_projects() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a/.prototools", "node = \"18\"");
+
+        let mut proto = ProtoEnvironment::new_testing(sandbox.path());
+        proto.cwd = sandbox.path().join("a");
+
+        ProjectRegistry::track_loaded(&proto).unwrap();
+
+        let projects = ProjectRegistry::load(&proto).unwrap();
+
+        assert!(projects.contains_key(&sandbox.path().join("a")));
+    }
+
+    #[test]
+    fn doesnt_track_when_loading_config() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a/.prototools", "node = \"18\"");
+
+        let mut proto = ProtoEnvironment::new_testing(sandbox.path());
+        proto.cwd = sandbox.path().join("a");
+        proto.load_config_manager().unwrap();
+
+        assert!(!sandbox.path().join(".proto/projects.json").exists());
+    }
+
+    #[test]
+    fn doesnt_track_without_config() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a/file", "");
+
+        let mut proto = ProtoEnvironment::new_testing(sandbox.path());
+        proto.cwd = sandbox.path().join("a");
+
+        ProjectRegistry::track_loaded(&proto).unwrap();
+
+        assert!(ProjectRegistry::load(&proto).unwrap().is_empty());
+    }
+
+    #[test]
+    fn prunes_missing_projects() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a/.prototools", "node = \"18\"");
+        sandbox.create_file("b/.prototools", "node = \"20\"");
+
+        let proto = ProtoEnvironment::new_testing(sandbox.path());
+
+        ProjectRegistry::track(
+            &proto,
+            vec![sandbox.path().join("a"), sandbox.path().join("b")],
+        )
+        .unwrap();
+
+        std::fs::remove_dir_all(sandbox.path().join("b")).unwrap();
+
+        let pruned = ProjectRegistry::prune(&proto).unwrap();
+        let projects = ProjectRegistry::load(&proto).unwrap();
+
+        assert_eq!(pruned, vec![sandbox.path().join("b")]);
+        assert!(projects.contains_key(&sandbox.path().join("a")));
+        assert!(!projects.contains_key(&sandbox.path().join("b")));
+    }
+}
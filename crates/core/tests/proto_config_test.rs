@@ -1,11 +1,13 @@
 use proto_core::{
     DetectStrategy, PartialProtoSettingsConfig, PinType, ProtoConfig, ProtoConfigManager,
+    ProtoEnvironment, ProtoPlatform, ProtoToolPermissionsConfig,
 };
 use schematic::ConfigError;
 use starbase_sandbox::create_empty_sandbox;
 use starbase_utils::json::JsonValue;
 use std::collections::BTreeMap;
 use std::env;
+use system_env::{SystemArch, SystemLibc, SystemOS};
 use version_spec::UnresolvedVersionSpec;
 use warpgate::{GitHubLocator, HttpOptions, Id, PluginLocator};
 
@@ -75,6 +77,99 @@ pin-latest = "global"
         );
     }
 
+    #[test]
+    fn can_set_plugin_signing_settings() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings]
+require-signed-plugins = true
+trusted-plugin-keys = ["RWRIbpsKiAmxTifqozUd/QOJkLoXAnSgQKz98H0pWDY1ntjM5Mt7d0oe"]
+"#,
+        );
+
+        let config = ProtoConfig::load_from(sandbox.path(), false).unwrap();
+
+        assert_eq!(
+            config.settings.unwrap(),
+            PartialProtoSettingsConfig {
+                require_signed_plugins: Some(true),
+                trusted_plugin_keys: Some(vec![
+                    "RWRIbpsKiAmxTifqozUd/QOJkLoXAnSgQKz98H0pWDY1ntjM5Mt7d0oe".into()
+                ]),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn ignores_plugin_signing_settings_from_projects() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".proto/.prototools",
+            r#"
+[settings]
+require-signed-plugins = true
+trusted-plugin-keys = ["RWRIbpsKiAmxTifqozUd/QOJkLoXAnSgQKz98H0pWDY1ntjM5Mt7d0oe"]
+"#,
+        );
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings]
+require-signed-plugins = false
+trusted-plugin-keys = ["RWSIHoorubPSc6++OX+/hM64XbMSxz3jZSXEhPfTN/2zesFZ+kVS/HH1"]
+"#,
+        );
+
+        let proto = ProtoEnvironment::new_testing(sandbox.path());
+        let config = proto.load_config().unwrap();
+
+        assert!(config.settings.require_signed_plugins);
+        assert_eq!(
+            config.settings.trusted_plugin_keys,
+            vec!["RWRIbpsKiAmxTifqozUd/QOJkLoXAnSgQKz98H0pWDY1ntjM5Mt7d0oe".to_owned()]
+        );
+    }
+
+    #[test]
+    fn can_set_plugin_runtime_settings() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings]
+persistent-plugin-cache = true
+plugin-memory-pages = 1024
+plugin-timeout = 30
+"#,
+        );
+
+        let config = ProtoConfig::load_from(sandbox.path(), false).unwrap();
+
+        assert_eq!(
+            config.settings.unwrap(),
+            PartialProtoSettingsConfig {
+                persistent_plugin_cache: Some(true),
+                plugin_memory_pages: Some(1024),
+                plugin_timeout: Some(30),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn disables_plugin_timeout_by_default() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "");
+
+        let manager = ProtoConfigManager::load(sandbox.path(), None).unwrap();
+        let config = manager.get_merged_config().unwrap();
+
+        assert_eq!(config.settings.plugin_timeout, 0);
+    }
+
     #[test]
     fn can_set_settings_from_env_vars() {
         let sandbox = create_empty_sandbox();
@@ -123,7 +218,8 @@ bar = "source:https://moonrepo.dev/path/file.wasm"
                 (
                     Id::raw("bar"),
                     PluginLocator::SourceUrl {
-                        url: "https://moonrepo.dev/path/file.wasm".into()
+                        url: "https://moonrepo.dev/path/file.wasm".into(),
+                        integrity: None,
                     }
                 ),
                 (
@@ -132,12 +228,49 @@ bar = "source:https://moonrepo.dev/path/file.wasm"
                         file_prefix: "foo_plugin".into(),
                         repo_slug: "moonrepo/foo".into(),
                         tag: None,
+                        integrity: None,
                     })
                 ),
             ])
         );
     }
 
+    #[test]
+    fn pins_integrity_for_builtin_plugins() {
+        for (id, locator) in ProtoConfig::builtin_plugins() {
+            assert!(
+                locator.get_integrity().is_some(),
+                "Built-in plugin {id} does not pin an integrity digest"
+            );
+        }
+    }
+
+    #[test]
+    fn can_set_plugins_with_integrity() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[plugins]
+foo = "source:https://moonrepo.dev/foo.wasm#sha256=e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+
+[plugins.bar]
+locator = "source:https://moonrepo.dev/bar.wasm"
+integrity = "sha256=e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+"#,
+        );
+
+        let config = ProtoConfig::load_from(sandbox.path(), false).unwrap();
+        let plugins = config.plugins.unwrap();
+
+        for id in ["foo", "bar"] {
+            assert_eq!(
+                plugins.get(id).unwrap().get_integrity(),
+                Some("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+            );
+        }
+    }
+
     #[test]
     fn updates_plugin_files_to_absolute() {
         let sandbox = create_empty_sandbox();
@@ -157,7 +290,8 @@ foo = "source:../file.wasm"
                 Id::raw("foo"),
                 PluginLocator::SourceFile {
                     file: "../file.wasm".into(),
-                    path: sandbox.path().join("../file.wasm")
+                    path: sandbox.path().join("../file.wasm"),
+                    integrity: None,
                 }
             )])
         );
@@ -223,14 +357,16 @@ root-cert = "../cert.pem"
                     Id::raw("foo"),
                     PluginLocator::SourceFile {
                         file: "./test.toml".into(),
-                        path: sandbox.path().join("./test.toml")
+                        path: sandbox.path().join("./test.toml"),
+                        integrity: None,
                     }
                 ),
                 (
                     Id::raw("kebab-case"),
                     PluginLocator::SourceFile {
                         file: "./camel.toml".into(),
-                        path: sandbox.path().join("./camel.toml")
+                        path: sandbox.path().join("./camel.toml"),
+                        integrity: None,
                     }
                 )
             ])
@@ -259,6 +395,7 @@ root-cert = "../cert.pem"
             PluginLocator::SourceFile {
                 file: "./test.toml".into(),
                 path: sandbox.path().join("./test.toml"),
+                integrity: None,
             },
         );
 
@@ -311,6 +448,124 @@ intercept-globals = false
             );
         }
 
+        #[test]
+        fn can_set_permissions() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".proto/.prototools",
+                r#"
+[tools.node.permissions]
+commands = ["git"]
+env-vars = ["NODE_*"]
+hosts = ["*.github.com"]
+"#,
+            );
+
+            let proto = ProtoEnvironment::new_testing(sandbox.path());
+            let config = proto.load_config().unwrap();
+
+            let permissions = config
+                .tools
+                .get("node")
+                .unwrap()
+                .permissions
+                .clone()
+                .unwrap();
+
+            assert_eq!(permissions.commands, vec!["git".to_owned()]);
+            assert!(permissions.allows_command("git"));
+            assert!(!permissions.allows_command("/usr/bin/git"));
+            assert!(!permissions.allows_command("./git"));
+            assert!(!permissions.allows_command("git.sh"));
+            assert!(!permissions.allows_command("curl"));
+            assert!(permissions.allows_env_var("NODE_OPTIONS"));
+            assert!(!permissions.allows_env_var("HOME"));
+            assert!(permissions.allows_host("api.github.com"));
+            assert!(!permissions.allows_host("github.com"));
+        }
+
+        #[test]
+        fn only_allows_listed_command_paths() {
+            let permissions = ProtoToolPermissionsConfig {
+                commands: vec!["/usr/bin/git".into(), "node*".into()],
+                env_vars: vec![],
+                hosts: vec![],
+            };
+
+            assert!(permissions.allows_command("/usr/bin/git"));
+            assert!(!permissions.allows_command("/tmp/x/git"));
+            assert!(!permissions.allows_command("git"));
+            assert!(permissions.allows_command("node-gyp"));
+            assert!(!permissions.allows_command("/tmp/node"));
+
+            let permissions = ProtoToolPermissionsConfig {
+                commands: vec!["*".into()],
+                env_vars: vec![],
+                hosts: vec![],
+            };
+
+            assert!(permissions.allows_command("/tmp/x/git"));
+        }
+
+        #[test]
+        fn ignores_permissions_from_projects() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".proto/.prototools",
+                r#"
+[tools.node.permissions]
+commands = ["git"]
+"#,
+            );
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[tools.node.permissions]
+commands = ["*"]
+env-vars = ["*"]
+
+[tools.deno.permissions]
+commands = ["*"]
+"#,
+            );
+
+            let proto = ProtoEnvironment::new_testing(sandbox.path());
+            let config = proto.load_config().unwrap();
+
+            let permissions = config
+                .tools
+                .get("node")
+                .unwrap()
+                .permissions
+                .clone()
+                .unwrap();
+
+            assert_eq!(permissions.commands, vec!["git".to_owned()]);
+            assert!(permissions.env_vars.is_empty());
+            assert!(!permissions.allows_command("curl"));
+            assert!(config.tools.get("deno").unwrap().permissions.is_none());
+        }
+
+        #[test]
+        fn permissions_are_unrestricted_by_default() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[tools.node]
+value = "abc"
+"#,
+            );
+
+            let config = ProtoConfigManager::load(sandbox.path(), None)
+                .unwrap()
+                .get_merged_config()
+                .unwrap()
+                .to_owned();
+
+            assert!(config.tools.get("node").unwrap().permissions.is_none());
+        }
+
         #[test]
         fn merges_plugin_settings() {
             let sandbox = create_empty_sandbox();
@@ -397,6 +652,185 @@ value = "4.5.6"
             );
         }
     }
+
+    mod platform_config {
+        use super::*;
+
+        fn linux_arm64() -> ProtoPlatform {
+            ProtoPlatform::new(SystemOS::Linux, SystemArch::Arm64, SystemLibc::Gnu)
+        }
+
+        fn windows_x64() -> ProtoPlatform {
+            ProtoPlatform::new(SystemOS::Windows, SystemArch::X64, SystemLibc::Unknown)
+        }
+
+        #[test]
+        fn matches_platform_keys() {
+            let platform = linux_arm64();
+
+            assert_eq!(platform.matches("linux"), Some(1));
+            assert_eq!(platform.matches("linux-arm64"), Some(2));
+            assert_eq!(platform.matches("linux-aarch64-gnu"), Some(3));
+            assert_eq!(platform.matches("unix"), Some(1));
+            assert_eq!(platform.matches("cfg(unix)"), Some(1));
+            assert_eq!(
+                platform.matches(r#"cfg(target_os = "linux", target_arch = "aarch64")"#),
+                Some(2)
+            );
+            assert_eq!(platform.matches("linux-x64"), None);
+            assert_eq!(platform.matches("linux-musl"), None);
+            assert_eq!(platform.matches("cfg(windows)"), None);
+            assert_eq!(platform.matches("unknown"), None);
+        }
+
+        #[test]
+        fn defers_libc_detection() {
+            let platform = ProtoPlatform::from_env();
+
+            assert_eq!(platform.libc, None);
+        }
+
+        #[test]
+        #[should_panic(expected = "unknown platform `linux-foo`")]
+        fn errors_for_unknown_platform() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[versions.linux-foo]
+node = "1.2.3"
+"#,
+            );
+
+            handle_error(ProtoConfig::load_from(sandbox.path(), false).unwrap_err());
+        }
+
+        #[test]
+        #[should_panic(expected = "unknown platform `cfg(os = \"beos\")`")]
+        fn errors_for_unknown_tool_platform() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[tools.node.platform.'cfg(os = "beos")']
+version = "1.2.3"
+"#,
+            );
+
+            handle_error(ProtoConfig::load_from(sandbox.path(), false).unwrap_err());
+        }
+
+        #[test]
+        fn selects_platform_versions() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+node = "20.0.0"
+python = "3.12.0"
+
+[versions.'cfg(windows)']
+node = "18.0.0"
+
+[versions.linux]
+python = "3.11.0"
+"#,
+            );
+
+            let config =
+                ProtoConfig::load_for_platform(sandbox.path(), false, &windows_x64()).unwrap();
+
+            assert_eq!(
+                config.versions.unwrap(),
+                BTreeMap::from_iter([
+                    (
+                        Id::raw("node"),
+                        UnresolvedVersionSpec::parse("18.0.0").unwrap()
+                    ),
+                    (
+                        Id::raw("python"),
+                        UnresolvedVersionSpec::parse("3.12.0").unwrap()
+                    ),
+                ])
+            );
+
+            let config =
+                ProtoConfig::load_for_platform(sandbox.path(), false, &linux_arm64()).unwrap();
+
+            assert_eq!(
+                config.versions.unwrap(),
+                BTreeMap::from_iter([
+                    (
+                        Id::raw("node"),
+                        UnresolvedVersionSpec::parse("20.0.0").unwrap()
+                    ),
+                    (
+                        Id::raw("python"),
+                        UnresolvedVersionSpec::parse("3.11.0").unwrap()
+                    ),
+                ])
+            );
+        }
+
+        #[test]
+        fn selects_most_specific_tool_platform() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+python = "3.12.0"
+
+[versions.linux]
+python = "3.10.0"
+
+[tools.python.platform.linux-arm64]
+version = "3.11.0"
+plugin = "source:./python.wasm"
+
+[tools.python.platform.linux]
+version = "3.9.0"
+"#,
+            );
+
+            let config =
+                ProtoConfig::load_for_platform(sandbox.path(), false, &linux_arm64()).unwrap();
+
+            assert_eq!(
+                config.versions.unwrap().get("python").unwrap(),
+                &UnresolvedVersionSpec::parse("3.11.0").unwrap()
+            );
+            assert_eq!(
+                config.plugins.unwrap().get("python").unwrap(),
+                &PluginLocator::SourceFile {
+                    file: "./python.wasm".into(),
+                    path: sandbox.path().join("./python.wasm"),
+                    integrity: None,
+                }
+            );
+        }
+
+        #[test]
+        fn doesnt_select_when_loading_raw() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+node = "20.0.0"
+
+[versions.'cfg(unix)']
+node = "18.0.0"
+"#,
+            );
+
+            let config = ProtoConfig::load_from(sandbox.path(), false).unwrap();
+
+            assert_eq!(
+                config.versions.unwrap().get("node").unwrap(),
+                &UnresolvedVersionSpec::parse("20.0.0").unwrap()
+            );
+            assert!(config.platform_versions.unwrap().contains_key("cfg(unix)"));
+        }
+    }
 }
 
 mod proto_config_manager {
@@ -467,7 +901,8 @@ deno = "7.8.9"
             config.plugins.get("node").unwrap(),
             &PluginLocator::SourceFile {
                 file: "./node.toml".into(),
-                path: sandbox.path().join("one/two/three/./node.toml")
+                path: sandbox.path().join("one/two/three/./node.toml"),
+                integrity: None,
             }
         );
 
@@ -475,7 +910,8 @@ deno = "7.8.9"
             config.plugins.get("bun").unwrap(),
             &PluginLocator::SourceFile {
                 file: "../bun.wasm".into(),
-                path: sandbox.path().join("one/two/../bun.wasm")
+                path: sandbox.path().join("one/two/../bun.wasm"),
+                integrity: None,
             }
         );
     }
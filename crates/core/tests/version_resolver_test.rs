@@ -1,5 +1,6 @@
 use proto_core::{
-    resolve_version, ProtoToolConfig, ToolManifest, UnresolvedVersionSpec, VersionSpec,
+    is_version_satisfied, resolve_version, ProtoToolConfig, ToolManifest, UnresolvedVersionSpec,
+    VersionResolver, VersionSpec,
 };
 use semver::Version;
 use std::collections::BTreeMap;
@@ -496,4 +497,134 @@ mod version_resolver {
             .unwrap();
         }
     }
+
+    #[test]
+    fn expands_aliases() {
+        let mut resolver = VersionResolver::default();
+        resolver.aliases = create_aliases();
+
+        assert_eq!(
+            resolver.expand_aliases(&UnresolvedVersionSpec::Alias("stable".into())),
+            vec![
+                UnresolvedVersionSpec::Alias("stable".into()),
+                UnresolvedVersionSpec::Alias("latest".into()),
+                UnresolvedVersionSpec::Version(Version::new(10, 0, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn expands_aliases_until_missing() {
+        let mut resolver = VersionResolver::default();
+        resolver.aliases = create_aliases();
+
+        assert_eq!(
+            resolver.expand_aliases(&UnresolvedVersionSpec::Alias("no-alias".into())),
+            vec![
+                UnresolvedVersionSpec::Alias("no-alias".into()),
+                UnresolvedVersionSpec::Alias("missing".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn doesnt_expand_versions() {
+        let resolver = VersionResolver::default();
+        let version = UnresolvedVersionSpec::Version(Version::new(1, 2, 3));
+
+        assert_eq!(resolver.expand_aliases(&version), vec![version]);
+    }
+
+    #[test]
+    fn stops_expanding_circular_aliases() {
+        let mut resolver = VersionResolver::default();
+        resolver
+            .aliases
+            .insert("a".into(), UnresolvedVersionSpec::Alias("b".into()));
+        resolver
+            .aliases
+            .insert("b".into(), UnresolvedVersionSpec::Alias("a".into()));
+
+        assert_eq!(
+            resolver.expand_aliases(&UnresolvedVersionSpec::Alias("a".into())),
+            vec![
+                UnresolvedVersionSpec::Alias("a".into()),
+                UnresolvedVersionSpec::Alias("b".into()),
+            ]
+        );
+    }
+}
+
+mod version_satisfied {
+    use super::*;
+
+    #[test]
+    fn matches_requirements() {
+        let version = VersionSpec::parse("1.2.3").unwrap();
+
+        assert!(is_version_satisfied(
+            &UnresolvedVersionSpec::parse("^1").unwrap(),
+            &version
+        ));
+        assert!(is_version_satisfied(
+            &UnresolvedVersionSpec::parse(">=1.2, <2").unwrap(),
+            &version
+        ));
+        assert!(!is_version_satisfied(
+            &UnresolvedVersionSpec::parse("^2").unwrap(),
+            &version
+        ));
+    }
+
+    #[test]
+    fn matches_any_requirement() {
+        let spec = UnresolvedVersionSpec::parse("^1 || ^3").unwrap();
+
+        assert!(is_version_satisfied(
+            &spec,
+            &VersionSpec::parse("3.0.0").unwrap()
+        ));
+        assert!(!is_version_satisfied(
+            &spec,
+            &VersionSpec::parse("2.0.0").unwrap()
+        ));
+    }
+
+    #[test]
+    fn matches_exact_versions() {
+        let spec = UnresolvedVersionSpec::parse("1.2.3").unwrap();
+
+        assert!(is_version_satisfied(
+            &spec,
+            &VersionSpec::parse("1.2.3").unwrap()
+        ));
+        assert!(!is_version_satisfied(
+            &spec,
+            &VersionSpec::parse("1.2.4").unwrap()
+        ));
+    }
+
+    #[test]
+    fn always_matches_aliases() {
+        assert!(is_version_satisfied(
+            &UnresolvedVersionSpec::Alias("lts".into()),
+            &VersionSpec::parse("1.2.3").unwrap()
+        ));
+    }
+
+    #[test]
+    fn matches_canary_only_with_canary() {
+        assert!(is_version_satisfied(
+            &UnresolvedVersionSpec::Canary,
+            &VersionSpec::Canary
+        ));
+        assert!(!is_version_satisfied(
+            &UnresolvedVersionSpec::Canary,
+            &VersionSpec::parse("1.2.3").unwrap()
+        ));
+        assert!(!is_version_satisfied(
+            &UnresolvedVersionSpec::parse("^1").unwrap(),
+            &VersionSpec::Canary
+        ));
+    }
 }
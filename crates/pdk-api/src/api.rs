@@ -22,6 +22,11 @@ json_struct!(
 json_struct!(
     /// Information about the current state of the tool.
     pub struct ToolContext {
+        /// Virtual paths to the primary executable of each required tool,
+        /// keyed by the tool's identifier. Only available when running or installing.
+        #[serde(skip_serializing_if = "HashMap::is_empty")]
+        pub dependency_bins: HashMap<String, VirtualPath>,
+
         /// The version of proto (the core crate) calling plugin functions.
         pub proto_version: Option<Version>,
 
@@ -70,6 +75,30 @@ json_struct!(
     }
 );
 
+json_struct!(
+    /// Another tool that must be installed before the current tool
+    /// can be installed or ran.
+    pub struct ToolRequirement {
+        /// Identifier of the required tool.
+        pub id: String,
+
+        /// Version or range the required tool must satisfy. The version pinned by
+        /// the project is preferred, otherwise this will be installed.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub version: Option<UnresolvedVersionSpec>,
+    }
+);
+
+impl ToolRequirement {
+    /// Create a new requirement for the provided tool and version.
+    pub fn new<T: AsRef<str>>(id: T, version: Option<UnresolvedVersionSpec>) -> Self {
+        Self {
+            id: id.as_ref().to_owned(),
+            version,
+        }
+    }
+}
+
 json_struct!(
     /// Output returned by the `register_tool` function.
     pub struct ToolMetadataOutput {
@@ -87,6 +116,11 @@ json_struct!(
         #[serde(skip_serializing_if = "Option::is_none")]
         pub plugin_version: Option<String>,
 
+        /// Other tools that this tool requires, for example, a package manager
+        /// that requires a language runtime.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub requires: Vec<ToolRequirement>,
+
         /// Names of commands that will self-upgrade the tool,
         /// and should be blocked from happening.
         #[serde(skip_serializing_if = "Vec::is_empty")]
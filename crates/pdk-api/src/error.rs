@@ -5,6 +5,9 @@ pub enum PluginError {
     #[error("{0}")]
     Message(String),
 
+    #[error("Failed to make request to {url}: status code {status}")]
+    HttpStatus { url: String, status: u16 },
+
     #[error("Unable to install {tool}, unsupported architecture {arch}.")]
     UnsupportedArch { tool: String, arch: String },
 
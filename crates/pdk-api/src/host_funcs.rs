@@ -1,6 +1,7 @@
-use crate::{json_enum, json_struct};
+use crate::{json_enum, json_struct, PluginError};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 json_enum!(
     /// Target where host logs should be written to.
@@ -117,3 +118,102 @@ impl ExecCommandOutput {
         out
     }
 }
+
+json_struct!(
+    /// Input passed to the `fetch` host function.
+    pub struct FetchInput {
+        /// Body to send with the request.
+        pub body: Option<String>,
+
+        /// Cache the response in proto's cache directory. Only applies to
+        /// `GET` requests with a successful response.
+        pub cache: bool,
+
+        /// Headers to send with the request.
+        pub headers: HashMap<String, String>,
+
+        /// The HTTP method. Defaults to `GET`.
+        pub method: Option<String>,
+
+        /// The URL to request.
+        pub url: String,
+    }
+);
+
+impl FetchInput {
+    /// Create a new `GET` request for the provided URL.
+    pub fn get<U: AsRef<str>>(url: U) -> FetchInput {
+        FetchInput {
+            url: url.as_ref().to_owned(),
+            ..FetchInput::default()
+        }
+    }
+}
+
+json_struct!(
+    /// Output returned from the `fetch` host function.
+    pub struct FetchOutput {
+        /// Body of the response, as text.
+        pub body: String,
+
+        /// Headers of the response.
+        pub headers: HashMap<String, String>,
+
+        /// The HTTP status code.
+        pub status: u16,
+    }
+);
+
+impl FetchOutput {
+    /// Return true if the status code is in the 2xx range.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Return an error if the status code is not in the 2xx range,
+    /// otherwise return the output as-is.
+    pub fn error_for_status(self, url: &str) -> Result<Self, PluginError> {
+        if self.is_success() {
+            return Ok(self);
+        }
+
+        Err(PluginError::HttpStatus {
+            url: url.to_owned(),
+            status: self.status,
+        })
+    }
+}
+
+json_enum!(
+    /// Algorithm used by the `hash_file` host function.
+    #[derive(Default)]
+    #[serde(rename_all = "lowercase")]
+    pub enum HashAlgorithm {
+        #[default]
+        Sha256,
+        Sha512,
+    }
+);
+
+json_struct!(
+    /// Input passed to the `hash_file` host function.
+    pub struct HashFileInput {
+        /// The algorithm to hash with.
+        pub algorithm: HashAlgorithm,
+
+        /// Virtual path of the file to hash.
+        pub path: PathBuf,
+    }
+);
+
+json_struct!(
+    /// Input passed to the `list_dir` host function.
+    pub struct ListDirInput {
+        /// Virtual path of the directory to list.
+        pub dir: PathBuf,
+
+        /// Glob patterns to match, relative to the directory.
+        /// When empty, all direct children are returned.
+        pub globs: Vec<String>,
+    }
+);
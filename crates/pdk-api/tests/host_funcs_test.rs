@@ -0,0 +1,39 @@
+use proto_pdk_api::FetchOutput;
+
+mod fetch_output {
+    use super::*;
+
+    fn create_output(status: u16) -> FetchOutput {
+        FetchOutput {
+            body: "body".into(),
+            status,
+            ..FetchOutput::default()
+        }
+    }
+
+    #[test]
+    fn returns_success_statuses() {
+        for status in [200, 201, 204, 299] {
+            assert_eq!(
+                create_output(status)
+                    .error_for_status("https://example.com")
+                    .unwrap(),
+                create_output(status)
+            );
+        }
+    }
+
+    #[test]
+    fn errors_for_other_statuses() {
+        for status in [0, 100, 301, 404, 500] {
+            let error = create_output(status)
+                .error_for_status("https://example.com")
+                .unwrap_err();
+
+            assert_eq!(
+                error.to_string(),
+                format!("Failed to make request to https://example.com: status code {status}")
+            );
+        }
+    }
+}
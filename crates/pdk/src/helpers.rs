@@ -2,10 +2,12 @@ use crate::exec_command;
 use extism_pdk::http::request;
 use extism_pdk::*;
 use proto_pdk_api::{
-    ExecCommandInput, ExecCommandOutput, HostArch, HostEnvironment, HostOS, PluginError,
+    ExecCommandInput, ExecCommandOutput, FetchInput, FetchOutput, HashAlgorithm, HashFileInput,
+    HostArch, HostEnvironment, HostOS, ListDirInput, PluginError,
 };
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::vec;
 
 #[host_fn]
@@ -13,20 +15,47 @@ extern "ExtismHost" {
     fn exec_command(input: Json<ExecCommandInput>) -> Json<ExecCommandOutput>;
 }
 
+mod host {
+    use super::*;
+
+    #[host_fn]
+    extern "ExtismHost" {
+        pub fn fetch(input: Json<FetchInput>) -> Json<FetchOutput>;
+        pub fn from_virtual_path(path: String) -> String;
+        pub fn hash_file(input: Json<HashFileInput>) -> String;
+        pub fn list_dir(input: Json<ListDirInput>) -> Json<Vec<PathBuf>>;
+        pub fn read_file(path: String) -> Vec<u8>;
+        pub fn to_virtual_path(path: String) -> String;
+    }
+}
+
 /// Fetch the provided request and return a response object.
+#[deprecated = "Use `send_request` instead, which respects proto's HTTP settings."]
 pub fn fetch(req: HttpRequest, body: Option<String>) -> anyhow::Result<HttpResponse> {
     debug!("Fetching URL {}", req.url);
 
     request(&req, body).map_err(|e| anyhow::anyhow!("Failed to make request to {}: {e}", req.url))
 }
 
+/// Send the provided request through proto's HTTP client, with the `fetch` host
+/// function, and return the response. Will error for non-2xx status codes.
+pub fn send_request(input: FetchInput) -> anyhow::Result<FetchOutput> {
+    debug!("Fetching URL {}", input.url);
+
+    let url = input.url.clone();
+    let Json(output) = unsafe { host::fetch(Json(input)) }
+        .map_err(|e| anyhow::anyhow!("Failed to make request to {url}: {e}"))?;
+
+    Ok(output.error_for_status(&url)?)
+}
+
 /// Fetch the provided URL and deserialize the response as JSON.
 pub fn fetch_url<R, U>(url: U) -> anyhow::Result<R>
 where
     R: DeserializeOwned,
     U: AsRef<str>,
 {
-    fetch(HttpRequest::new(url.as_ref()), None)?.json()
+    Ok(json::from_str(&send_request(FetchInput::get(url))?.body)?)
 }
 
 /// Fetch the provided URL and return the text response.
@@ -34,45 +63,87 @@ pub fn fetch_url_text<U>(url: U) -> anyhow::Result<String>
 where
     U: AsRef<str>,
 {
-    String::from_bytes(fetch(HttpRequest::new(url.as_ref()), None)?.body())
+    Ok(send_request(FetchInput::get(url))?.body)
 }
 
 /// Fetch the provided URL, deserialize the response as JSON,
-/// and cache the response in memory for subsequent WASM function calls.
+/// and cache the response in proto's cache directory for subsequent calls.
 pub fn fetch_url_with_cache<R, U>(url: U) -> anyhow::Result<R>
 where
     R: DeserializeOwned,
     U: AsRef<str>,
 {
-    let url = url.as_ref();
-    let req = HttpRequest::new(url);
+    Ok(json::from_str(
+        &send_request(FetchInput {
+            cache: true,
+            ..FetchInput::get(url)
+        })?
+        .body,
+    )?)
+}
 
-    // Only cache GET requests
-    let cache = req.method.is_none()
-        || req
-            .method
-            .as_ref()
-            .is_some_and(|m| m.to_uppercase() == "GET");
+/// Convert the provided virtual path to a real path on the host.
+/// The path must be within one of the directories available to plugins.
+pub fn from_virtual_path<P: AsRef<Path>>(path: P) -> anyhow::Result<PathBuf> {
+    let path = path.as_ref().to_string_lossy().to_string();
 
-    if cache {
-        if let Some(body) = var::get::<Vec<u8>>(url)? {
-            debug!("Reading URL {} from cache (len = {})", url, body.len());
+    Ok(PathBuf::from(unsafe { host::from_virtual_path(path)? }))
+}
 
-            return Ok(json::from_slice(&body)?);
-        }
-    }
+/// Convert the provided real path on the host to a virtual path.
+/// The path must be within one of the directories available to plugins.
+pub fn to_virtual_path<P: AsRef<Path>>(path: P) -> anyhow::Result<PathBuf> {
+    let path = path.as_ref().to_string_lossy().to_string();
 
-    let res = fetch(req, None)?;
+    Ok(PathBuf::from(unsafe { host::to_virtual_path(path)? }))
+}
 
-    if cache {
-        let body = res.body();
+/// Read the contents of the file at the provided virtual path, from the host, as text.
+/// Will error if the file is not valid UTF-8, use [`read_file_bytes`] for binary files.
+pub fn read_file<P: AsRef<Path>>(path: P) -> anyhow::Result<String> {
+    let path = path.as_ref();
 
-        debug!("Writing URL {} to cache (len = {})", url, body.len());
+    String::from_utf8(read_file_bytes(path)?)
+        .map_err(|_| anyhow::anyhow!("File {} is not valid UTF-8", path.display()))
+}
 
-        var::set(url, body)?;
-    }
+/// Read the contents of the file at the provided virtual path, from the host, as bytes.
+pub fn read_file_bytes<P: AsRef<Path>>(path: P) -> anyhow::Result<Vec<u8>> {
+    let path = path.as_ref().to_string_lossy().to_string();
+
+    debug!("Reading file {}", path);
+
+    Ok(unsafe { host::read_file(path)? })
+}
+
+/// List the entries of the directory at the provided virtual path, from the host,
+/// that match the glob patterns. When no patterns are provided, all direct children
+/// are returned. The returned paths are virtual.
+pub fn list_dir<P, I, V>(dir: P, globs: I) -> anyhow::Result<Vec<PathBuf>>
+where
+    P: AsRef<Path>,
+    I: IntoIterator<Item = V>,
+    V: AsRef<str>,
+{
+    let Json(paths) = unsafe {
+        host::list_dir(Json(ListDirInput {
+            dir: dir.as_ref().to_path_buf(),
+            globs: globs.into_iter().map(|g| g.as_ref().to_owned()).collect(),
+        }))?
+    };
+
+    Ok(paths)
+}
 
-    res.json()
+/// Hash the file at the provided virtual path, from the host,
+/// and return the hex encoded digest.
+pub fn hash_file<P: AsRef<Path>>(path: P, algorithm: HashAlgorithm) -> anyhow::Result<String> {
+    Ok(unsafe {
+        host::hash_file(Json(HashFileInput {
+            algorithm,
+            path: path.as_ref().to_path_buf(),
+        }))?
+    })
 }
 
 /// Load all git tags from the provided remote URL.
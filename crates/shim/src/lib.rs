@@ -11,7 +11,7 @@ pub use unix::*;
 pub use windows::*;
 
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[cfg(debug_assertions)]
 pub const SHIM_VERSION: u8 = 0;
@@ -77,3 +77,17 @@ pub fn locate_proto_exe(bin: &str) -> Option<PathBuf> {
 
     None
 }
+
+/// Return an absolute path to the resolution cache for the provided working
+/// directory. The file name is an FNV-1a hash of the directory path, so that
+/// both proto and the shim can derive it without additional dependencies.
+pub fn get_shim_cache_path(shims_dir: &Path, dir: &Path) -> PathBuf {
+    let mut hash: u64 = 0xcbf29ce484222325;
+
+    for byte in dir.as_os_str().as_encoded_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    shims_dir.join("cache").join(format!("{hash:016x}.json"))
+}
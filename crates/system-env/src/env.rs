@@ -167,3 +167,56 @@ impl fmt::Display for SystemOS {
         write!(f, "{}", format!("{:?}", self).to_lowercase())
     }
 }
+
+/// C standard library of the host environment. Only applicable to Linux.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[cfg_attr(feature = "schematic", derive(schematic::Schematic))]
+#[serde(rename_all = "lowercase")]
+pub enum SystemLibc {
+    #[serde(alias = "glibc")]
+    Gnu,
+    Musl,
+    #[default]
+    Unknown,
+}
+
+impl SystemLibc {
+    /// Detect the libc of the current host. On Linux, this checks for
+    /// a musl dynamic loader, otherwise assumes GNU. On other operating
+    /// systems, this will always be unknown.
+    pub fn detect(os: SystemOS) -> SystemLibc {
+        if !os.is_linux() {
+            return SystemLibc::Unknown;
+        }
+
+        for dir in ["/lib", "/usr/lib"] {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                if entry.file_name().to_string_lossy().starts_with("ld-musl-") {
+                    return SystemLibc::Musl;
+                }
+            }
+        }
+
+        SystemLibc::Gnu
+    }
+
+    /// Return true if GNU libc.
+    pub fn is_gnu(&self) -> bool {
+        matches!(self, Self::Gnu)
+    }
+
+    /// Return true if musl libc.
+    pub fn is_musl(&self) -> bool {
+        matches!(self, Self::Musl)
+    }
+}
+
+impl fmt::Display for SystemLibc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
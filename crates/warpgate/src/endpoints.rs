@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Empty {}
@@ -16,6 +17,40 @@ pub struct GitHubApiAsset {
 #[serde(default)]
 pub struct GitHubApiRelease {
     pub assets: Vec<GitHubApiAsset>,
+    pub tag_name: String,
+}
+
+// OCI
+
+pub const OCI_MANIFEST_TYPES: &str =
+    "application/vnd.oci.image.manifest.v1+json, application/vnd.docker.distribution.manifest.v2+json";
+
+pub const OCI_WASM_LAYER_TYPES: [&str; 2] = [
+    "application/vnd.wasm.content.layer.v1+wasm",
+    "application/wasm",
+];
+
+pub const OCI_SIGNATURE_LAYER_TYPE: &str = "application/vnd.minisign.signature";
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OciDescriptor {
+    pub annotations: HashMap<String, String>,
+    pub digest: String,
+    pub media_type: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct OciManifest {
+    pub layers: Vec<OciDescriptor>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct OciTokenResponse {
+    pub token: Option<String>,
+    pub access_token: Option<String>,
 }
 
 // WAPM
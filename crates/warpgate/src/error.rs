@@ -38,6 +38,13 @@ pub enum WarpgateError {
     )]
     GitHubAssetMissing { repo_slug: String, tag: String },
 
+    #[diagnostic(code(plugin::github::release_missing))]
+    #[error(
+        "Cannot find the latest release for GitHub repository {}.",
+        .repo_slug.style(Style::Id),
+    )]
+    GitHubReleaseMissing { repo_slug: String },
+
     #[diagnostic(code(plugin::wapm::module_missing))]
     #[error(
         "Cannot download plugin from wamp.io ({}), no applicable module found for release {}.",
@@ -46,6 +53,88 @@ pub enum WarpgateError {
     )]
     WapmModuleMissing { package: String, version: String },
 
+    #[diagnostic(code(plugin::oci::layer_missing))]
+    #[error(
+        "Cannot download plugin from OCI registry ({}), no applicable WASM layer found for {}.",
+        .repository.style(Style::Id),
+        .reference,
+    )]
+    OciLayerMissing {
+        repository: String,
+        reference: String,
+    },
+
+    #[diagnostic(code(plugin::oci::invalid_manifest))]
+    #[error("Failed to parse OCI manifest from {}.", .url.style(Style::Url))]
+    OciManifestInvalid {
+        url: String,
+        #[source]
+        error: serde_json::Error,
+    },
+
+    #[diagnostic(code(plugin::oci::digest_mismatch))]
+    #[error(
+        "Downloaded content from {} does not match its digest. Expected {}, received {}.",
+        .url.style(Style::Url),
+        .expected.style(Style::Hash),
+        .actual.style(Style::Hash),
+    )]
+    OciDigestMismatch {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[diagnostic(
+        code(plugin::integrity_mismatch),
+        help = "The plugin may have been tampered with, or the configured integrity is out of date."
+    )]
+    #[error(
+        "Plugin {} ({}) does not match its integrity digest. Expected {}, received {}.",
+        .id.style(Style::Id),
+        .path.style(Style::Path),
+        .expected.style(Style::Hash),
+        .actual.style(Style::Hash),
+    )]
+    IntegrityMismatch {
+        id: String,
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
+
+    #[diagnostic(
+        code(plugin::signature::missing),
+        help = "Ensure the plugin is published with a detached minisign signature (.minisig next to the file, or a signature layer for OCI artifacts), or remove it from the plugins cache to download it again."
+    )]
+    #[error(
+        "Plugin {} ({}) is not signed, but signed plugins are required.",
+        .id.style(Style::Id),
+        .path.style(Style::Path),
+    )]
+    SignatureMissing { id: String, path: PathBuf },
+
+    #[diagnostic(code(plugin::signature::invalid))]
+    #[error(
+        "Plugin {} ({}) does not match its signature, or was not signed by a trusted public key.",
+        .id.style(Style::Id),
+        .path.style(Style::Path),
+    )]
+    SignatureInvalid { id: String, path: PathBuf },
+
+    #[diagnostic(code(plugin::signature::invalid_key))]
+    #[error("Invalid trusted public key {}.", .key.style(Style::Hash))]
+    SignatureInvalidKey {
+        key: String,
+
+        #[source]
+        error: minisign_verify::Error,
+    },
+
+    #[diagnostic(code(plugin::signature::no_keys))]
+    #[error("Signed plugins are required, but no trusted public keys have been configured.")]
+    SignatureNoTrustedKeys,
+
     #[diagnostic(code(plugin::create::failed))]
     #[error("Failed to load and create WASM plugin: {error}")]
     PluginCreateFailed {
@@ -61,6 +150,36 @@ pub enum WarpgateError {
     #[error("{error}")]
     PluginCallFailedRelease { error: String },
 
+    #[diagnostic(
+        code(plugin::call_func::timeout),
+        help = "Increase the timeout with the --plugin-timeout option or the plugin-timeout setting."
+    )]
+    #[error(
+        "Plugin {} timed out after {timeout}ms while calling function {}.",
+        .id.style(Style::Id),
+        .func.style(Style::Label),
+    )]
+    PluginCallTimeout {
+        id: String,
+        func: String,
+        timeout: u64,
+    },
+
+    #[diagnostic(
+        code(plugin::call_func::memory_limit),
+        help = "Increase the limit with the plugin-memory-pages setting."
+    )]
+    #[error(
+        "Plugin {} exceeded the memory limit of {max_pages} pages while calling function {}.",
+        .id.style(Style::Id),
+        .func.style(Style::Label),
+    )]
+    PluginMemoryLimitExceeded {
+        id: String,
+        func: String,
+        max_pages: u32,
+    },
+
     #[diagnostic(code(plugin::call_func::format_input))]
     #[error("Failed to format input for plugin function {} call.", .func.style(Style::Id))]
     FormatInputFailed {
@@ -37,6 +37,18 @@ pub fn create_wasm_file_prefix(name: &str) -> String {
     name
 }
 
+/// Return the environment variable name that holds the token for an OCI registry,
+/// for example, `OCI_REGISTRY_TOKEN_GHCR_IO` for `ghcr.io`.
+pub fn get_oci_registry_token_var(registry: &str) -> String {
+    let host = registry
+        .to_uppercase()
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect::<String>();
+
+    format!("OCI_REGISTRY_TOKEN_{host}")
+}
+
 pub async fn download_from_url_to_file(
     source_url: &str,
     temp_file: &Path,
@@ -3,15 +3,18 @@ use crate::endpoints::*;
 use crate::error::WarpgateError;
 use crate::helpers::{
     determine_cache_extension, download_from_url_to_file, extract_prefix_from_slug,
-    move_or_unpack_download,
+    get_oci_registry_token_var, move_or_unpack_download,
 };
 use crate::id::Id;
-use crate::locator::{GitHubLocator, PluginLocator, WapmLocator};
+use crate::locator::{GitHubLocator, OciLocator, PluginLocator, WapmLocator};
 use once_cell::sync::OnceCell;
+use reqwest::header;
 use sha2::{Digest, Sha256};
 use starbase_styles::color;
-use starbase_utils::fs;
+use starbase_utils::fs::{self, FsError};
+use std::collections::BTreeMap;
 use std::env;
+use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -32,14 +35,30 @@ pub struct PluginLoader {
     /// Checks whether there's an internet connection or not.
     offline_checker: Option<OfflineChecker>,
 
+    /// How long downloaded plugins are cached for. If `None`, forever.
+    cache_ttl: Option<Duration>,
+
+    /// How long downloaded plugins using a latest strategy are cached for.
+    /// If `None`, forever.
+    latest_cache_ttl: Option<Duration>,
+
     /// Location where downloaded `.wasm` plugins are stored.
     plugins_dir: PathBuf,
 
+    /// Ignore the cache and always download plugins.
+    refresh: bool,
+
     /// Location where temporary files (like archives) are stored.
     temp_dir: PathBuf,
 
     /// A unique seed for generating hashes.
     seed: Option<String>,
+
+    /// Refuse to load plugins that are not signed by a trusted key.
+    require_signed: bool,
+
+    /// Minisign public keys that plugin signatures are verified with.
+    trusted_keys: Vec<String>,
 }
 
 impl PluginLoader {
@@ -53,9 +72,14 @@ impl PluginLoader {
             http_client: OnceCell::new(),
             http_options: HttpOptions::default(),
             offline_checker: None,
+            cache_ttl: Some(Duration::from_secs(86400 * 30)),
+            latest_cache_ttl: Some(Duration::from_secs(86400 * 7)),
             plugins_dir: plugins_dir.to_owned(),
+            refresh: false,
             temp_dir: temp_dir.as_ref().to_owned(),
             seed: None,
+            require_signed: false,
+            trusted_keys: vec![],
         }
     }
 
@@ -81,7 +105,7 @@ impl PluginLoader {
             color::id(id.as_str())
         );
 
-        match locator {
+        let path = match locator {
             PluginLocator::SourceFile { path, .. } => {
                 let path = path
                     .canonicalize()
@@ -94,26 +118,180 @@ impl PluginLoader {
                         "Using source file",
                     );
 
-                    Ok(path)
+                    path
                 } else {
-                    Err(WarpgateError::SourceFileMissing(path).into())
+                    return Err(WarpgateError::SourceFileMissing(path).into());
                 }
             }
-            PluginLocator::SourceUrl { url } => {
+            PluginLocator::SourceUrl { url, .. } => {
                 self.download_plugin(
                     id,
                     url,
                     self.create_cache_path(id, url, url.contains("latest")),
                 )
-                .await
+                .await?
+            }
+            PluginLocator::GitHub(github) => self.download_plugin_from_github(id, github).await?,
+            PluginLocator::Wapm(wapm) => self.download_plugin_from_wapm(id, wapm).await?,
+            PluginLocator::Oci(oci) => self.download_plugin_from_oci(id, oci).await?,
+        };
+
+        // Verify both freshly downloaded and previously cached files,
+        // as the cache may have been modified since it was written
+        if let Some(integrity) = locator.get_integrity() {
+            self.verify_integrity(id, &path, integrity)?;
+        }
+
+        // Local files are under the user's control, so they are only verified
+        // when a signature exists next to them, and are never required to be signed
+        let is_local = matches!(locator, PluginLocator::SourceFile { .. });
+
+        if self.is_signing_enabled() && (!is_local || self.get_signature_path(&path).exists()) {
+            self.verify_signature(id, &path)?;
+        }
+
+        Ok(path)
+    }
+
+    /// Return the path to the detached minisign signature of the provided plugin file.
+    pub fn get_signature_path(&self, path: &Path) -> PathBuf {
+        path.with_file_name(format!("{}.minisig", fs::file_name(path)))
+    }
+
+    /// Verify the plugin file against its detached minisign signature, using the
+    /// trusted public keys. Unsigned plugins are only rejected when required.
+    pub fn verify_signature(&self, id: &Id, path: &Path) -> miette::Result<()> {
+        let signature_path = self.get_signature_path(path);
+
+        if !signature_path.exists() {
+            if self.require_signed {
+                return Err(WarpgateError::SignatureMissing {
+                    id: id.to_string(),
+                    path: path.to_path_buf(),
+                }
+                .into());
+            }
+
+            trace!(plugin = id.as_str(), path = ?path, "Plugin is not signed, skipping verification");
+
+            return Ok(());
+        }
+
+        if self.trusted_keys.is_empty() {
+            if self.require_signed {
+                return Err(WarpgateError::SignatureNoTrustedKeys.into());
+            }
+
+            return Ok(());
+        }
+
+        trace!(
+            plugin = id.as_str(),
+            path = ?path,
+            signature = ?signature_path,
+            "Verifying plugin signature",
+        );
+
+        let invalid = || WarpgateError::SignatureInvalid {
+            id: id.to_string(),
+            path: path.to_path_buf(),
+        };
+
+        let signature = minisign_verify::Signature::decode(&fs::read_file(&signature_path)?)
+            .map_err(|_| invalid())?;
+        let contents = fs::read_file_bytes(path)?;
+
+        for key in &self.trusted_keys {
+            let public_key = minisign_verify::PublicKey::from_base64(key).map_err(|error| {
+                WarpgateError::SignatureInvalidKey {
+                    key: key.to_owned(),
+                    error,
+                }
+            })?;
+
+            if public_key.verify(&contents, &signature, false).is_ok() {
+                trace!(plugin = id.as_str(), "Plugin signature verified");
+
+                return Ok(());
+            }
+        }
+
+        Err(invalid().into())
+    }
+
+    /// Verify that the SHA256 digest of the provided plugin file matches the expected
+    /// digest. Mismatched files in the plugins directory are removed, so that they
+    /// are downloaded again on the next load.
+    pub fn verify_integrity(&self, id: &Id, path: &Path, expected: &str) -> miette::Result<()> {
+        trace!(plugin = id.as_str(), path = ?path, "Verifying plugin integrity");
+
+        let mut file = fs::open_file(path)?;
+        let mut sha = Sha256::new();
+
+        io::copy(&mut file, &mut sha).map_err(|error| FsError::Read {
+            path: path.to_path_buf(),
+            error,
+        })?;
+
+        let actual = format!("{:x}", sha.finalize());
+
+        if actual == expected {
+            return Ok(());
+        }
+
+        if path.starts_with(&self.plugins_dir) {
+            fs::remove_file(path)?;
+        }
+
+        Err(WarpgateError::IntegrityMismatch {
+            id: id.to_string(),
+            path: path.to_path_buf(),
+            expected: expected.to_owned(),
+            actual,
+        }
+        .into())
+    }
+
+    /// Load a plugin using the provided locator, like [`PluginLoader::load_plugin`],
+    /// but ignore the cache and download remote plugins again.
+    pub async fn refresh_plugin<I: AsRef<Id>, L: AsRef<PluginLocator>>(
+        &self,
+        id: I,
+        locator: L,
+    ) -> miette::Result<PathBuf> {
+        let mut loader = self.clone();
+        loader.refresh = true;
+        loader.load_plugin(id, locator).await
+    }
+
+    /// Fetch the tag of the latest release for the provided GitHub repository.
+    pub async fn fetch_latest_github_tag(&self, repo_slug: &str) -> miette::Result<String> {
+        let api_url = format!("https://api.github.com/repos/{repo_slug}/releases/latest");
+
+        if self.is_offline() {
+            return Err(WarpgateError::InternetConnectionRequired {
+                message: "Unable to check for the latest release.".into(),
+                url: api_url,
+            }
+            .into());
+        }
+
+        let release = self.fetch_github_release(&api_url).await?;
+
+        if release.tag_name.is_empty() {
+            return Err(WarpgateError::GitHubReleaseMissing {
+                repo_slug: repo_slug.to_owned(),
             }
-            PluginLocator::GitHub(github) => self.download_plugin_from_github(id, github).await,
-            PluginLocator::Wapm(wapm) => self.download_plugin_from_wapm(id, wapm).await,
+            .into());
         }
+
+        Ok(release.tag_name)
     }
 
     /// Create an absolute path to the plugin's destination file, located in the plugins directory.
     /// Hash the source URL to ensure uniqueness of each plugin + version combination.
+    /// When signing is enabled, the signing options are also hashed, so that plugins
+    /// cached without a signature are downloaded again (with their signature).
     pub fn create_cache_path(&self, id: &Id, url: &str, is_latest: bool) -> PathBuf {
         let mut sha = Sha256::new();
         sha.update(url);
@@ -122,6 +300,18 @@ impl PluginLoader {
             sha.update(seed);
         }
 
+        if self.is_signing_enabled() {
+            sha.update(if self.require_signed {
+                "signed"
+            } else {
+                "trusted"
+            });
+
+            for key in &self.trusted_keys {
+                sha.update(key);
+            }
+        }
+
         self.plugins_dir.join(format!(
             "{id}{}{:x}{}",
             if is_latest { "-latest-" } else { "-" },
@@ -130,9 +320,29 @@ impl PluginLoader {
         ))
     }
 
-    /// Check if the plugin has been downloaded and is cached.
-    /// If using a latest strategy (no explicit version or tag), the cache
-    /// is only valid for 7 days (to ensure not stale), otherwise forever.
+    /// Return the path that a remote plugin is cached at, whether it has been
+    /// downloaded or not. Local source files are not cached, so return nothing.
+    pub fn get_cache_path(&self, id: &Id, locator: &PluginLocator) -> Option<PathBuf> {
+        let (url, is_latest) = match locator {
+            PluginLocator::SourceFile { .. } => return None,
+            PluginLocator::SourceUrl { url, .. } => (url.to_owned(), url.contains("latest")),
+            PluginLocator::GitHub(github) => (
+                get_github_api_url(github),
+                github.tag.as_deref().unwrap_or("latest") == "latest",
+            ),
+            PluginLocator::Wapm(wapm) => (
+                get_wapm_cache_url(wapm),
+                wapm.version.as_deref().unwrap_or("latest") == "latest",
+            ),
+            PluginLocator::Oci(oci) => (get_oci_manifest_url(oci), oci.is_latest()),
+        };
+
+        Some(self.create_cache_path(id, &url, is_latest))
+    }
+
+    /// Check if the plugin has been downloaded and is cached. If using a latest strategy
+    /// (no explicit version or tag), the cache is valid for the latest TTL (7 days by default),
+    /// otherwise the TTL (30 days by default). Stale files are replaced when downloaded again.
     pub fn is_cached(&self, id: &Id, path: &Path) -> miette::Result<bool> {
         if !path.exists() {
             trace!(plugin = id.as_str(), "Plugin not cached, downloading");
@@ -140,28 +350,32 @@ impl PluginLoader {
             return Ok(false);
         }
 
-        let metadata = fs::metadata(path)?;
+        if self.refresh {
+            trace!(plugin = id.as_str(), path = ?path, "Refreshing cached plugin, re-downloading");
 
-        let mut cached = if let Ok(filetime) = metadata.created().or_else(|_| metadata.modified()) {
-            let days = if fs::file_name(path).contains("-latest-") {
-                7
-            } else {
-                30
-            };
+            return Ok(false);
+        }
 
-            filetime > SystemTime::now() - Duration::from_secs(86400 * days)
+        let metadata = fs::metadata(path)?;
+
+        let ttl = if fs::file_name(path).contains("-latest-") {
+            self.latest_cache_ttl
         } else {
-            false
+            self.cache_ttl
+        };
+
+        let mut cached = match ttl {
+            Some(ttl) => match metadata.modified().or_else(|_| metadata.created()) {
+                Ok(filetime) => filetime > SystemTime::now() - ttl,
+                Err(_) => false,
+            },
+            None => true,
         };
 
         if !cached && self.is_offline() {
             cached = true;
         }
 
-        if !cached {
-            fs::remove_file(path)?;
-        }
-
         if cached {
             trace!(plugin = id.as_str(), path = ?path, "Plugin already downloaded and cached");
         } else {
@@ -171,6 +385,11 @@ impl PluginLoader {
         Ok(cached)
     }
 
+    /// Whether plugin signatures should be downloaded and verified.
+    pub fn is_signing_enabled(&self) -> bool {
+        self.require_signed || !self.trusted_keys.is_empty()
+    }
+
     /// Check for an internet connection.
     pub fn is_offline(&self) -> bool {
         self.offline_checker
@@ -179,11 +398,25 @@ impl PluginLoader {
             .unwrap_or_default()
     }
 
+    /// Set how long downloaded plugins are cached for, and how long downloaded
+    /// plugins using a latest strategy are cached for. If `None`, forever.
+    pub fn set_cache_ttls(&mut self, ttl: Option<Duration>, latest_ttl: Option<Duration>) {
+        self.cache_ttl = ttl;
+        self.latest_cache_ttl = latest_ttl;
+    }
+
     /// Set the options to pass to the HTTP client.
     pub fn set_client_options(&mut self, options: &HttpOptions) {
         self.http_options = options.to_owned();
     }
 
+    /// Set the public keys that plugin signatures are verified with,
+    /// and whether unsigned plugins should be refused.
+    pub fn set_signing_options(&mut self, trusted_keys: &[String], require_signed: bool) {
+        self.trusted_keys = trusted_keys.to_vec();
+        self.require_signed = require_signed;
+    }
+
     /// Set the function that checks for offline state.
     pub fn set_offline_checker(&mut self, op: fn() -> bool) {
         self.offline_checker = Some(Arc::new(op));
@@ -224,31 +457,58 @@ impl PluginLoader {
         download_from_url_to_file(source_url, &temp_file, self.get_client()?).await?;
         move_or_unpack_download(&temp_file, &dest_file)?;
 
+        // Remove the signature of a previous download
+        fs::remove_file(self.get_signature_path(&dest_file))?;
+
+        // Signatures are published next to the file, and are only
+        // applicable to `.wasm` files, not archives
+        if self.is_signing_enabled() && source_url.ends_with(".wasm") {
+            self.download_signature(id, source_url, &dest_file).await?;
+        }
+
         Ok(dest_file)
     }
 
+    async fn download_signature(
+        &self,
+        id: &Id,
+        source_url: &str,
+        dest_file: &Path,
+    ) -> miette::Result<()> {
+        let signature_url = format!("{source_url}.minisig");
+        let signature_file = self.get_signature_path(dest_file);
+        let temp_file = self.get_signature_path(&self.temp_dir.join(fs::file_name(dest_file)));
+
+        trace!(
+            plugin = id.as_str(),
+            from = &signature_url,
+            to = ?signature_file,
+            "Downloading plugin signature"
+        );
+
+        match download_from_url_to_file(&signature_url, &temp_file, self.get_client()?).await {
+            Ok(_) => {
+                fs::rename(&temp_file, &signature_file)?;
+            }
+            Err(error) => {
+                trace!(
+                    plugin = id.as_str(),
+                    "Plugin signature not available: {}",
+                    error
+                );
+            }
+        };
+
+        Ok(())
+    }
+
     async fn download_plugin_from_github(
         &self,
         id: &Id,
         github: &GitHubLocator,
     ) -> miette::Result<PathBuf> {
-        let (api_url, release_tag) = if let Some(tag) = &github.tag {
-            (
-                format!(
-                    "https://api.github.com/repos/{}/releases/tags/{tag}",
-                    github.repo_slug,
-                ),
-                tag.to_owned(),
-            )
-        } else {
-            (
-                format!(
-                    "https://api.github.com/repos/{}/releases/latest",
-                    github.repo_slug,
-                ),
-                "latest".to_owned(),
-            )
-        };
+        let api_url = get_github_api_url(github);
+        let release_tag = github.tag.as_deref().unwrap_or("latest").to_owned();
 
         // Check the cache first using the API URL as the seed,
         // so that we can avoid making unnecessary HTTP requests.
@@ -265,11 +525,6 @@ impl PluginLoader {
             "Attempting to download plugin from GitHub release",
         );
 
-        let handle_error = |error: reqwest::Error| WarpgateError::Http {
-            error,
-            url: api_url.clone(),
-        };
-
         if self.is_offline() {
             return Err(WarpgateError::InternetConnectionRequired {
                 message: format!(
@@ -283,14 +538,7 @@ impl PluginLoader {
 
         // Otherwise make an HTTP request to the GitHub releases API,
         // and loop through the assets to find a matching one.
-        let mut request = self.get_client()?.get(&api_url);
-
-        if let Ok(auth_token) = env::var("GITHUB_TOKEN") {
-            request = request.bearer_auth(auth_token);
-        }
-
-        let response = request.send().await.map_err(handle_error)?;
-        let release: GitHubApiRelease = response.json().await.map_err(handle_error)?;
+        let release = self.fetch_github_release(&api_url).await?;
 
         // Find a direct WASM asset first
         for asset in &release.assets {
@@ -339,16 +587,31 @@ impl PluginLoader {
         .into())
     }
 
+    async fn fetch_github_release(&self, api_url: &str) -> miette::Result<GitHubApiRelease> {
+        let handle_error = |error: reqwest::Error| WarpgateError::Http {
+            error,
+            url: api_url.to_owned(),
+        };
+
+        let mut request = self.get_client()?.get(api_url);
+
+        if let Ok(auth_token) = env::var("GITHUB_TOKEN") {
+            request = request.bearer_auth(auth_token);
+        }
+
+        let response = request.send().await.map_err(handle_error)?;
+        let release: GitHubApiRelease = response.json().await.map_err(handle_error)?;
+
+        Ok(release)
+    }
+
     async fn download_plugin_from_wapm(
         &self,
         id: &Id,
         wapm: &WapmLocator,
     ) -> miette::Result<PathBuf> {
         let version = wapm.version.as_deref().unwrap_or("latest");
-        let fake_api_url = format!(
-            "https://registry.wapm.io/graphql/{}@{}",
-            wapm.package_name, version
-        );
+        let fake_api_url = get_wapm_cache_url(wapm);
 
         // Check the cache first using the API URL as the seed,
         // so that we can avoid making unnecessary HTTP requests.
@@ -455,4 +718,335 @@ impl PluginLoader {
         }
         .into())
     }
+
+    async fn download_plugin_from_oci(&self, id: &Id, oci: &OciLocator) -> miette::Result<PathBuf> {
+        let api_url = oci.get_api_url();
+        let reference = oci.get_reference();
+        let manifest_url = get_oci_manifest_url(oci);
+
+        // Check the cache first using the manifest URL as the seed,
+        // so that we can avoid making unnecessary HTTP requests.
+        let plugin_path = self.create_cache_path(id, &manifest_url, oci.is_latest());
+
+        if self.is_cached(id, &plugin_path)? {
+            return Ok(plugin_path);
+        }
+
+        trace!(
+            plugin = id.as_str(),
+            manifest_url = &manifest_url,
+            "Attempting to download plugin from OCI registry",
+        );
+
+        if self.is_offline() {
+            return Err(WarpgateError::InternetConnectionRequired {
+                message: format!(
+                    "Unable to download plugin {} from OCI registry.",
+                    PluginLocator::Oci(oci.to_owned())
+                ),
+                url: manifest_url,
+            }
+            .into());
+        }
+
+        // Use an explicit token if provided for this registry, otherwise one
+        // will be requested anonymously if the registry requires it. Tokens are
+        // scoped per host, so they're never sent to a registry named by a locator
+        // that the token wasn't configured for.
+        let mut token = env::var(get_oci_registry_token_var(&oci.registry))
+            .ok()
+            .filter(|token| !token.is_empty());
+
+        let response = self
+            .send_oci_request(&manifest_url, OCI_MANIFEST_TYPES, &mut token)
+            .await?;
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|error| WarpgateError::Http {
+                error,
+                url: manifest_url.clone(),
+            })?;
+
+        // When requested by digest, the manifest itself is content
+        // addressable, so verify it before trusting its layers
+        if let Some(expected) = oci
+            .digest
+            .as_deref()
+            .and_then(|digest| digest.strip_prefix("sha256:"))
+        {
+            let actual = format!("{:x}", Sha256::digest(&bytes));
+
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(WarpgateError::OciDigestMismatch {
+                    url: manifest_url,
+                    expected: expected.to_owned(),
+                    actual,
+                }
+                .into());
+            }
+        }
+
+        let manifest: OciManifest =
+            serde_json::from_slice(&bytes).map_err(|error| WarpgateError::OciManifestInvalid {
+                error,
+                url: manifest_url.clone(),
+            })?;
+
+        // Find a layer with a WASM media type or file name,
+        // otherwise use the only layer available
+        let layer = manifest
+            .layers
+            .iter()
+            .find(|layer| {
+                OCI_WASM_LAYER_TYPES.contains(&layer.media_type.as_str())
+                    || layer
+                        .annotations
+                        .get("org.opencontainers.image.title")
+                        .is_some_and(|title| title.ends_with(".wasm"))
+            })
+            .or_else(|| {
+                if manifest.layers.len() == 1 {
+                    manifest.layers.first()
+                } else {
+                    None
+                }
+            });
+
+        let Some(layer) = layer else {
+            return Err(WarpgateError::OciLayerMissing {
+                repository: oci.repository.to_owned(),
+                reference: reference.to_owned(),
+            }
+            .into());
+        };
+
+        trace!(
+            plugin = id.as_str(),
+            digest = &layer.digest,
+            media_type = &layer.media_type,
+            "Found WASM layer, downloading blob"
+        );
+
+        let bytes = self.download_oci_blob(&api_url, layer, &mut token).await?;
+        let temp_file = self.temp_dir.join(fs::file_name(&plugin_path));
+
+        fs::write_file(&temp_file, bytes)?;
+        move_or_unpack_download(&temp_file, &plugin_path)?;
+
+        // Remove the signature of a previous download
+        let signature_path = self.get_signature_path(&plugin_path);
+
+        fs::remove_file(&signature_path)?;
+
+        // Signatures are published as another layer in the same artifact
+        if self.is_signing_enabled() {
+            let signature_layer = manifest.layers.iter().find(|layer| {
+                layer.media_type == OCI_SIGNATURE_LAYER_TYPE
+                    || layer
+                        .annotations
+                        .get("org.opencontainers.image.title")
+                        .is_some_and(|title| title.ends_with(".minisig"))
+            });
+
+            if let Some(signature_layer) = signature_layer {
+                trace!(
+                    plugin = id.as_str(),
+                    digest = &signature_layer.digest,
+                    "Found signature layer, downloading blob"
+                );
+
+                let bytes = self
+                    .download_oci_blob(&api_url, signature_layer, &mut token)
+                    .await?;
+
+                fs::write_file(&signature_path, bytes)?;
+            } else {
+                trace!(plugin = id.as_str(), "Plugin signature layer not available");
+            }
+        }
+
+        Ok(plugin_path)
+    }
+
+    async fn download_oci_blob(
+        &self,
+        api_url: &str,
+        layer: &OciDescriptor,
+        token: &mut Option<String>,
+    ) -> miette::Result<Vec<u8>> {
+        let blob_url = format!("{api_url}/blobs/{}", layer.digest);
+
+        let response = self.send_oci_request(&blob_url, "*/*", token).await?;
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|error| WarpgateError::Http {
+                error,
+                url: blob_url.clone(),
+            })?;
+
+        // Blobs are content addressable, so verify the contents
+        if let Some(expected) = layer.digest.strip_prefix("sha256:") {
+            let actual = format!("{:x}", Sha256::digest(&bytes));
+
+            if actual != expected {
+                return Err(WarpgateError::OciDigestMismatch {
+                    url: blob_url,
+                    expected: expected.to_owned(),
+                    actual,
+                }
+                .into());
+            }
+        }
+
+        Ok(bytes.to_vec())
+    }
+
+    async fn send_oci_request(
+        &self,
+        url: &str,
+        accept: &str,
+        token: &mut Option<String>,
+    ) -> miette::Result<reqwest::Response> {
+        let client = self.get_client()?;
+
+        let handle_error = |error: reqwest::Error| WarpgateError::Http {
+            error,
+            url: url.to_owned(),
+        };
+
+        let send = |token: Option<&String>| {
+            let mut request = client.get(url).header(header::ACCEPT, accept);
+
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            }
+
+            request.send()
+        };
+
+        let mut response = send(token.as_ref()).await.map_err(handle_error)?;
+
+        // The registry requires a token, so request one anonymously
+        // using the challenge, and try again
+        if response.status().as_u16() == 401 && token.is_none() {
+            if let Some(challenge) = response
+                .headers()
+                .get(header::WWW_AUTHENTICATE)
+                .and_then(|value| value.to_str().ok())
+            {
+                *token = Some(self.request_oci_token(challenge).await?);
+
+                response = send(token.as_ref()).await.map_err(handle_error)?;
+            }
+        }
+
+        let status = response.status();
+
+        if status.as_u16() == 404 {
+            return Err(WarpgateError::DownloadNotFound {
+                url: url.to_owned(),
+            }
+            .into());
+        }
+
+        if !status.is_success() {
+            return Err(WarpgateError::DownloadFailed {
+                url: url.to_owned(),
+                status: status.to_string(),
+            }
+            .into());
+        }
+
+        Ok(response)
+    }
+
+    async fn request_oci_token(&self, challenge: &str) -> miette::Result<String> {
+        // Bearer realm="https://ghcr.io/token",service="ghcr.io",scope="repository:org/name:pull"
+        let params = parse_auth_challenge(challenge);
+
+        let Some(realm) = params.get("realm").copied() else {
+            return Err(WarpgateError::Serde(format!(
+                "Invalid authentication challenge from OCI registry: {challenge}"
+            ))
+            .into());
+        };
+
+        trace!(realm, "Requesting anonymous token from OCI registry");
+
+        let handle_error = |error: reqwest::Error| WarpgateError::Http {
+            error,
+            url: realm.to_owned(),
+        };
+
+        let query = params
+            .iter()
+            .filter(|(key, _)| **key == "service" || **key == "scope")
+            .collect::<Vec<_>>();
+
+        let response = self
+            .get_client()?
+            .get(realm)
+            .query(&query)
+            .send()
+            .await
+            .map_err(handle_error)?;
+
+        if !response.status().is_success() {
+            return Err(WarpgateError::DownloadFailed {
+                url: realm.to_owned(),
+                status: response.status().to_string(),
+            }
+            .into());
+        }
+
+        let data: OciTokenResponse = response.json().await.map_err(handle_error)?;
+
+        data.token.or(data.access_token).ok_or_else(|| {
+            WarpgateError::Serde("OCI registry did not return an authentication token.".into())
+                .into()
+        })
+    }
+}
+
+fn parse_auth_challenge(challenge: &str) -> BTreeMap<&str, &str> {
+    let params = challenge
+        .strip_prefix("Bearer ")
+        .or_else(|| challenge.strip_prefix("bearer "))
+        .unwrap_or(challenge);
+
+    params
+        .split(',')
+        .filter_map(|param| param.trim().split_once('='))
+        .map(|(key, value)| (key, value.trim_matches('"')))
+        .collect()
+}
+
+fn get_github_api_url(github: &GitHubLocator) -> String {
+    match &github.tag {
+        Some(tag) => format!(
+            "https://api.github.com/repos/{}/releases/tags/{tag}",
+            github.repo_slug
+        ),
+        None => format!(
+            "https://api.github.com/repos/{}/releases/latest",
+            github.repo_slug
+        ),
+    }
+}
+
+// Not a real endpoint, only used for caching
+fn get_wapm_cache_url(wapm: &WapmLocator) -> String {
+    format!(
+        "https://registry.wapm.io/graphql/{}@{}",
+        wapm.package_name,
+        wapm.version.as_deref().unwrap_or("latest")
+    )
+}
+
+fn get_oci_manifest_url(oci: &OciLocator) -> String {
+    format!("{}/manifests/{}", oci.get_api_url(), oci.get_reference())
 }
@@ -17,6 +17,9 @@ pub struct GitHubLocator {
 
     /// Release tag to use. Defaults to `latest`.
     pub tag: Option<String>,
+
+    /// Expected SHA256 digest of the `.wasm` file.
+    pub integrity: Option<String>,
 }
 
 /// A wapm.io package locator.
@@ -31,17 +34,75 @@ pub struct WapmLocator {
 
     /// Version to use. Defaults to `latest`.
     pub version: Option<String>,
+
+    /// Expected SHA256 digest of the `.wasm` file.
+    pub integrity: Option<String>,
 }
 
-/// Strategies for locating plugins.
+/// An OCI registry artifact locator.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OciLocator {
+    /// Registry host, with an optional port: `ghcr.io`.
+    pub registry: String,
+
+    /// Repository within the registry: `org/plugin`.
+    pub repository: String,
+
+    /// Tag to use. Defaults to `latest`.
+    pub tag: Option<String>,
+
+    /// Content digest to use, takes precedence over the tag: `sha256:...`.
+    pub digest: Option<String>,
+
+    /// Expected SHA256 digest of the `.wasm` file.
+    pub integrity: Option<String>,
+}
+
+impl OciLocator {
+    /// Return the reference (digest or tag) to request a manifest with.
+    pub fn get_reference(&self) -> &str {
+        self.digest
+            .as_deref()
+            .or(self.tag.as_deref())
+            .unwrap_or("latest")
+    }
+
+    /// Return the base URL for registry API requests. Local registries
+    /// are assumed to not use TLS.
+    pub fn get_api_url(&self) -> String {
+        let host = self.registry.split(':').next().unwrap_or_default();
+        let scheme = if host == "localhost" || host == "127.0.0.1" {
+            "http"
+        } else {
+            "https"
+        };
+
+        format!("{scheme}://{}/v2/{}", self.registry, self.repository)
+    }
+
+    /// Whether the reference is mutable, and may change over time.
+    pub fn is_latest(&self) -> bool {
+        self.digest.is_none() && self.tag.as_deref().unwrap_or("latest") == "latest"
+    }
+}
+
+/// Strategies for locating plugins. Every strategy may be suffixed
+/// with an expected digest of the `.wasm` file: `#sha256=...`.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
-#[serde(untagged, into = "String", try_from = "String")]
+#[serde(untagged, into = "String", try_from = "PluginLocatorInput")]
 pub enum PluginLocator {
     /// source:path/to/file.wasm
-    SourceFile { file: String, path: PathBuf },
+    SourceFile {
+        file: String,
+        path: PathBuf,
+        integrity: Option<String>,
+    },
 
     /// source:https://url/to/file.wasm
-    SourceUrl { url: String },
+    SourceUrl {
+        url: String,
+        integrity: Option<String>,
+    },
 
     /// github:owner/repo
     /// github:owner/repo@tag
@@ -50,20 +111,89 @@ pub enum PluginLocator {
     /// wapm:package/name
     /// wapm:package/name@version
     Wapm(WapmLocator),
+
+    /// oci:registry/org/name
+    /// oci:registry/org/name:tag
+    /// oci:registry/org/name@sha256:digest
+    Oci(OciLocator),
+}
+
+impl PluginLocator {
+    /// Return the expected SHA256 digest of the `.wasm` file, if configured.
+    pub fn get_integrity(&self) -> Option<&str> {
+        match self {
+            PluginLocator::SourceFile { integrity, .. } => integrity.as_deref(),
+            PluginLocator::SourceUrl { integrity, .. } => integrity.as_deref(),
+            PluginLocator::GitHub(github) => github.integrity.as_deref(),
+            PluginLocator::Wapm(wapm) => wapm.integrity.as_deref(),
+            PluginLocator::Oci(oci) => oci.integrity.as_deref(),
+        }
+    }
+
+    /// Set the expected SHA256 digest of the `.wasm` file.
+    pub fn set_integrity(&mut self, value: Option<String>) {
+        match self {
+            PluginLocator::SourceFile { integrity, .. } => *integrity = value,
+            PluginLocator::SourceUrl { integrity, .. } => *integrity = value,
+            PluginLocator::GitHub(github) => github.integrity = value,
+            PluginLocator::Wapm(wapm) => wapm.integrity = value,
+            PluginLocator::Oci(oci) => oci.integrity = value,
+        };
+    }
 }
 
 #[cfg(feature = "schematic")]
 impl schematic::Schematic for PluginLocator {
     fn generate_schema() -> schematic::SchemaType {
-        schematic::SchemaType::string()
+        use schematic::{SchemaField, SchemaType};
+
+        SchemaType::union([
+            SchemaType::string(),
+            SchemaType::structure([
+                SchemaField::new("locator", SchemaType::string()),
+                SchemaField::new("integrity", SchemaType::nullable(SchemaType::string())),
+            ]),
+        ])
     }
 }
 
+/// Parse an integrity value in the format of `sha256=<hex>`,
+/// and return the lowercased hex digest.
+// Only a trailing `#sha<bits>=...` is an integrity digest, as URLs
+// and file paths may contain a `#` themselves
+fn split_integrity(value: &str) -> Option<(&str, &str)> {
+    let (location, integrity) = value.rsplit_once('#')?;
+    let (algorithm, _) = integrity.split_once('=')?;
+    let bits = algorithm.strip_prefix("sha")?;
+
+    if bits.is_empty() || !bits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((location, integrity))
+}
+
+fn parse_integrity(value: &str) -> Result<String, WarpgateError> {
+    let Some(digest) = value.strip_prefix("sha256=") else {
+        return Err(WarpgateError::Serde(
+            "Plugin integrity must be a SHA256 digest (sha256=...).".into(),
+        ));
+    };
+
+    if digest.len() != 64 || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(WarpgateError::Serde(
+            "Plugin integrity must be a 64 character hex encoded SHA256 digest.".into(),
+        ));
+    }
+
+    Ok(digest.to_lowercase())
+}
+
 impl Display for PluginLocator {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             PluginLocator::SourceFile { file, .. } => write!(f, "source:{}", file),
-            PluginLocator::SourceUrl { url } => write!(f, "source:{}", url),
+            PluginLocator::SourceUrl { url, .. } => write!(f, "source:{}", url),
             PluginLocator::GitHub(github) => write!(
                 f,
                 "github:{}{}",
@@ -83,6 +213,62 @@ impl Display for PluginLocator {
                     .map(|v| format!("@{v}"))
                     .unwrap_or_default()
             ),
+            PluginLocator::Oci(oci) => write!(
+                f,
+                "oci:{}/{}{}",
+                oci.registry,
+                oci.repository,
+                if let Some(digest) = &oci.digest {
+                    format!("@{digest}")
+                } else if let Some(tag) = &oci.tag {
+                    format!(":{tag}")
+                } else {
+                    String::new()
+                }
+            ),
+        }?;
+
+        if let Some(integrity) = self.get_integrity() {
+            write!(f, "#sha256={integrity}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Locators may be configured as a string, or as a table
+/// with the integrity declared separately.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PluginLocatorInput {
+    String(String),
+    Table {
+        locator: String,
+        integrity: Option<String>,
+    },
+}
+
+impl TryFrom<PluginLocatorInput> for PluginLocator {
+    type Error = WarpgateError;
+
+    fn try_from(input: PluginLocatorInput) -> Result<Self, Self::Error> {
+        match input {
+            PluginLocatorInput::String(value) => PluginLocator::try_from(value),
+            PluginLocatorInput::Table { locator, integrity } => {
+                let mut locator = PluginLocator::try_from(locator)?;
+
+                if let Some(integrity) = integrity {
+                    if locator.get_integrity().is_some() {
+                        return Err(WarpgateError::Serde(
+                            "Plugin integrity cannot be declared in both the locator and the integrity setting.".into(),
+                        ));
+                    }
+
+                    locator.set_integrity(Some(parse_integrity(&integrity)?));
+                }
+
+                Ok(locator)
+            }
         }
     }
 }
@@ -99,83 +285,133 @@ impl TryFrom<String> for PluginLocator {
     type Error = WarpgateError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        let mut parts = value.splitn(2, ':');
-
-        let Some(scope) = parts.next() else {
-            return Err(WarpgateError::Serde(
-                "Missing plugin scope or location.".into(),
-            ));
+        let (value, integrity) = match split_integrity(&value) {
+            Some((value, integrity)) => (value, Some(parse_integrity(integrity)?)),
+            None => (value.as_str(), None),
         };
 
-        let Some(location) = parts.next() else {
-            return Err(WarpgateError::Serde(
-                "Missing plugin scope or location.".into(),
-            ));
-        };
+        let mut locator = parse_locator(value)?;
+        locator.set_integrity(integrity);
 
-        if location.is_empty() {
-            return Err(WarpgateError::Serde(
-                "Missing plugin location (after :).".into(),
-            ));
-        }
+        Ok(locator)
+    }
+}
 
-        match scope {
-            "source" => {
-                if location.starts_with("http:") {
-                    Err(WarpgateError::Serde(
-                        "Only https URLs are supported for source plugins.".into(),
-                    ))
-                } else if location.starts_with("https:") {
-                    Ok(PluginLocator::SourceUrl {
-                        url: location.to_owned(),
-                    })
-                } else {
-                    Ok(PluginLocator::SourceFile {
-                        file: location.to_owned(),
-                        path: PathBuf::from(location),
-                    })
-                }
+fn parse_locator(value: &str) -> Result<PluginLocator, WarpgateError> {
+    let mut parts = value.splitn(2, ':');
+
+    let Some(scope) = parts.next() else {
+        return Err(WarpgateError::Serde(
+            "Missing plugin scope or location.".into(),
+        ));
+    };
+
+    let Some(location) = parts.next() else {
+        return Err(WarpgateError::Serde(
+            "Missing plugin scope or location.".into(),
+        ));
+    };
+
+    if location.is_empty() {
+        return Err(WarpgateError::Serde(
+            "Missing plugin location (after :).".into(),
+        ));
+    }
+
+    match scope {
+        "source" => {
+            if location.starts_with("http:") {
+                Err(WarpgateError::Serde(
+                    "Only https URLs are supported for source plugins.".into(),
+                ))
+            } else if location.starts_with("https:") {
+                Ok(PluginLocator::SourceUrl {
+                    url: location.to_owned(),
+                    integrity: None,
+                })
+            } else {
+                Ok(PluginLocator::SourceFile {
+                    file: location.to_owned(),
+                    path: PathBuf::from(location),
+                    integrity: None,
+                })
             }
-            "github" => {
-                if !location.contains('/') {
-                    return Err(WarpgateError::Serde(
+        }
+        "github" => {
+            if !location.contains('/') {
+                return Err(WarpgateError::Serde(
                         "GitHub release locator requires a repository with organization scope (org/repo)."
                             .into(),
                     ));
-                }
+            }
 
-                let mut parts = location.splitn(2, '@');
-                let repo_slug = parts.next().unwrap().to_owned();
-                let tag = parts.next().map(|t| t.to_owned());
+            let mut parts = location.splitn(2, '@');
+            let repo_slug = parts.next().unwrap().to_owned();
+            let tag = parts.next().map(|t| t.to_owned());
 
-                Ok(PluginLocator::GitHub(GitHubLocator {
-                    file_prefix: create_wasm_file_prefix(extract_suffix_from_slug(&repo_slug)),
-                    repo_slug,
-                    tag,
-                }))
+            Ok(PluginLocator::GitHub(GitHubLocator {
+                file_prefix: create_wasm_file_prefix(extract_suffix_from_slug(&repo_slug)),
+                repo_slug,
+                tag,
+                integrity: None,
+            }))
+        }
+        "wapm" => {
+            if !location.contains('/') {
+                return Err(WarpgateError::Serde(
+                    "wapm.io locator requires a package with owner scope (owner/package).".into(),
+                ));
             }
-            "wapm" => {
-                if !location.contains('/') {
-                    return Err(WarpgateError::Serde(
-                        "wapm.io locator requires a package with owner scope (owner/package)."
-                            .into(),
-                    ));
-                }
 
-                let mut parts = location.splitn(2, '@');
-                let package_name = parts.next().unwrap().to_owned();
-                let version = parts.next().map(|t| t.to_owned());
+            let mut parts = location.splitn(2, '@');
+            let package_name = parts.next().unwrap().to_owned();
+            let version = parts.next().map(|t| t.to_owned());
+
+            Ok(PluginLocator::Wapm(WapmLocator {
+                file_prefix: create_wasm_file_prefix(extract_suffix_from_slug(&package_name)),
+                package_name,
+                version,
+                integrity: None,
+            }))
+        }
+        "oci" => {
+            let Some((registry, path)) = location.split_once('/') else {
+                return Err(WarpgateError::Serde(
+                    "OCI locator requires a registry and repository (registry/org/name).".into(),
+                ));
+            };
+
+            let (repository, tag, digest) = if let Some((repo, digest)) = path.split_once('@') {
+                (repo, None, Some(digest.to_owned()))
+            } else if let Some((repo, tag)) = path.rsplit_once(':') {
+                (repo, Some(tag.to_owned()), None)
+            } else {
+                (path, None, None)
+            };
 
-                Ok(PluginLocator::Wapm(WapmLocator {
-                    file_prefix: create_wasm_file_prefix(extract_suffix_from_slug(&package_name)),
-                    package_name,
-                    version,
-                }))
+            if registry.is_empty() || repository.is_empty() {
+                return Err(WarpgateError::Serde(
+                    "OCI locator requires a registry and repository (registry/org/name).".into(),
+                ));
             }
-            unknown => Err(WarpgateError::Serde(format!(
-                "Unknown plugin scope `{unknown}`."
-            ))),
+
+            if digest.as_ref().is_some_and(|d| !d.starts_with("sha256:")) {
+                return Err(WarpgateError::Serde(
+                    "OCI locator digest must be a SHA256 digest (@sha256:...).".into(),
+                ));
+            }
+
+            Ok(PluginLocator::Oci(OciLocator {
+                registry: registry.to_owned(),
+                repository: repository.to_owned(),
+                tag,
+                digest,
+                integrity: None,
+            }))
         }
+        unknown => Err(WarpgateError::Serde(format!(
+            "Unknown plugin scope `{unknown}`."
+        ))),
     }
 }
 
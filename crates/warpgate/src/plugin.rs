@@ -2,27 +2,43 @@ use crate::endpoints::Empty;
 use crate::error::WarpgateError;
 use crate::helpers::{from_virtual_path, to_virtual_path};
 use crate::id::Id;
-use extism::{Function, Manifest, Plugin};
+use extism::{manifest::Wasm, Function, Manifest, Plugin};
+use once_cell::sync::OnceCell;
 use once_map::OnceMap;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use starbase_styles::color;
-use std::collections::BTreeMap;
+use starbase_utils::fs;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::sync::RwLock;
+use std::time::UNIX_EPOCH;
 use tracing::trace;
 use warpgate_api::VirtualPath;
 
 /// A container around Extism's [`Plugin`] and [`Manifest`] types that provides convenience
 /// methods for calling and caching functions from the WASM plugin. It also provides
 /// additional methods for easily working with WASI and virtual paths.
+///
+/// The underlying plugin instance is created on first use, so that results read
+/// from the cache do not pay the cost of compiling the WASM module.
 pub struct PluginContainer<'plugin> {
     pub id: Id,
     pub manifest: Manifest,
 
+    exports: OnceCell<Option<HashSet<String>>>,
     func_cache: OnceMap<String, Vec<u8>>,
-    plugin: Arc<RwLock<Plugin<'plugin>>>,
+    functions: Vec<Function>,
+    persistent_cache: Option<PersistentCache>,
+    plugin: OnceCell<RwLock<Plugin<'plugin>>>,
+}
+
+/// Function results that are persisted to the file system.
+struct PersistentCache {
+    dir: PathBuf,
+    funcs: HashSet<String>,
 }
 
 unsafe impl<'plugin> Send for PluginContainer<'plugin> {}
@@ -35,14 +51,14 @@ impl<'plugin> PluginContainer<'plugin> {
         manifest: Manifest,
         functions: impl IntoIterator<Item = Function>,
     ) -> miette::Result<PluginContainer<'new>> {
-        let plugin = Plugin::create_with_manifest(&manifest, functions, true)
-            .map_err(|error| WarpgateError::PluginCreateFailed { error })?;
-
         Ok(PluginContainer {
             manifest,
-            plugin: Arc::new(RwLock::new(plugin)),
+            plugin: OnceCell::new(),
             id,
+            exports: OnceCell::new(),
             func_cache: OnceMap::new(),
+            functions: functions.into_iter().collect(),
+            persistent_cache: None,
         })
     }
 
@@ -54,8 +70,89 @@ impl<'plugin> PluginContainer<'plugin> {
         Self::new(id, manifest, [])
     }
 
+    /// Persist the results of the provided functions, when called with
+    /// [`PluginContainer::cache_func_with`], to the cache directory, so that they
+    /// can be shared across processes. Results are keyed by a hash of the plugin's
+    /// WASM file paths and modified times, and the provided seed (for example,
+    /// the host's version), and are removed when either changes.
+    ///
+    /// Each result is keyed by the function's input and the plugin configuration,
+    /// so the provided functions must be pure. Results that depend on environment
+    /// variables or files read through host functions will not be invalidated.
+    pub fn enable_persistent_cache<F, S>(
+        &mut self,
+        cache_dir: &Path,
+        seed: &str,
+        funcs: F,
+    ) -> miette::Result<()>
+    where
+        F: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut sha = Sha256::new();
+        sha.update(seed);
+
+        for wasm in &self.manifest.wasm {
+            match wasm {
+                // Downloaded plugins are replaced when refreshed, so the path
+                // and modified time identify them without reading the file
+                Wasm::File { path, .. } => {
+                    let modified = fs::metadata(path)?
+                        .modified()
+                        .ok()
+                        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                        .map(|duration| duration.as_nanos())
+                        .unwrap_or_default();
+
+                    sha.update(path.to_string_lossy().as_bytes());
+                    sha.update(modified.to_le_bytes());
+                }
+                Wasm::Data { data, .. } => {
+                    sha.update(data);
+                }
+                Wasm::Url { req, .. } => {
+                    sha.update(&req.url);
+                }
+            };
+        }
+
+        let fingerprint = format!("{:x}", sha.finalize());
+        let plugin_dir = cache_dir.join(self.id.as_str());
+
+        // Remove results from previous plugin files or seeds. This is best-effort,
+        // as another process may be pruning or writing to the same directory.
+        if let Ok(entries) = fs::read_dir(&plugin_dir) {
+            for entry in entries {
+                if entry.file_name() != fingerprint.as_str() {
+                    let _ = fs::remove_dir_all(entry.path());
+                }
+            }
+        }
+
+        trace!(
+            plugin = self.id.as_str(),
+            dir = ?plugin_dir.join(&fingerprint),
+            "Enabled persistent cache for plugin functions",
+        );
+
+        self.persistent_cache = Some(PersistentCache {
+            dir: plugin_dir.join(fingerprint),
+            funcs: funcs
+                .into_iter()
+                .map(|func| func.as_ref().to_owned())
+                .collect(),
+        });
+
+        Ok(())
+    }
+
     /// Reload the plugin's configuration from the manifest.
     pub fn reload_config(&mut self) -> miette::Result<()> {
+        // The instance will be created with the current configuration
+        let Some(plugin) = self.plugin.get_mut() else {
+            return Ok(());
+        };
+
         let config = self
             .manifest
             .config
@@ -63,8 +160,8 @@ impl<'plugin> PluginContainer<'plugin> {
             .map(|(k, v)| (k.to_owned(), Some(v.to_owned())))
             .collect::<BTreeMap<_, _>>();
 
-        self.plugin
-            .write()
+        plugin
+            .get_mut()
             .expect("Failed to acquire write access!")
             .set_config(&config)
             .unwrap();
@@ -98,10 +195,41 @@ impl<'plugin> PluginContainer<'plugin> {
             }
         }
 
+        let persistent_path = self.get_persistent_cache_path(func, &input);
+
+        // Then check if it's been persisted by another process
+        if let Some(path) = &persistent_path {
+            if let Ok(data) = fs::read_file_bytes(path) {
+                if let Ok(output) = self.parse_output(func, &data) {
+                    trace!(
+                        plugin = self.id.as_str(),
+                        cache = ?path,
+                        "Read plugin function {} from persistent cache",
+                        color::label(func),
+                    );
+
+                    self.func_cache.insert(cache_key, |_| data);
+
+                    return Ok(output);
+                }
+            }
+        }
+
         // Otherwise call the function and cache the result
         let data = self.call(func, input)?;
         let output: O = self.parse_output(func, &data)?;
 
+        if let Some(path) = &persistent_path {
+            if let Err(error) = fs::write_file(path, &data) {
+                trace!(
+                    plugin = self.id.as_str(),
+                    cache = ?path,
+                    "Failed to persist plugin function {}: {error}",
+                    color::label(func),
+                );
+            }
+        }
+
         self.func_cache.insert(cache_key, |_| data);
 
         Ok(output)
@@ -133,9 +261,15 @@ impl<'plugin> PluginContainer<'plugin> {
         Ok(())
     }
 
-    /// Return true if the plugin has a function with the given id.
-    pub fn has_func(&self, func: &str) -> bool {
-        self.plugin
+    /// Return true if the plugin has a function with the given id. The function
+    /// is looked up in the module's exports, without creating an instance.
+    pub fn has_func(&self, func: &str) -> miette::Result<bool> {
+        if let Some(exports) = self.get_exports() {
+            return Ok(exports.contains(func));
+        }
+
+        Ok(self
+            .get_instance()?
             .read()
             .unwrap_or_else(|_| {
                 panic!(
@@ -143,7 +277,7 @@ impl<'plugin> PluginContainer<'plugin> {
                     self.id
                 )
             })
-            .has_function(func)
+            .has_function(func))
     }
 
     /// Convert the provided virtual guest path to an absolute host path.
@@ -176,7 +310,7 @@ impl<'plugin> PluginContainer<'plugin> {
             color::label(func),
         );
 
-        let mut instance = self.plugin.write().unwrap_or_else(|_| {
+        let mut instance = self.get_instance()?.write().unwrap_or_else(|_| {
             panic!(
                 "Unable to acquire write access to `{}` WASM plugin.",
                 self.id
@@ -184,6 +318,10 @@ impl<'plugin> PluginContainer<'plugin> {
         });
 
         let output = instance.call(func, input).map_err(|error| {
+            if let Some(limit_error) = self.check_limits(func, &error) {
+                return limit_error;
+            }
+
             // When in debug mode, include more information around errors.
             #[cfg(debug_assertions)]
             {
@@ -215,6 +353,97 @@ impl<'plugin> PluginContainer<'plugin> {
         Ok(output.to_vec())
     }
 
+    // Read the exported functions from the module's export section. Returns nothing
+    // if the module can't be read or parsed, or if the manifest has multiple
+    // modules, in which case the instance is used instead.
+    fn get_exports(&self) -> Option<&HashSet<String>> {
+        self.exports
+            .get_or_init(|| {
+                let bytes = match self.manifest.wasm.as_slice() {
+                    [Wasm::File { path, .. }] => fs::read_file_bytes(path).ok()?,
+                    [Wasm::Data { data, .. }] => data.to_owned(),
+                    _ => return None,
+                };
+
+                let mut exports = HashSet::new();
+
+                for payload in wasmparser::Parser::new(0).parse_all(&bytes) {
+                    if let wasmparser::Payload::ExportSection(reader) = payload.ok()? {
+                        for export in reader {
+                            let export = export.ok()?;
+
+                            if export.kind == wasmparser::ExternalKind::Func {
+                                exports.insert(export.name.to_owned());
+                            }
+                        }
+                    }
+                }
+
+                Some(exports)
+            })
+            .as_ref()
+    }
+
+    fn get_instance(&self) -> miette::Result<&RwLock<Plugin<'plugin>>> {
+        self.plugin.get_or_try_init(|| {
+            trace!(plugin = self.id.as_str(), "Creating plugin instance");
+
+            let plugin = Plugin::create_with_manifest(&self.manifest, self.functions.clone(), true)
+                .map_err(|error| WarpgateError::PluginCreateFailed { error })?;
+
+            Ok(RwLock::new(plugin))
+        })
+    }
+
+    fn get_persistent_cache_path(&self, func: &str, input: &str) -> Option<PathBuf> {
+        let cache = self.persistent_cache.as_ref()?;
+
+        if !cache.funcs.contains(func) {
+            return None;
+        }
+
+        // Configuration can change the output of a function
+        let mut sha = Sha256::new();
+        sha.update(input);
+        sha.update(serde_json::to_string(&self.manifest.config).unwrap_or_default());
+
+        Some(cache.dir.join(format!("{func}-{:x}.json", sha.finalize())))
+    }
+
+    // Extism only reports limits through the error message, so detect
+    // them and return an error that names the plugin and function.
+    fn check_limits(&self, func: &str, error: &extism::Error) -> Option<WarpgateError> {
+        let message = error.to_string();
+
+        if let Some(timeout) = self.manifest.timeout_ms {
+            if message.trim_matches('"') == "timeout" {
+                return Some(WarpgateError::PluginCallTimeout {
+                    id: self.id.to_string(),
+                    func: func.to_owned(),
+                    timeout,
+                });
+            }
+        }
+
+        // Extism denies growing memory past the limit without an error, so detect
+        // when the host fails to allocate, or when the allocator in a Rust plugin
+        // aborts through its allocation error handler (requires debug names)
+        if let Some(max_pages) = self.manifest.memory.max_pages {
+            if message.contains("out of memory")
+                || message.contains("handle_alloc_error")
+                || message.contains("rust_oom")
+            {
+                return Some(WarpgateError::PluginMemoryLimitExceeded {
+                    id: self.id.to_string(),
+                    func: func.to_owned(),
+                    max_pages,
+                });
+            }
+        }
+
+        None
+    }
+
     fn format_input<I: Serialize>(&self, func: &str, input: I) -> miette::Result<String> {
         Ok(
             serde_json::to_string(&input).map_err(|error| WarpgateError::FormatInputFailed {
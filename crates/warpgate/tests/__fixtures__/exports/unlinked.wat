@@ -0,0 +1,6 @@
+;; Imports a host function that doesn't exist, so it can't be instantiated
+(module
+  (import "env" "missing" (func $missing))
+  (memory (export "memory") 1)
+  (func (export "exported")
+    call $missing))
@@ -0,0 +1,10 @@
+;; Grows memory by 512 pages, and like the allocator in Rust plugins,
+;; aborts through the allocation error handler when growing fails
+(module
+  (memory (export "memory") 1 1024)
+  (func $alloc::alloc::handle_alloc_error
+    unreachable)
+  (func (export "grow") (result i32)
+    (if (i32.lt_s (memory.grow (i32.const 512)) (i32.const 0))
+      (then call $alloc::alloc::handle_alloc_error))
+    (i32.const 0)))
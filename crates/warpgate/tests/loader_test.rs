@@ -1,4 +1,5 @@
 use starbase_sandbox::{create_empty_sandbox, locate_fixture, Sandbox};
+use starbase_utils::fs;
 use std::path::PathBuf;
 use warpgate::{GitHubLocator, Id, PluginLoader, PluginLocator};
 
@@ -26,6 +27,7 @@ mod loader {
                     PluginLocator::SourceFile {
                         file: "".into(),
                         path: PathBuf::from("fake-file"),
+                        integrity: None,
                     },
                 )
                 .await
@@ -43,6 +45,7 @@ mod loader {
                     PluginLocator::SourceFile {
                         file: "".into(),
                         path: fixture.join("test.wasm"),
+                        integrity: None,
                     },
                 )
                 .await
@@ -57,6 +60,204 @@ mod loader {
         }
     }
 
+    mod integrity {
+        use super::*;
+
+        // SHA256 of the empty test.wasm fixture
+        const DIGEST: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+        #[tokio::test]
+        async fn passes_matching_digest() {
+            let (_sandbox, loader) = create_loader();
+
+            loader
+                .load_plugin(
+                    Id::raw("test"),
+                    PluginLocator::SourceFile {
+                        file: "".into(),
+                        path: locate_fixture("loader").join("test.wasm"),
+                        integrity: Some(DIGEST.into()),
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "does not match its integrity digest")]
+        async fn errors_mismatched_digest() {
+            let (_sandbox, loader) = create_loader();
+
+            loader
+                .load_plugin(
+                    Id::raw("test"),
+                    PluginLocator::SourceFile {
+                        file: "".into(),
+                        path: locate_fixture("loader").join("test.wasm"),
+                        integrity: Some("0".repeat(64)),
+                    },
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    mod signing {
+        use super::*;
+
+        const TRUSTED_KEY: &str = "RWRIbpsKiAmxTifqozUd/QOJkLoXAnSgQKz98H0pWDY1ntjM5Mt7d0oe";
+        const OTHER_KEY: &str = "RWSIHoorubPSc6++OX+/hM64XbMSxz3jZSXEhPfTN/2zesFZ+kVS/HH1";
+
+        fn create_locator(path: PathBuf) -> PluginLocator {
+            PluginLocator::SourceFile {
+                file: "".into(),
+                path,
+                integrity: None,
+            }
+        }
+
+        #[tokio::test]
+        async fn verifies_trusted_signature() {
+            let (_sandbox, mut loader) = create_loader();
+            loader.set_signing_options(&[OTHER_KEY.into(), TRUSTED_KEY.into()], true);
+
+            loader
+                .load_plugin(
+                    Id::raw("test"),
+                    create_locator(locate_fixture("signing").join("plugin.wasm")),
+                )
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "plugin::signature::invalid")]
+        async fn errors_untrusted_key() {
+            let (_sandbox, mut loader) = create_loader();
+            loader.set_signing_options(&[OTHER_KEY.into()], false);
+
+            loader
+                .load_plugin(
+                    Id::raw("test"),
+                    create_locator(locate_fixture("signing").join("plugin.wasm")),
+                )
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "does not match its signature")]
+        async fn errors_modified_file() {
+            let (sandbox, mut loader) = create_loader();
+            loader.set_signing_options(&[TRUSTED_KEY.into()], false);
+
+            let fixture = locate_fixture("signing");
+
+            sandbox.create_file("plugin.wasm", "modified");
+            sandbox.create_file(
+                "plugin.wasm.minisig",
+                fs::read_file(fixture.join("plugin.wasm.minisig")).unwrap(),
+            );
+
+            loader
+                .load_plugin(
+                    Id::raw("test"),
+                    create_locator(sandbox.path().join("plugin.wasm")),
+                )
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        async fn allows_unsigned_when_not_required() {
+            let (_sandbox, mut loader) = create_loader();
+            loader.set_signing_options(&[TRUSTED_KEY.into()], false);
+
+            loader
+                .load_plugin(
+                    Id::raw("test"),
+                    create_locator(locate_fixture("loader").join("test.wasm")),
+                )
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        async fn allows_unsigned_local_files_when_required() {
+            let (_sandbox, mut loader) = create_loader();
+            loader.set_signing_options(&[TRUSTED_KEY.into()], true);
+
+            loader
+                .load_plugin(
+                    Id::raw("test"),
+                    create_locator(locate_fixture("loader").join("test.wasm")),
+                )
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "plugin::signature::missing")]
+        async fn errors_unsigned_cached_file_when_required() {
+            let (_sandbox, mut loader) = create_loader();
+            loader.set_signing_options(&[TRUSTED_KEY.into()], true);
+
+            let id = Id::raw("test");
+            let url = "https://example.com/plugin.wasm";
+
+            fs::copy_file(
+                locate_fixture("loader").join("test.wasm"),
+                loader.create_cache_path(&id, url, false),
+            )
+            .unwrap();
+
+            loader
+                .load_plugin(
+                    id,
+                    PluginLocator::SourceUrl {
+                        url: url.into(),
+                        integrity: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        #[test]
+        fn changes_cache_path_with_signing_options() {
+            let (_sandbox, mut loader) = create_loader();
+            let id = Id::raw("test");
+            let url = "https://example.com/plugin.wasm";
+
+            let unsigned = loader.create_cache_path(&id, url, false);
+
+            loader.set_signing_options(&[TRUSTED_KEY.into()], false);
+            let trusted = loader.create_cache_path(&id, url, false);
+
+            loader.set_signing_options(&[TRUSTED_KEY.into()], true);
+            let required = loader.create_cache_path(&id, url, false);
+
+            assert_ne!(unsigned, trusted);
+            assert_ne!(unsigned, required);
+            assert_ne!(trusted, required);
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "plugin::signature::no_keys")]
+        async fn errors_required_without_keys() {
+            let (_sandbox, mut loader) = create_loader();
+            loader.set_signing_options(&[], true);
+
+            loader
+                .load_plugin(
+                    Id::raw("test"),
+                    create_locator(locate_fixture("signing").join("plugin.wasm")),
+                )
+                .await
+                .unwrap();
+        }
+    }
+
     mod source_url {
         use super::*;
 
@@ -68,7 +269,7 @@ mod loader {
             loader
                 .load_plugin(
                     Id::raw("test"),
-                    PluginLocator::SourceUrl { url: "https://github.com/moonrepo/deno-plugin/releases/download/v0.0.2/deno_plugin_invalid_name.wasm".into() },
+                    PluginLocator::SourceUrl { url: "https://github.com/moonrepo/deno-plugin/releases/download/v0.0.2/deno_plugin_invalid_name.wasm".into(), integrity: None },
                 )
                 .await
                 .unwrap();
@@ -81,7 +282,7 @@ mod loader {
             let path = loader
                 .load_plugin(
                     Id::raw("test"),
-                    PluginLocator::SourceUrl { url: "https://github.com/moonrepo/deno-plugin/releases/download/v0.0.2/deno_plugin.wasm".into() },
+                    PluginLocator::SourceUrl { url: "https://github.com/moonrepo/deno-plugin/releases/download/v0.0.2/deno_plugin.wasm".into(), integrity: None },
                 )
                 .await
                 .unwrap();
@@ -96,7 +297,7 @@ mod loader {
             let path = loader
                 .load_plugin(
                     Id::raw("test"),
-                    PluginLocator::SourceUrl { url: "https://github.com/moonrepo/deno-plugin/releases/latest/download/deno_plugin.wasm".into() },
+                    PluginLocator::SourceUrl { url: "https://github.com/moonrepo/deno-plugin/releases/latest/download/deno_plugin.wasm".into(), integrity: None },
                 )
                 .await
                 .unwrap();
@@ -120,6 +321,7 @@ mod loader {
                         file_prefix: "bun_plugin.wasm".into(),
                         repo_slug: "moonrepo/invalid-repo".into(),
                         tag: None,
+                        integrity: None,
                     }),
                 )
                 .await
@@ -137,6 +339,7 @@ mod loader {
                         file_prefix: "bun_plugin.wasm".into(),
                         repo_slug: "moonrepo/bun-plugin".into(),
                         tag: Some("v0.0.3".into()),
+                        integrity: None,
                     }),
                 )
                 .await
@@ -156,6 +359,7 @@ mod loader {
                         file_prefix: "bun_plugin.wasm".into(),
                         repo_slug: "moonrepo/bun-plugin".into(),
                         tag: None,
+                        integrity: None,
                     }),
                 )
                 .await
@@ -164,4 +368,388 @@ mod loader {
             assert_eq!(path, sandbox.path().join("plugins/test-latest-fbd480065d33e0cb2cc9501b7f20fb7edd1a552f1c629dd8b35071f5bac4a0cb.wasm"));
         }
     }
+
+    mod oci {
+        use super::*;
+        use sha2::{Digest, Sha256};
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::thread;
+        use std::time::Duration;
+        use warpgate::get_oci_registry_token_var;
+
+        fn start_registry(wasm: Vec<u8>, require_auth: bool) -> String {
+            start_registry_with_token_count(wasm, None, require_auth).0
+        }
+
+        // A minimal OCI distribution registry that serves a single artifact,
+        // with an optional signature layer, and counts how many anonymous
+        // tokens were requested
+        fn start_registry_with_token_count(
+            wasm: Vec<u8>,
+            signature: Option<Vec<u8>>,
+            require_auth: bool,
+        ) -> (String, Arc<AtomicUsize>) {
+            let token_requests = Arc::new(AtomicUsize::new(0));
+            let token_requests_clone = Arc::clone(&token_requests);
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let host = listener.local_addr().unwrap().to_string();
+            let realm = format!("http://{host}/token");
+            let digest = format!("sha256:{:x}", Sha256::digest(&wasm));
+            let signature = signature.unwrap_or_default();
+            let signature_digest = format!("sha256:{:x}", Sha256::digest(&signature));
+            let signature_layer = if signature.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    r#",{{"mediaType":"application/vnd.minisign.signature","digest":"{signature_digest}","size":{}}}"#,
+                    signature.len()
+                )
+            };
+
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = stream.unwrap();
+                    let mut buffer = [0; 4096];
+                    let size = stream.read(&mut buffer).unwrap();
+                    let request = String::from_utf8_lossy(&buffer[0..size]).to_lowercase();
+                    let path = request.split(' ').nth(1).unwrap_or_default().to_owned();
+                    let authorized = request.contains("authorization: bearer secret");
+
+                    let (status, headers, body): (&str, String, Vec<u8>) = if path
+                        .starts_with("/token")
+                    {
+                        token_requests_clone.fetch_add(1, Ordering::SeqCst);
+
+                        ("200 OK", String::new(), br#"{"token":"secret"}"#.to_vec())
+                    } else if require_auth && !authorized {
+                        (
+                                "401 Unauthorized",
+                                format!(
+                                    "WWW-Authenticate: Bearer realm=\"{realm}\",service=\"test\",scope=\"repository:org/plugin:pull\"\r\n"
+                                ),
+                                vec![],
+                            )
+                    } else if path.starts_with("/v2/org/plugin/manifests/") {
+                        (
+                                "200 OK",
+                                String::new(),
+                                format!(
+                                    r#"{{"schemaVersion":2,"layers":[{{"mediaType":"application/vnd.wasm.content.layer.v1+wasm","digest":"{digest}","size":{}}}{signature_layer}]}}"#,
+                                    wasm.len()
+                                )
+                                .into_bytes(),
+                            )
+                    } else if path == format!("/v2/org/plugin/blobs/{digest}") {
+                        ("200 OK", String::new(), wasm.clone())
+                    } else if !signature.is_empty()
+                        && path == format!("/v2/org/plugin/blobs/{signature_digest}")
+                    {
+                        ("200 OK", String::new(), signature.clone())
+                    } else {
+                        ("404 Not Found", String::new(), vec![])
+                    };
+
+                    let head = format!(
+                        "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n",
+                        body.len()
+                    );
+
+                    stream.write_all(head.as_bytes()).unwrap();
+                    stream.write_all(&body).unwrap();
+                }
+            });
+
+            (host, token_requests)
+        }
+
+        #[tokio::test]
+        async fn downloads_to_plugins() {
+            let (sandbox, loader) = create_loader();
+            let wasm = std::fs::read(locate_fixture("loader").join("test.wasm")).unwrap();
+            let host = start_registry(wasm.clone(), false);
+
+            let path = loader
+                .load_plugin(
+                    Id::raw("test"),
+                    PluginLocator::try_from(format!("oci:{host}/org/plugin:v1.0.0")).unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert!(path.starts_with(sandbox.path().join("plugins")));
+            assert!(!fs::file_name(&path).contains("-latest-"));
+            assert_eq!(std::fs::read(path).unwrap(), wasm);
+        }
+
+        #[tokio::test]
+        async fn verifies_manifest_digest() {
+            let (_sandbox, loader) = create_loader();
+            let wasm = std::fs::read(locate_fixture("loader").join("test.wasm")).unwrap();
+            let host = start_registry(wasm.clone(), false);
+
+            let manifest = reqwest::get(format!("http://{host}/v2/org/plugin/manifests/v1.0.0"))
+                .await
+                .unwrap()
+                .bytes()
+                .await
+                .unwrap();
+
+            let path = loader
+                .load_plugin(
+                    Id::raw("test"),
+                    PluginLocator::try_from(format!(
+                        "oci:{host}/org/plugin@sha256:{:x}",
+                        Sha256::digest(&manifest)
+                    ))
+                    .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(std::fs::read(path).unwrap(), wasm);
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "plugin::oci::digest_mismatch")]
+        async fn errors_for_manifest_digest_mismatch() {
+            let (_sandbox, loader) = create_loader();
+            let wasm = std::fs::read(locate_fixture("loader").join("test.wasm")).unwrap();
+            let host = start_registry(wasm, false);
+
+            loader
+                .load_plugin(
+                    Id::raw("test"),
+                    PluginLocator::try_from(format!(
+                        "oci:{host}/org/plugin@sha256:{}",
+                        "0".repeat(64)
+                    ))
+                    .unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        async fn supports_latest() {
+            let (_sandbox, loader) = create_loader();
+            let wasm = std::fs::read(locate_fixture("loader").join("test.wasm")).unwrap();
+            let host = start_registry(wasm, false);
+
+            let path = loader
+                .load_plugin(
+                    Id::raw("test"),
+                    PluginLocator::try_from(format!("oci:{host}/org/plugin")).unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert!(fs::file_name(path).contains("-latest-"));
+        }
+
+        #[tokio::test]
+        async fn requests_anonymous_token() {
+            let (_sandbox, loader) = create_loader();
+            let wasm = std::fs::read(locate_fixture("loader").join("test.wasm")).unwrap();
+            let host = start_registry(wasm.clone(), true);
+
+            let path = loader
+                .load_plugin(
+                    Id::raw("test"),
+                    PluginLocator::try_from(format!("oci:{host}/org/plugin:v1.0.0")).unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(std::fs::read(path).unwrap(), wasm);
+        }
+
+        #[tokio::test]
+        async fn uses_token_configured_for_registry() {
+            let (_sandbox, loader) = create_loader();
+            let wasm = b"\0asm".to_vec();
+            let (host, token_requests) = start_registry_with_token_count(wasm.clone(), None, true);
+
+            std::env::set_var(get_oci_registry_token_var(&host), "secret");
+
+            let path = loader
+                .load_plugin(
+                    Id::raw("test"),
+                    PluginLocator::try_from(format!("oci:{host}/org/plugin:v1.0.0")).unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(std::fs::read(path).unwrap(), wasm);
+            assert_eq!(token_requests.load(Ordering::SeqCst), 0);
+        }
+
+        #[tokio::test]
+        async fn doesnt_send_token_to_other_registries() {
+            let (_sandbox, loader) = create_loader();
+            let wasm = b"\0asm".to_vec();
+            let (host, token_requests) = start_registry_with_token_count(wasm.clone(), None, true);
+
+            std::env::set_var(get_oci_registry_token_var("ghcr.io"), "secret");
+
+            loader
+                .load_plugin(
+                    Id::raw("test"),
+                    PluginLocator::try_from(format!("oci:{host}/org/plugin:v1.0.0")).unwrap(),
+                )
+                .await
+                .unwrap();
+
+            // The configured token was not sent, so one was requested anonymously
+            assert_eq!(token_requests.load(Ordering::SeqCst), 1);
+        }
+
+        #[test]
+        fn formats_token_var_from_host() {
+            assert_eq!(
+                get_oci_registry_token_var("ghcr.io"),
+                "OCI_REGISTRY_TOKEN_GHCR_IO"
+            );
+            assert_eq!(
+                get_oci_registry_token_var("localhost:5000"),
+                "OCI_REGISTRY_TOKEN_LOCALHOST_5000"
+            );
+        }
+
+        #[tokio::test]
+        async fn verifies_signature_layer() {
+            let (_sandbox, mut loader) = create_loader();
+            let fixture = locate_fixture("signing");
+            let wasm = std::fs::read(fixture.join("plugin.wasm")).unwrap();
+            let signature = std::fs::read(fixture.join("plugin.wasm.minisig")).unwrap();
+            let (host, _) = start_registry_with_token_count(wasm.clone(), Some(signature), false);
+
+            loader.set_signing_options(
+                &["RWRIbpsKiAmxTifqozUd/QOJkLoXAnSgQKz98H0pWDY1ntjM5Mt7d0oe".into()],
+                true,
+            );
+
+            let path = loader
+                .load_plugin(
+                    Id::raw("test"),
+                    PluginLocator::try_from(format!("oci:{host}/org/plugin:v1.0.0")).unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(std::fs::read(&path).unwrap(), wasm);
+            assert!(loader.get_signature_path(&path).exists());
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "plugin::signature::missing")]
+        async fn errors_without_signature_layer_when_required() {
+            let (_sandbox, mut loader) = create_loader();
+            let wasm = std::fs::read(locate_fixture("signing").join("plugin.wasm")).unwrap();
+            let host = start_registry(wasm, false);
+
+            loader.set_signing_options(
+                &["RWRIbpsKiAmxTifqozUd/QOJkLoXAnSgQKz98H0pWDY1ntjM5Mt7d0oe".into()],
+                true,
+            );
+
+            loader
+                .load_plugin(
+                    Id::raw("test"),
+                    PluginLocator::try_from(format!("oci:{host}/org/plugin:v1.0.0")).unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        async fn verifies_integrity_when_cached() {
+            let (_sandbox, loader) = create_loader();
+            let wasm = b"\0asm".to_vec();
+            let host = start_registry(wasm.clone(), false);
+            let locator = PluginLocator::try_from(format!(
+                "oci:{host}/org/plugin:v1.0.0#sha256={:x}",
+                Sha256::digest(&wasm)
+            ))
+            .unwrap();
+
+            let path = loader.load_plugin(Id::raw("test"), &locator).await.unwrap();
+
+            // Tamper with the cached file
+            fs::write_file(&path, "changed").unwrap();
+
+            let error = loader
+                .load_plugin(Id::raw("test"), &locator)
+                .await
+                .unwrap_err();
+
+            assert!(error
+                .to_string()
+                .contains("does not match its integrity digest"));
+            assert!(!path.exists());
+        }
+
+        #[tokio::test]
+        async fn redownloads_when_stale() {
+            let (_sandbox, mut loader) = create_loader();
+            let wasm = b"\0asm".to_vec();
+            let host = start_registry(wasm.clone(), false);
+            let locator = PluginLocator::try_from(format!("oci:{host}/org/plugin:v1.0.0")).unwrap();
+
+            let path = loader.load_plugin(Id::raw("test"), &locator).await.unwrap();
+
+            fs::write_file(&path, "changed").unwrap();
+
+            // Cached forever
+            loader.set_cache_ttls(None, None);
+            loader.load_plugin(Id::raw("test"), &locator).await.unwrap();
+
+            assert_eq!(std::fs::read(&path).unwrap(), b"changed");
+
+            // Always stale
+            loader.set_cache_ttls(Some(Duration::ZERO), Some(Duration::ZERO));
+            loader.load_plugin(Id::raw("test"), &locator).await.unwrap();
+
+            assert_eq!(std::fs::read(&path).unwrap(), wasm);
+        }
+
+        #[tokio::test]
+        async fn refreshes_cached_plugin() {
+            let (_sandbox, mut loader) = create_loader();
+            let wasm = b"\0asm".to_vec();
+            let host = start_registry(wasm.clone(), false);
+            let locator = PluginLocator::try_from(format!("oci:{host}/org/plugin:v1.0.0")).unwrap();
+
+            loader.set_cache_ttls(None, None);
+
+            let path = loader.load_plugin(Id::raw("test"), &locator).await.unwrap();
+
+            fs::write_file(&path, "changed").unwrap();
+
+            let refreshed_path = loader
+                .refresh_plugin(Id::raw("test"), &locator)
+                .await
+                .unwrap();
+
+            assert_eq!(refreshed_path, path);
+            assert_eq!(std::fs::read(&path).unwrap(), wasm);
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "does not exist")]
+        async fn errors_missing_repository() {
+            let (_sandbox, loader) = create_loader();
+            let host = start_registry(vec![], false);
+
+            loader
+                .load_plugin(
+                    Id::raw("test"),
+                    PluginLocator::try_from(format!("oci:{host}/org/unknown:v1.0.0")).unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+    }
 }
@@ -1,5 +1,5 @@
 use std::path::PathBuf;
-use warpgate::{GitHubLocator, PluginLocator, WapmLocator};
+use warpgate::{GitHubLocator, OciLocator, PluginLocator, WapmLocator};
 
 mod locator {
     use super::*;
@@ -10,6 +10,7 @@ mod locator {
             PluginLocator::SourceFile {
                 file: "foo.wasm".into(),
                 path: PathBuf::from("/abs/foo.wasm"),
+                integrity: None,
             }
             .to_string(),
             "source:foo.wasm"
@@ -17,7 +18,8 @@ mod locator {
 
         assert_eq!(
             PluginLocator::SourceUrl {
-                url: "https://download.com/bar.wasm".into()
+                url: "https://download.com/bar.wasm".into(),
+                integrity: None,
             }
             .to_string(),
             "source:https://download.com/bar.wasm"
@@ -28,6 +30,7 @@ mod locator {
                 file_prefix: "proto_plugin".into(),
                 repo_slug: "moonrepo/proto".into(),
                 tag: None,
+                integrity: None,
             })
             .to_string(),
             "github:moonrepo/proto"
@@ -38,6 +41,7 @@ mod locator {
                 file_prefix: "proto_plugin".into(),
                 repo_slug: "moonrepo/proto".into(),
                 tag: Some("latest".into()),
+                integrity: None,
             })
             .to_string(),
             "github:moonrepo/proto@latest"
@@ -48,6 +52,7 @@ mod locator {
                 file_prefix: "proto_plugin".into(),
                 package_name: "moonrepo/proto".into(),
                 version: None,
+                integrity: None,
             })
             .to_string(),
             "wapm:moonrepo/proto"
@@ -58,6 +63,7 @@ mod locator {
                 file_prefix: "proto_plugin".into(),
                 package_name: "moonrepo/proto".into(),
                 version: Some("1.2.3".into()),
+                integrity: None,
             })
             .to_string(),
             "wapm:moonrepo/proto@1.2.3"
@@ -108,7 +114,8 @@ mod locator {
             assert_eq!(
                 PluginLocator::try_from("source:https://domain.com/file.wasm".to_string()).unwrap(),
                 PluginLocator::SourceUrl {
-                    url: "https://domain.com/file.wasm".into()
+                    url: "https://domain.com/file.wasm".into(),
+                    integrity: None,
                 }
             );
         }
@@ -120,6 +127,7 @@ mod locator {
                 PluginLocator::SourceFile {
                     file: "file.wasm".into(),
                     path: PathBuf::from("file.wasm"),
+                    integrity: None,
                 }
             );
         }
@@ -131,6 +139,7 @@ mod locator {
                 PluginLocator::SourceFile {
                     file: "../file.wasm".into(),
                     path: PathBuf::from("../file.wasm"),
+                    integrity: None,
                 }
             );
             assert_eq!(
@@ -138,11 +147,150 @@ mod locator {
                 PluginLocator::SourceFile {
                     file: "./file.wasm".into(),
                     path: PathBuf::from("./file.wasm"),
+                    integrity: None,
                 }
             );
         }
     }
 
+    mod integrity {
+        use super::*;
+
+        const DIGEST: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+        #[derive(serde::Deserialize)]
+        struct Wrapper {
+            plugin: PluginLocator,
+        }
+
+        #[test]
+        #[should_panic(expected = "Plugin integrity must be a SHA256 digest (sha256=...).")]
+        fn errors_unknown_algorithm() {
+            PluginLocator::try_from(format!(
+                "source:https://domain.com/file.wasm#sha512={DIGEST}"
+            ))
+            .unwrap();
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "Plugin integrity must be a 64 character hex encoded SHA256 digest."
+        )]
+        fn errors_invalid_digest() {
+            PluginLocator::try_from("source:https://domain.com/file.wasm#sha256=xyz".to_string())
+                .unwrap();
+        }
+
+        #[test]
+        fn parses_url() {
+            assert_eq!(
+                PluginLocator::try_from(format!(
+                    "source:https://domain.com/file.wasm#sha256={}",
+                    DIGEST.to_uppercase()
+                ))
+                .unwrap(),
+                PluginLocator::SourceUrl {
+                    url: "https://domain.com/file.wasm".into(),
+                    integrity: Some(DIGEST.into()),
+                }
+            );
+        }
+
+        #[test]
+        fn ignores_hash_in_location() {
+            assert_eq!(
+                PluginLocator::try_from("source:https://domain.com/file.wasm#section".to_string())
+                    .unwrap(),
+                PluginLocator::SourceUrl {
+                    url: "https://domain.com/file.wasm#section".into(),
+                    integrity: None,
+                }
+            );
+
+            assert_eq!(
+                PluginLocator::try_from("source:./plugins/#1/file.wasm".to_string()).unwrap(),
+                PluginLocator::SourceFile {
+                    file: "./plugins/#1/file.wasm".into(),
+                    path: PathBuf::from("./plugins/#1/file.wasm"),
+                    integrity: None,
+                }
+            );
+        }
+
+        #[test]
+        fn parses_hash_in_location_with_digest() {
+            assert_eq!(
+                PluginLocator::try_from(format!(
+                    "source:https://domain.com/#v1/file.wasm#sha256={DIGEST}"
+                ))
+                .unwrap(),
+                PluginLocator::SourceUrl {
+                    url: "https://domain.com/#v1/file.wasm".into(),
+                    integrity: Some(DIGEST.into()),
+                }
+            );
+        }
+
+        #[test]
+        fn parses_github() {
+            assert_eq!(
+                PluginLocator::try_from(format!(
+                    "github:moonrepo/bun-plugin@v1.0.0#sha256={DIGEST}"
+                ))
+                .unwrap(),
+                PluginLocator::GitHub(GitHubLocator {
+                    file_prefix: "bun_plugin".into(),
+                    repo_slug: "moonrepo/bun-plugin".into(),
+                    tag: Some("v1.0.0".into()),
+                    integrity: Some(DIGEST.into()),
+                })
+            );
+        }
+
+        #[test]
+        fn parses_oci_digest() {
+            let locator = PluginLocator::try_from(format!(
+                "oci:ghcr.io/org/plugin@sha256:abc#sha256={DIGEST}"
+            ))
+            .unwrap();
+
+            assert_eq!(locator.get_integrity(), Some(DIGEST));
+
+            if let PluginLocator::Oci(oci) = locator {
+                assert_eq!(oci.digest.unwrap(), "sha256:abc");
+            }
+        }
+
+        #[test]
+        fn parses_table() {
+            let wrapper: Wrapper = serde_json::from_str(&format!(
+                r#"{{"plugin":{{"locator":"source:https://domain.com/file.wasm","integrity":"sha256={DIGEST}"}}}}"#
+            ))
+            .unwrap();
+
+            assert_eq!(wrapper.plugin.get_integrity(), Some(DIGEST));
+        }
+
+        #[test]
+        #[should_panic(expected = "Plugin integrity cannot be declared in both")]
+        fn errors_table_with_locator_integrity() {
+            serde_json::from_str::<Wrapper>(&format!(
+                r#"{{"plugin":{{"locator":"source:https://domain.com/file.wasm#sha256={DIGEST}","integrity":"sha256={DIGEST}"}}}}"#
+            ))
+            .unwrap();
+        }
+
+        #[test]
+        fn round_trips_display() {
+            let value = format!("wapm:moonrepo/plugin@1.0.0#sha256={DIGEST}");
+
+            assert_eq!(
+                PluginLocator::try_from(value.clone()).unwrap().to_string(),
+                value
+            );
+        }
+    }
+
     mod github {
         use super::*;
 
@@ -162,6 +310,7 @@ mod locator {
                     file_prefix: "bun_plugin".into(),
                     repo_slug: "moonrepo/bun".into(),
                     tag: None,
+                    integrity: None,
                 })
             );
         }
@@ -174,6 +323,7 @@ mod locator {
                     file_prefix: "bun_plugin".into(),
                     repo_slug: "moonrepo/bun-plugin".into(),
                     tag: Some("latest".into()),
+                    integrity: None,
                 })
             );
         }
@@ -186,6 +336,7 @@ mod locator {
                     file_prefix: "bun_plugin".into(),
                     repo_slug: "moonrepo/bun_plugin".into(),
                     tag: Some("v1.2.3".into()),
+                    integrity: None,
                 })
             );
         }
@@ -210,6 +361,7 @@ mod locator {
                     file_prefix: "bun_plugin".into(),
                     package_name: "moonrepo/bun".into(),
                     version: None,
+                    integrity: None,
                 })
             );
         }
@@ -222,6 +374,7 @@ mod locator {
                     file_prefix: "bun_plugin".into(),
                     package_name: "moonrepo/bun-plugin".into(),
                     version: Some("latest".into()),
+                    integrity: None,
                 })
             );
         }
@@ -234,8 +387,102 @@ mod locator {
                     file_prefix: "bun_plugin".into(),
                     package_name: "moonrepo/bun_plugin".into(),
                     version: Some("1.2.3".into()),
+                    integrity: None,
+                })
+            );
+        }
+    }
+
+    mod oci {
+        use super::*;
+
+        #[test]
+        #[should_panic(
+            expected = "OCI locator requires a registry and repository (registry/org/name)."
+        )]
+        fn errors_no_repository() {
+            PluginLocator::try_from("oci:ghcr.io".to_string()).unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "OCI locator digest must be a SHA256 digest (@sha256:...).")]
+        fn errors_invalid_digest() {
+            PluginLocator::try_from("oci:ghcr.io/org/plugin@md5:abc".to_string()).unwrap();
+        }
+
+        #[test]
+        fn parses_repository() {
+            assert_eq!(
+                PluginLocator::try_from("oci:ghcr.io/org/plugin".to_string()).unwrap(),
+                PluginLocator::Oci(OciLocator {
+                    registry: "ghcr.io".into(),
+                    repository: "org/plugin".into(),
+                    tag: None,
+                    digest: None,
+                    integrity: None,
+                })
+            );
+        }
+
+        #[test]
+        fn parses_tag() {
+            assert_eq!(
+                PluginLocator::try_from("oci:ghcr.io/org/plugin:v1.2.3".to_string()).unwrap(),
+                PluginLocator::Oci(OciLocator {
+                    registry: "ghcr.io".into(),
+                    repository: "org/plugin".into(),
+                    tag: Some("v1.2.3".into()),
+                    digest: None,
+                    integrity: None,
+                })
+            );
+        }
+
+        #[test]
+        fn parses_digest() {
+            assert_eq!(
+                PluginLocator::try_from("oci:ghcr.io/org/plugin@sha256:abc123".to_string())
+                    .unwrap(),
+                PluginLocator::Oci(OciLocator {
+                    registry: "ghcr.io".into(),
+                    repository: "org/plugin".into(),
+                    tag: None,
+                    digest: Some("sha256:abc123".into()),
+                    integrity: None,
+                })
+            );
+        }
+
+        #[test]
+        fn parses_registry_port() {
+            assert_eq!(
+                PluginLocator::try_from("oci:localhost:5000/org/plugin:latest".to_string())
+                    .unwrap(),
+                PluginLocator::Oci(OciLocator {
+                    registry: "localhost:5000".into(),
+                    repository: "org/plugin".into(),
+                    tag: Some("latest".into()),
+                    digest: None,
+                    integrity: None,
                 })
             );
         }
+
+        #[test]
+        fn displays_correctly() {
+            for value in [
+                "oci:ghcr.io/org/plugin",
+                "oci:ghcr.io/org/plugin:v1.2.3",
+                "oci:ghcr.io/org/plugin@sha256:abc123",
+                "oci:localhost:5000/org/plugin:latest",
+            ] {
+                assert_eq!(
+                    PluginLocator::try_from(value.to_string())
+                        .unwrap()
+                        .to_string(),
+                    value
+                );
+            }
+        }
     }
 }
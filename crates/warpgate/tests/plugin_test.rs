@@ -0,0 +1,242 @@
+use extism::{manifest::Wasm, Manifest};
+use std::path::PathBuf;
+use std::time::Duration;
+use warpgate::{Id, PluginContainer};
+
+// A module that exports a `spin` function that loops forever
+fn create_spin_manifest() -> Manifest {
+    Manifest::new([Wasm::file(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/__fixtures__/limits/spin.wasm"),
+    )])
+}
+
+// A module that exports a `grow` function that grows memory by 512 pages
+fn create_grow_manifest(max_pages: u32) -> Manifest {
+    let mut manifest = Manifest::new([Wasm::file(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/__fixtures__/limits/grow.wasm"),
+    )]);
+    manifest.memory.max_pages = Some(max_pages);
+    manifest
+}
+
+mod plugin_container {
+    use super::*;
+
+    #[test]
+    fn errors_when_call_times_out() {
+        let plugin = PluginContainer::new_without_functions(
+            Id::raw("spin"),
+            create_spin_manifest().with_timeout(Duration::from_millis(100)),
+        )
+        .unwrap();
+
+        let error = plugin.call("spin", "").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Plugin spin timed out after 100ms while calling function spin."
+        );
+    }
+
+    #[test]
+    fn errors_when_memory_limit_exceeded() {
+        let plugin =
+            PluginContainer::new_without_functions(Id::raw("grow"), create_grow_manifest(100))
+                .unwrap();
+
+        let error = plugin.call("grow", "").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Plugin grow exceeded the memory limit of 100 pages while calling function grow."
+        );
+    }
+
+    #[test]
+    fn errors_when_input_exceeds_memory_limit() {
+        let plugin =
+            PluginContainer::new_without_functions(Id::raw("grow"), create_grow_manifest(100))
+                .unwrap();
+
+        let error = plugin.call("grow", vec![0; 200 * 65536]).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Plugin grow exceeded the memory limit of 100 pages while calling function grow."
+        );
+    }
+
+    #[test]
+    fn checks_exports_without_creating_instance() {
+        let plugin = PluginContainer::new_without_functions(
+            Id::raw("unlinked"),
+            Manifest::new([Wasm::file(
+                PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                    .join("tests/__fixtures__/exports/unlinked.wasm"),
+            )]),
+        )
+        .unwrap();
+
+        // The module imports a missing function, so this would fail if instantiated
+        assert!(plugin.has_func("exported").unwrap());
+        assert!(!plugin.has_func("memory").unwrap());
+        assert!(!plugin.has_func("unknown").unwrap());
+        assert!(plugin.call("exported", "").is_err());
+    }
+
+    #[test]
+    fn grows_memory_within_limit() {
+        let plugin =
+            PluginContainer::new_without_functions(Id::raw("grow"), create_grow_manifest(1000))
+                .unwrap();
+
+        plugin.call("grow", "").unwrap();
+    }
+}
+
+mod persistent_cache {
+    use super::*;
+    use serde_json::Value;
+    use sha2::{Digest, Sha256};
+    use starbase_sandbox::create_empty_sandbox;
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::time::UNIX_EPOCH;
+
+    fn create_plugin() -> PluginContainer<'static> {
+        PluginContainer::new_without_functions(Id::raw("spin"), create_spin_manifest()).unwrap()
+    }
+
+    fn get_fingerprint() -> String {
+        let path =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/__fixtures__/limits/spin.wasm");
+        let modified = fs::metadata(&path)
+            .unwrap()
+            .modified()
+            .unwrap()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+
+        let mut sha = Sha256::new();
+        sha.update("1.2.3");
+        sha.update(path.to_string_lossy().as_bytes());
+        sha.update(modified.to_le_bytes());
+
+        format!("{:x}", sha.finalize())
+    }
+
+    fn get_entry_name(func: &str) -> String {
+        let mut sha = Sha256::new();
+        sha.update("{}");
+        sha.update("{}");
+
+        format!("{func}-{:x}.json", sha.finalize())
+    }
+
+    #[test]
+    fn reads_results_from_disk() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            format!("spin/{}/{}", get_fingerprint(), get_entry_name("unknown")),
+            r#"{"cached":true}"#,
+        );
+
+        let mut plugin = create_plugin();
+        plugin
+            .enable_persistent_cache(sandbox.path(), "1.2.3", ["unknown"])
+            .unwrap();
+
+        // The function doesn't exist, so this would fail if called
+        let output: Value = plugin
+            .cache_func_with("unknown", BTreeMap::<String, String>::new())
+            .unwrap();
+
+        assert_eq!(output, serde_json::json!({ "cached": true }));
+    }
+
+    #[test]
+    fn doesnt_create_instance_for_cached_results() {
+        let sandbox = create_empty_sandbox();
+
+        let mut sha = Sha256::new();
+        sha.update("1.2.3");
+        sha.update(b"data");
+
+        sandbox.create_file(
+            format!("spin/{:x}/{}", sha.finalize(), get_entry_name("unknown")),
+            r#"{"cached":true}"#,
+        );
+
+        let mut plugin = PluginContainer::new_without_functions(
+            Id::raw("spin"),
+            Manifest::new([Wasm::data(b"data".to_vec())]),
+        )
+        .unwrap();
+
+        plugin
+            .enable_persistent_cache(sandbox.path(), "1.2.3", ["unknown"])
+            .unwrap();
+
+        // The module is invalid, so this would fail if instantiated
+        let output: Value = plugin
+            .cache_func_with("unknown", BTreeMap::<String, String>::new())
+            .unwrap();
+
+        assert_eq!(output, serde_json::json!({ "cached": true }));
+        assert!(plugin.has_func("unknown").is_err());
+    }
+
+    #[test]
+    fn ignores_functions_not_marked() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            format!("spin/{}/{}", get_fingerprint(), get_entry_name("unknown")),
+            r#"{"cached":true}"#,
+        );
+
+        let mut plugin = create_plugin();
+        plugin
+            .enable_persistent_cache(sandbox.path(), "1.2.3", ["other"])
+            .unwrap();
+
+        let output: miette::Result<Value> =
+            plugin.cache_func_with("unknown", BTreeMap::<String, String>::new());
+
+        assert!(output.is_err());
+    }
+
+    #[test]
+    fn removes_results_for_other_fingerprints() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("spin/old/unknown-123.json", "{}");
+        sandbox.create_file(format!("spin/{}/unknown-123.json", get_fingerprint()), "{}");
+
+        let mut plugin = create_plugin();
+        plugin
+            .enable_persistent_cache(sandbox.path(), "1.2.3", ["unknown"])
+            .unwrap();
+
+        assert!(!sandbox.path().join("spin/old").exists());
+        assert!(sandbox
+            .path()
+            .join(format!("spin/{}", get_fingerprint()))
+            .exists());
+    }
+
+    #[test]
+    fn removes_results_when_seed_changes() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(format!("spin/{}/unknown-123.json", get_fingerprint()), "{}");
+
+        let mut plugin = create_plugin();
+        plugin
+            .enable_persistent_cache(sandbox.path(), "4.5.6", ["unknown"])
+            .unwrap();
+
+        assert!(!sandbox
+            .path()
+            .join(format!("spin/{}", get_fingerprint()))
+            .exists());
+    }
+}